@@ -0,0 +1,212 @@
+/**
+ * Completions command - Generate and install shell completion scripts
+ */
+
+import chalk from 'chalk';
+import fs from 'fs-extra';
+import path from 'path';
+import os from 'os';
+import { createStandardHelp, CommandHelpConfig } from '../utils/helpFormatter.js';
+import { displayCommandBanner } from '../utils/banner.js';
+
+type SupportedShell = 'bash' | 'zsh' | 'fish';
+
+const SUPPORTED_SHELLS: SupportedShell[] = ['bash', 'zsh', 'fish'];
+
+/**
+ * Commands offered by the completion scripts. Kept in one place so the
+ * generated scripts stay in sync across shells.
+ */
+const CLI_COMMANDS = [
+  'create', 'analyze', 'update', 'add', 'check', 'clone', 'clean',
+  'cache', 'env', 'doctor', 'upgrade-cli', 'deploy', 'email',
+  'completions', 'help'
+];
+
+/**
+ * Display help for completions command
+ */
+export function showCompletionsHelp(): void {
+  const helpConfig: CommandHelpConfig = {
+    commandName: 'Completions',
+    emoji: '⌨️',
+    description: 'Generate shell completion scripts for the pi CLI.\nPrint to stdout for manual setup, or use --install to write the script to the conventional location for your shell.',
+    usage: [
+      'completions [options]',
+      'completions --shell zsh --install'
+    ],
+    options: [
+      { flag: '--shell <shell>', description: 'Target shell: bash, zsh or fish (auto-detected by default)' },
+      { flag: '--install', description: 'Write the script to the conventional completions directory' },
+      { flag: '--force', description: 'Overwrite an existing completion file when installing' }
+    ],
+    examples: [
+      { command: 'completions', description: 'Print completions for your current shell' },
+      { command: 'completions --shell fish', description: 'Print fish completions' },
+      { command: 'completions --install', description: 'Install completions for your current shell' },
+      { command: 'completions --shell bash --install --force', description: 'Reinstall bash completions' }
+    ],
+    additionalSections: [
+      {
+        title: 'Install Locations',
+        items: [
+          'bash: ~/.local/share/bash-completion/completions/pi',
+          'zsh: ~/.zsh/completions/_pi',
+          'fish: ~/.config/fish/completions/pi.fish'
+        ]
+      }
+    ],
+    tips: [
+      'Existing files are never overwritten without --force',
+      'For zsh, make sure ~/.zsh/completions is in your fpath'
+    ]
+  };
+
+  createStandardHelp(helpConfig);
+}
+
+/**
+ * Detect the user's shell from the SHELL environment variable.
+ */
+function detectShell(): SupportedShell | null {
+  const shellPath = process.env.SHELL || '';
+  const shellName = path.basename(shellPath);
+  return SUPPORTED_SHELLS.includes(shellName as SupportedShell)
+    ? (shellName as SupportedShell)
+    : null;
+}
+
+/**
+ * Generate the completion script for the given shell.
+ */
+export function generateCompletionScript(shell: SupportedShell): string {
+  const commands = CLI_COMMANDS.join(' ');
+
+  switch (shell) {
+    case 'bash':
+      return [
+        '# bash completion for pi (Package Installer CLI)',
+        '_pi_completions() {',
+        '  local cur="${COMP_WORDS[COMP_CWORD]}"',
+        '  if [ "$COMP_CWORD" -eq 1 ]; then',
+        `    COMPREPLY=( $(compgen -W "${commands}" -- "$cur") )`,
+        '  fi',
+        '}',
+        'complete -F _pi_completions pi package-installer',
+        ''
+      ].join('\n');
+    case 'zsh':
+      return [
+        '#compdef pi package-installer',
+        '# zsh completion for pi (Package Installer CLI)',
+        '_pi() {',
+        `  local -a commands=(${commands})`,
+        '  if (( CURRENT == 2 )); then',
+        '    _describe "command" commands',
+        '  fi',
+        '}',
+        '_pi "$@"',
+        ''
+      ].join('\n');
+    case 'fish':
+      return [
+        '# fish completion for pi (Package Installer CLI)',
+        ...CLI_COMMANDS.map(
+          (cmd) => `complete -c pi -n "__fish_use_subcommand" -a ${cmd}`
+        ),
+        ''
+      ].join('\n');
+  }
+}
+
+/**
+ * Conventional install path for the given shell's completion script.
+ */
+export function completionInstallPath(shell: SupportedShell): string {
+  const home = os.homedir();
+  switch (shell) {
+    case 'bash': {
+      const dataHome = process.env.XDG_DATA_HOME || path.join(home, '.local', 'share');
+      return path.join(dataHome, 'bash-completion', 'completions', 'pi');
+    }
+    case 'zsh':
+      return path.join(home, '.zsh', 'completions', '_pi');
+    case 'fish':
+      return path.join(home, '.config', 'fish', 'completions', 'pi.fish');
+  }
+}
+
+/**
+ * Per-shell instructions printed after a successful install.
+ */
+function sourcingInstructions(shell: SupportedShell, installedPath: string): string[] {
+  switch (shell) {
+    case 'bash':
+      return ['Restart your shell, or run:', `  source ${installedPath}`];
+    case 'zsh':
+      return [
+        'Make sure the directory is in your fpath (add to ~/.zshrc before compinit):',
+        `  fpath=(${path.dirname(installedPath)} $fpath)`,
+        'Then restart your shell or run: autoload -Uz compinit && compinit'
+      ];
+    case 'fish':
+      return ['Completions load automatically — start a new fish session to pick them up.'];
+  }
+}
+
+/**
+ * Main completions command function
+ */
+export async function completionsCommand(options: any = {}): Promise<void> {
+  // Show help if help flag is present
+  if (options.help || options['--help'] || options['-h']) {
+    showCompletionsHelp();
+    return;
+  }
+
+  // Resolve the target shell (flag wins over detection)
+  let shell: SupportedShell | null = null;
+  if (options.shell) {
+    const requested = String(options.shell).toLowerCase();
+    if (!SUPPORTED_SHELLS.includes(requested as SupportedShell)) {
+      console.log(chalk.red(`❌ Unsupported shell: ${options.shell}`));
+      console.log(chalk.gray(`💡 Supported shells: ${SUPPORTED_SHELLS.join(', ')}`));
+      process.exit(1);
+    }
+    shell = requested as SupportedShell;
+  } else {
+    shell = detectShell();
+    if (!shell) {
+      console.log(chalk.red('❌ Could not detect your shell from $SHELL'));
+      console.log(chalk.gray('💡 Use --shell <bash|zsh|fish> to pick one explicitly'));
+      process.exit(1);
+    }
+  }
+
+  const script = generateCompletionScript(shell);
+
+  if (!options.install) {
+    // Plain stdout output so it can be piped or eval'd
+    process.stdout.write(script);
+    return;
+  }
+
+  displayCommandBanner('Completions', 'Install shell completion scripts for the pi CLI');
+
+  const installPath = completionInstallPath(shell);
+
+  if (await fs.pathExists(installPath) && !options.force) {
+    console.log(chalk.yellow(`⚠️  Completion file already exists: ${installPath}`));
+    console.log(chalk.gray('💡 Re-run with --force to overwrite it'));
+    process.exit(1);
+  }
+
+  await fs.ensureDir(path.dirname(installPath));
+  await fs.writeFile(installPath, script);
+
+  console.log(chalk.green(`✅ Installed ${shell} completions to:`));
+  console.log(`   ${chalk.cyan(installPath)}\n`);
+  for (const line of sourcingInstructions(shell, installPath)) {
+    console.log(chalk.hex('#95afc0')(line));
+  }
+}
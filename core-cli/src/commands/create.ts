@@ -3,6 +3,7 @@
  */
 
 import chalk from 'chalk';
+import fs from 'fs-extra';
 import path from 'path';
 import gradient from 'gradient-string';
 import boxen from 'boxen';
@@ -30,7 +31,12 @@ import {
   ProjectInfo,
   getFrameworkConfig
 } from '../utils/templateResolver.js';
-import { createProjectFromTemplate, installDependenciesForCreate } from '../utils/templateCreator.js';
+import {
+  createProjectFromTemplate,
+  installDependenciesForCreate,
+  previewTemplateMerge,
+  mergeTemplateIntoDirectory
+} from '../utils/templateCreator.js';
 import {
   updateTemplateUsage,
   getCachedTemplateFiles,
@@ -39,6 +45,8 @@ import {
   cacheProjectData
 } from '../utils/cacheManager.js';
 import { CacheManager } from '../utils/cacheUtils.js';
+import { displayCreateSummary } from '../utils/createSummary.js';
+import { writeCiWorkflow } from '../utils/ciWorkflowGenerator.js';
 
 
 /**
@@ -55,7 +63,13 @@ export function showCreateHelp(): void {
     ],
     options: [
       { flag: '--show-cache', description: 'Show cached preferences' },
-      { flag: '--clear-cache', description: 'Clear cached preferences' }
+      { flag: '--clear-cache', description: 'Clear cached preferences' },
+      { flag: '--json', description: 'Print the post-create summary as JSON for tooling' },
+      { flag: '--quiet', description: 'Suppress the post-create summary' },
+      { flag: '--into <dir>', description: 'Merge the template into an existing directory' },
+      { flag: '--dry-run', description: 'Preview the merge without writing files (with --into)' },
+      { flag: '--diff', description: 'Show unified diffs for existing files (with --dry-run --into)' },
+      { flag: '--init-ci', description: 'Generate a GitHub Actions workflow tailored to the template' }
     ],
     examples: [
       { command: 'create my-awesome-app', description: 'Create with specific name' },
@@ -106,6 +120,14 @@ export function showCreateHelp(): void {
 export async function createProject(providedName?: string, options?: any): Promise<void> {
   const startTime = Date.now();
   const cacheManager = new CacheManager();
+  // Captured before the framework `options` variable shadows the parameter
+  const outputOptions = { json: !!options?.json, quiet: !!options?.quiet };
+  const mergeOptions = {
+    into: options?.into ? String(options.into) : undefined,
+    dryRun: !!options?.dryRun,
+    diff: !!options?.diff
+  };
+  const initCi = !!options?.initCi;
 
   // Check for special flags
   if (providedName === '--help' || providedName === '-h' || options?.help || options?.['--help'] || options?.['-h']) {
@@ -192,6 +214,47 @@ export async function createProject(providedName?: string, options?: any): Promi
       return;
     }
 
+    // Step 9.5: Merge-into-existing-directory workflow (--into)
+    if (mergeOptions.into) {
+      const targetDir = path.resolve(process.cwd(), mergeOptions.into);
+      if (!await fs.pathExists(targetDir)) {
+        console.log(chalk.red(`❌ Target directory does not exist: ${targetDir}`));
+        return;
+      }
+
+      if (mergeOptions.dryRun) {
+        const entries = await previewTemplateMerge(templatePath, targetDir, projectName);
+        console.log(chalk.hex('#00d2d3')(`\n🔍 DRY RUN - merging into ${chalk.bold(targetDir)}:\n`));
+        for (const entry of entries) {
+          if (entry.status === 'add') {
+            console.log(`  ${chalk.green('A')} ${entry.relativePath}`);
+          } else if (entry.status === 'modify') {
+            console.log(`  ${chalk.yellow('M')} ${entry.relativePath}`);
+            if (mergeOptions.diff && entry.diff) {
+              for (const line of entry.diff.split('\n')) {
+                const color = line.startsWith('+') ? chalk.green
+                  : line.startsWith('-') ? chalk.red
+                  : line.startsWith('@@') ? chalk.cyan
+                  : chalk.gray;
+                console.log(`    ${color(line)}`);
+              }
+            }
+          }
+        }
+        console.log(chalk.hex('#95afc0')('\nNo files were written.'));
+        return;
+      }
+
+      const { written, skipped } = await mergeTemplateIntoDirectory(templatePath, targetDir, projectName);
+      console.log(chalk.green(`\n✅ Merged template into ${targetDir}`));
+      console.log(`   ${chalk.gray('Written:')} ${written.length} file(s)`);
+      if (skipped.length > 0) {
+        console.log(`   ${chalk.gray('Skipped (already exist):')} ${chalk.yellow(skipped.join(', '))}`);
+        console.log(chalk.hex('#95afc0')('💡 Use --dry-run --diff to preview how skipped files differ'));
+      }
+      return;
+    }
+
     // Step 10: Create project from template
     const projectPath = await createProjectFromTemplate({
       projectName,
@@ -205,6 +268,16 @@ export async function createProject(providedName?: string, options?: any): Promi
     // Step 11: Install dependencies
     await installDependenciesForCreate(projectPath);
 
+    // Step 11.2: Generate a CI workflow if requested (--init-ci)
+    if (initCi) {
+      const workflowPath = await writeCiWorkflow(projectPath, selectedFramework, selectedLanguage);
+      if (workflowPath) {
+        console.log(chalk.green(`✅ Generated CI workflow: ${chalk.cyan(path.relative(projectPath, workflowPath))}`));
+      } else {
+        console.log(chalk.yellow('⚠️  .github/workflows/ci.yml already exists, leaving it untouched'));
+      }
+    }
+
     // Step 11.5: Cache template usage and project data
     try {
       await updateTemplateUsage(
@@ -277,18 +350,30 @@ export async function createProject(providedName?: string, options?: any): Promi
     });
 
     // Success message
-    console.log(chalk.hex('#00d2d3')('\n🎉 Project created successfully!\n'));
-    console.log(chalk.white('📦 Project Details:'));
-    console.log(`   ${chalk.gray('Path:')} ${chalk.cyan(projectPath)}`);
-    console.log(`   ${chalk.gray('Framework:')} ${chalk.green(selectedFramework)}`);
-    console.log(`   ${chalk.gray('Language:')} ${chalk.blue(selectedLanguage)}`);
-    if (templateName) {
-      console.log(`   ${chalk.gray('Template:')} ${chalk.yellow(templateName)}`);
-    }
-    if (selectedFeatures.length > 0) {
-      console.log(`   ${chalk.gray('Features:')} ${chalk.magenta(selectedFeatures.join(', '))}`);
+    if (!outputOptions.json && !outputOptions.quiet) {
+      console.log(chalk.hex('#00d2d3')('\n🎉 Project created successfully!\n'));
+      console.log(chalk.white('📦 Project Details:'));
+      console.log(`   ${chalk.gray('Path:')} ${chalk.cyan(projectPath)}`);
+      console.log(`   ${chalk.gray('Framework:')} ${chalk.green(selectedFramework)}`);
+      console.log(`   ${chalk.gray('Language:')} ${chalk.blue(selectedLanguage)}`);
+      if (templateName) {
+        console.log(`   ${chalk.gray('Template:')} ${chalk.yellow(templateName)}`);
+      }
+      if (selectedFeatures.length > 0) {
+        console.log(`   ${chalk.gray('Features:')} ${chalk.magenta(selectedFeatures.join(', '))}`);
+      }
     }
-    console.log(`\n${chalk.hex('#95afc0')('Navigate to your project:')} ${chalk.bold(providedName === '.' ? 'Already in project directory!' : `cd ${projectName}`)}`);
+
+    // Next-steps summary derived from template metadata
+    displayCreateSummary({
+      projectName,
+      projectPath,
+      framework: selectedFramework,
+      language: selectedLanguage,
+      templateName,
+      features: selectedFeatures,
+      inCurrentDirectory: providedName === '.'
+    }, outputOptions);
 
   } catch (error) {
     // Track command failure
@@ -0,0 +1,155 @@
+/**
+ * Self-uninstall command - Cleanly remove CLI caches, config and bundles
+ */
+
+import chalk from 'chalk';
+import fs from 'fs-extra';
+import path from 'path';
+import os from 'os';
+import inquirer from 'inquirer';
+import { createStandardHelp, CommandHelpConfig } from '../utils/helpFormatter.js';
+import { displayCommandBanner } from '../utils/banner.js';
+
+/**
+ * Display help for self-uninstall command
+ */
+export function showSelfUninstallHelp(): void {
+  const helpConfig: CommandHelpConfig = {
+    commandName: 'Self-Uninstall',
+    emoji: '🗑️',
+    description: 'Cleanly remove everything the CLI has written to your machine:\ncaches, configuration, history and any downloaded standalone bundle.\nUser projects are never touched.',
+    usage: [
+      'self-uninstall [options]'
+    ],
+    options: [
+      { flag: '--yes', description: 'Skip the confirmation prompt' },
+      { flag: '--dry-run', description: 'Show what would be removed without deleting anything' }
+    ],
+    examples: [
+      { command: 'self-uninstall', description: 'Interactive removal with confirmation' },
+      { command: 'self-uninstall --yes', description: 'Remove without prompting' },
+      { command: 'self-uninstall --dry-run', description: 'Preview what would be removed' }
+    ],
+    additionalSections: [
+      {
+        title: 'What Gets Removed',
+        items: [
+          'Cache and config: ~/.package-installer-cli',
+          'Downloaded standalone bundle (bundle-standalone next to the binary)'
+        ]
+      }
+    ],
+    tips: [
+      'The binary itself cannot always delete its own executable — removal instructions are printed instead',
+      'Use pi cache to manage caches without uninstalling'
+    ]
+  };
+
+  createStandardHelp(helpConfig);
+}
+
+/**
+ * Directories the CLI owns. Only these are ever considered for removal —
+ * never user projects or the current working directory.
+ */
+export function getOwnedDirectories(): string[] {
+  const owned = [path.join(os.homedir(), '.package-installer-cli')];
+
+  // Standalone bundle shipped next to the wrapper binary, if we were
+  // launched through one
+  const bundleDir = path.join(path.dirname(process.execPath), 'bundle-standalone');
+  if (path.dirname(bundleDir) !== process.cwd()) {
+    owned.push(bundleDir);
+  }
+
+  return owned;
+}
+
+/**
+ * Remove the given directories, returning the paths actually deleted.
+ * Exported separately from the command so it can be exercised against a
+ * temporary cache/config layout.
+ */
+export async function removeOwnedDirectories(directories: string[], dryRun: boolean): Promise<string[]> {
+  const removed: string[] = [];
+  for (const dir of directories) {
+    if (!await fs.pathExists(dir)) continue;
+    if (!dryRun) {
+      await fs.remove(dir);
+    }
+    removed.push(dir);
+  }
+  return removed;
+}
+
+/**
+ * Main self-uninstall command function
+ */
+export async function selfUninstallCommand(options: any = {}): Promise<void> {
+  // Show help if help flag is present
+  if (options.help || options['--help'] || options['-h']) {
+    showSelfUninstallHelp();
+    return;
+  }
+
+  displayCommandBanner('Self-Uninstall', 'Remove CLI caches, configuration and downloaded bundles');
+
+  const targets = getOwnedDirectories();
+  const existing: string[] = [];
+  for (const dir of targets) {
+    if (await fs.pathExists(dir)) existing.push(dir);
+  }
+
+  if (existing.length === 0) {
+    console.log(chalk.green('✅ Nothing to remove — no caches, config or bundles found.'));
+    printBinaryRemovalInstructions();
+    return;
+  }
+
+  console.log(chalk.white('The following will be removed:'));
+  for (const dir of existing) {
+    console.log(`   ${chalk.red('✗')} ${chalk.cyan(dir)}`);
+  }
+  console.log(chalk.hex('#95afc0')('\nUser projects are not touched.\n'));
+
+  const isDryRun = options['dryRun'] || options['dry-run'];
+  if (isDryRun) {
+    console.log(chalk.cyan('🔍 DRY RUN - nothing was deleted.'));
+    return;
+  }
+
+  if (!options.yes) {
+    const { confirmed } = await inquirer.prompt([
+      {
+        type: 'confirm',
+        name: 'confirmed',
+        message: 'Remove all of the above?',
+        default: false
+      }
+    ]);
+    if (!confirmed) {
+      console.log(chalk.yellow('\n❌ Uninstall cancelled. Nothing was removed.'));
+      return;
+    }
+  }
+
+  const removed = await removeOwnedDirectories(existing, false);
+
+  console.log(chalk.green('\n✅ Removed:'));
+  for (const dir of removed) {
+    console.log(`   ${chalk.cyan(dir)}`);
+  }
+
+  printBinaryRemovalInstructions();
+}
+
+/**
+ * A running process can't reliably delete its own executable (especially
+ * on Windows), so finish by telling the user how to remove the binary.
+ */
+function printBinaryRemovalInstructions(): void {
+  console.log(chalk.white('\n📋 To remove the CLI binary itself:'));
+  console.log(chalk.hex('#00d2d3')('   npm uninstall -g @0xshariq/package-installer'));
+  console.log(chalk.hex('#95afc0')('   (or delete the pi wrapper binary from your PATH, e.g.'));
+  console.log(chalk.hex('#95afc0')(`    ${process.execPath})`));
+}
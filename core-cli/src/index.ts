@@ -23,6 +23,8 @@ import { updateCommand, showUpdateHelp } from './commands/update.js';
 import { analyzeCommand, showAnalyzeHelp } from './commands/analyze.js';
 import { deployCommand, showDeployHelp } from './commands/deploy.js';
 import { cleanCommand, showCleanHelp } from './commands/clean.js';
+import { completionsCommand, showCompletionsHelp } from './commands/completions.js';
+import { selfUninstallCommand, showSelfUninstallHelp } from './commands/self-uninstall.js';
 import { cacheCommand, showCacheHelp } from './commands/cache.js';
 import { environmentCommand, showEnvironmentHelp } from './commands/env.js';
 import { doctorCommand, showDoctorHelp } from './commands/doctor.js';
@@ -163,16 +165,22 @@ program
   .command('create')
   .description(chalk.hex('#10ac84')('🚀 Create a new project from templates'))
   .argument('[project-name]', chalk.hex('#95afc0')('Project name (will prompt if not provided)'))
+  .option('--json', chalk.hex('#95afc0')('Print the post-create summary as JSON for tooling'))
+  .option('--quiet', chalk.hex('#95afc0')('Suppress the post-create summary'))
+  .option('--into <dir>', chalk.hex('#95afc0')('Merge the template into an existing directory'))
+  .option('--dry-run', chalk.hex('#95afc0')('Preview the merge without writing files (with --into)'))
+  .option('--diff', chalk.hex('#95afc0')('Show unified diffs for existing files (with --dry-run --into)'))
+  .option('--init-ci', chalk.hex('#95afc0')('Generate a GitHub Actions workflow tailored to the template'))
   .configureHelp({
     helpWidth: 120,
   })
   .on('--help', () => {
     showCreateHelp();
   })
-  .action(async (projectName: string | undefined) => {
+  .action(async (projectName: string | undefined, cmdOptions: any) => {
     try {
       displayCommandBanner('create', 'Create a new project from templates');
-      await createProject(projectName);
+      await createProject(projectName, cmdOptions);
     } catch (error) {
       handleCommandError('create project', error as Error);
     }
@@ -390,6 +398,43 @@ program
     }
   });
 
+// COMPLETIONS COMMAND - Shell completion scripts
+program
+  .command('completions')
+  .description(chalk.hex('#00d2d3')('⌨️  Generate or install shell completion scripts'))
+  .option('--shell <shell>', 'Target shell: bash, zsh or fish (auto-detected by default)')
+  .option('--install', 'Write the script to the conventional completions directory')
+  .option('--force', 'Overwrite an existing completion file when installing')
+  .option('-h, --help', 'Show help for completions command')
+  .on('--help', () => {
+    showCompletionsHelp();
+  })
+  .action(async (options) => {
+    try {
+      await completionsCommand(options);
+    } catch (error) {
+      handleCommandError('completions', error as Error);
+    }
+  });
+
+// SELF-UNINSTALL COMMAND - Clean removal of caches, config and bundles
+program
+  .command('self-uninstall')
+  .description(chalk.hex('#ff6b6b')('🗑️  Remove CLI caches, configuration and downloaded bundles'))
+  .option('--yes', 'Skip the confirmation prompt')
+  .option('--dry-run', 'Show what would be removed without deleting anything')
+  .option('-h, --help', 'Show help for self-uninstall command')
+  .on('--help', () => {
+    showSelfUninstallHelp();
+  })
+  .action(async (options) => {
+    try {
+      await selfUninstallCommand(options);
+    } catch (error) {
+      handleCommandError('self-uninstall', error as Error);
+    }
+  });
+
 // ENVIRONMENT COMMAND - Environment analysis
 program
   .command('env')
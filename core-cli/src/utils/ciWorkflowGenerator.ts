@@ -0,0 +1,163 @@
+/**
+ * CI Workflow Generator - GitHub Actions workflows tailored per template
+ *
+ * The workflow content is assembled from per-language metadata (setup
+ * action, build/test commands) instead of hard-coded YAML, so new
+ * templates automatically get an appropriate pipeline.
+ */
+
+import fs from 'fs-extra';
+import path from 'path';
+
+interface CiSetupStep {
+  name: string;
+  uses: string;
+  with?: Record<string, string>;
+}
+
+export interface CiMetadata {
+  /** Toolchain setup action for the language */
+  setup?: CiSetupStep;
+  /** Commands run in order as individual workflow steps */
+  commands: { name: string; run: string }[];
+}
+
+/**
+ * Per-language CI metadata. Commands here drive the generated workflow
+ * steps one-to-one.
+ */
+const LANGUAGE_CI_INFO: Record<string, CiMetadata> = {
+  rust: {
+    setup: {
+      name: 'Install Rust toolchain',
+      uses: 'dtolnay/rust-toolchain@stable',
+      with: { components: 'rustfmt, clippy' }
+    },
+    commands: [
+      { name: 'Check formatting', run: 'cargo fmt --check' },
+      { name: 'Lint', run: 'cargo clippy -- -D warnings' },
+      { name: 'Test', run: 'cargo test' }
+    ]
+  },
+  javascript: {
+    setup: {
+      name: 'Setup Node.js',
+      uses: 'actions/setup-node@v4',
+      with: { 'node-version': '20' }
+    },
+    commands: [
+      { name: 'Install dependencies', run: 'npm install' },
+      { name: 'Test', run: 'npm test' }
+    ]
+  },
+  typescript: {
+    setup: {
+      name: 'Setup Node.js',
+      uses: 'actions/setup-node@v4',
+      with: { 'node-version': '20' }
+    },
+    commands: [
+      { name: 'Install dependencies', run: 'npm install' },
+      { name: 'Build', run: 'npm run build --if-present' },
+      { name: 'Test', run: 'npm test' }
+    ]
+  },
+  python: {
+    setup: {
+      name: 'Setup Python',
+      uses: 'actions/setup-python@v5',
+      with: { 'python-version': '3.12' }
+    },
+    commands: [
+      { name: 'Install dependencies', run: 'pip install -r requirements.txt' },
+      { name: 'Test', run: 'python -m pytest' }
+    ]
+  },
+  go: {
+    setup: {
+      name: 'Setup Go',
+      uses: 'actions/setup-go@v5',
+      with: { 'go-version': 'stable' }
+    },
+    commands: [
+      { name: 'Build', run: 'go build ./...' },
+      { name: 'Test', run: 'go test ./...' }
+    ]
+  },
+  ruby: {
+    setup: {
+      name: 'Setup Ruby',
+      uses: 'ruby/setup-ruby@v1',
+      with: { 'ruby-version': '3.3', 'bundler-cache': 'true' }
+    },
+    commands: [
+      { name: 'Test', run: 'bundle exec rake test' }
+    ]
+  }
+};
+
+/**
+ * Resolve CI metadata for a template's language (falls back to the
+ * Node.js pipeline, which matches most templates in the catalog).
+ */
+export function getCiMetadata(language: string): CiMetadata {
+  return LANGUAGE_CI_INFO[language.toLowerCase()] || LANGUAGE_CI_INFO.javascript;
+}
+
+/**
+ * Render a GitHub Actions workflow for the given template metadata.
+ */
+export function generateCiWorkflow(framework: string, language: string): string {
+  const metadata = getCiMetadata(language);
+  const lines: string[] = [
+    `name: CI`,
+    '',
+    'on:',
+    '  push:',
+    '    branches: [main]',
+    '  pull_request:',
+    '',
+    'jobs:',
+    '  ci:',
+    '    runs-on: ubuntu-latest',
+    '    steps:',
+    '      - uses: actions/checkout@v4'
+  ];
+
+  if (metadata.setup) {
+    lines.push(`      - name: ${metadata.setup.name}`);
+    lines.push(`        uses: ${metadata.setup.uses}`);
+    if (metadata.setup.with) {
+      lines.push('        with:');
+      for (const [key, value] of Object.entries(metadata.setup.with)) {
+        lines.push(`          ${key}: ${value}`);
+      }
+    }
+  }
+
+  for (const command of metadata.commands) {
+    lines.push(`      - name: ${command.name}`);
+    lines.push(`        run: ${command.run}`);
+  }
+
+  lines.push('');
+  return lines.join('\n');
+}
+
+/**
+ * Write `.github/workflows/ci.yml` into the project, returning the path
+ * written (or null if one already exists, which is left untouched).
+ */
+export async function writeCiWorkflow(
+  projectPath: string,
+  framework: string,
+  language: string
+): Promise<string | null> {
+  const workflowPath = path.join(projectPath, '.github', 'workflows', 'ci.yml');
+  if (await fs.pathExists(workflowPath)) {
+    return null;
+  }
+  await fs.ensureDir(path.dirname(workflowPath));
+  await fs.writeFile(workflowPath, generateCiWorkflow(framework, language));
+  return workflowPath;
+}
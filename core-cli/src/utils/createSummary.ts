@@ -0,0 +1,161 @@
+/**
+ * Create Summary - Next-steps report printed after successful scaffolding
+ *
+ * Derives the run command, dev-server URL and recommended follow-ups from
+ * template metadata so the guidance is accurate per framework instead of a
+ * generic "cd and run" message.
+ */
+
+import chalk from 'chalk';
+import boxen from 'boxen';
+
+export interface CreateSummaryInfo {
+  projectName: string;
+  projectPath: string;
+  framework: string;
+  language: string;
+  templateName?: string;
+  features?: string[];
+  inCurrentDirectory?: boolean;
+}
+
+export interface CreateSummaryOptions {
+  /** Emit the summary as JSON for tooling instead of the formatted report */
+  json?: boolean;
+  /** Suppress the summary entirely */
+  quiet?: boolean;
+}
+
+interface FrameworkRunInfo {
+  /** Command that starts the project (dev server where applicable) */
+  runCommand: string;
+  /** Default dev-server port, if the template serves HTTP */
+  port?: number;
+  /** Command that installs dependencies, if not handled by the CLI */
+  installCommand?: string;
+  /** Extra per-framework recommendations (env vars, migrations, ...) */
+  followUps?: string[];
+}
+
+/**
+ * Per-framework run metadata. Node-based frameworks are resolved by
+ * language at lookup time; everything else is static.
+ */
+const FRAMEWORK_RUN_INFO: Record<string, FrameworkRunInfo> = {
+  reactjs: { runCommand: 'npm run dev', port: 5173, installCommand: 'npm install' },
+  nextjs: { runCommand: 'npm run dev', port: 3000, installCommand: 'npm install' },
+  vuejs: { runCommand: 'npm run dev', port: 5173, installCommand: 'npm install' },
+  angularjs: { runCommand: 'npm start', port: 4200, installCommand: 'npm install' },
+  expressjs: {
+    runCommand: 'npm run dev',
+    port: 3000,
+    installCommand: 'npm install',
+    followUps: ['Copy .env.example to .env and fill in your secrets']
+  },
+  nestjs: { runCommand: 'npm run start:dev', port: 3000, installCommand: 'npm install' },
+  remixjs: { runCommand: 'npm run dev', port: 3000, installCommand: 'npm install' },
+  // Rust entries are keyed by the framework names in the template
+  // catalog; ports match what each template binds by default
+  'actix-web': { runCommand: 'cargo run', port: 8080 },
+  axum: { runCommand: 'cargo run', port: 3000 },
+  'axum-sqlite': { runCommand: 'cargo run', port: 3000 },
+  gotham: { runCommand: 'cargo run', port: 7878 },
+  poem: { runCommand: 'cargo run', port: 4000 },
+  rocket: { runCommand: 'cargo run', port: 8000 },
+  rouille: { runCommand: 'cargo run', port: 8000 },
+  // The catalog spells this framework with three l's
+  rouillle: { runCommand: 'cargo run', port: 8000 },
+  salvo: { runCommand: 'cargo run', port: 7878 },
+  thruster: { runCommand: 'cargo run', port: 4321 },
+  tide: { runCommand: 'cargo run', port: 8080 },
+  warp: { runCommand: 'cargo run', port: 3030 },
+  django: {
+    runCommand: 'python manage.py runserver',
+    port: 8000,
+    installCommand: 'pip install -r requirements.txt',
+    followUps: ['Run python manage.py migrate before first start']
+  },
+  flask: {
+    runCommand: 'flask run',
+    port: 5000,
+    installCommand: 'pip install -r requirements.txt'
+  },
+  go: { runCommand: 'go run .', port: 8080 },
+  ruby: { runCommand: 'bundle exec ruby app.rb', port: 4567, installCommand: 'bundle install' }
+};
+
+/** Fallback run commands keyed by project language */
+const LANGUAGE_RUN_INFO: Record<string, FrameworkRunInfo> = {
+  javascript: { runCommand: 'npm run dev', installCommand: 'npm install' },
+  typescript: { runCommand: 'npm run dev', installCommand: 'npm install' },
+  rust: { runCommand: 'cargo run' },
+  python: { runCommand: 'python main.py', installCommand: 'pip install -r requirements.txt' },
+  go: { runCommand: 'go run .' },
+  ruby: { runCommand: 'ruby main.rb', installCommand: 'bundle install' }
+};
+
+/**
+ * Resolve run metadata for a framework/language pair, preferring the
+ * framework entry and falling back to language defaults.
+ */
+export function getRunInfo(framework: string, language: string): FrameworkRunInfo {
+  return (
+    FRAMEWORK_RUN_INFO[framework.toLowerCase()] ||
+    LANGUAGE_RUN_INFO[language.toLowerCase()] || { runCommand: 'npm run dev' }
+  );
+}
+
+/**
+ * Print the post-create summary report (or JSON with --json; nothing with
+ * --quiet).
+ */
+export function displayCreateSummary(
+  info: CreateSummaryInfo,
+  options: CreateSummaryOptions = {}
+): void {
+  if (options.quiet) return;
+
+  const runInfo = getRunInfo(info.framework, info.language);
+  const serverUrl = runInfo.port ? `http://localhost:${runInfo.port}` : undefined;
+
+  if (options.json) {
+    console.log(JSON.stringify({
+      name: info.projectName,
+      path: info.projectPath,
+      framework: info.framework,
+      language: info.language,
+      template: info.templateName || null,
+      features: info.features || [],
+      runCommand: runInfo.runCommand,
+      installCommand: runInfo.installCommand || null,
+      serverUrl: serverUrl || null,
+      followUps: runInfo.followUps || []
+    }, null, 2));
+    return;
+  }
+
+  const lines: string[] = [];
+  lines.push(chalk.hex('#00d2d3')('🎯 Next Steps'));
+  lines.push('');
+  if (!info.inCurrentDirectory) {
+    lines.push(`  ${chalk.gray('1.')} ${chalk.bold(`cd ${info.projectName}`)}`);
+  }
+  if (runInfo.installCommand) {
+    lines.push(`  ${chalk.gray(info.inCurrentDirectory ? '1.' : '2.')} ${chalk.bold(runInfo.installCommand)} ${chalk.hex('#95afc0')('(if dependencies were not installed)')}`);
+  }
+  lines.push(`  ${chalk.gray('▶')} ${chalk.bold(runInfo.runCommand)}`);
+  if (serverUrl) {
+    lines.push(`  ${chalk.gray('🌐')} Server: ${chalk.cyan(serverUrl)}`);
+  }
+  for (const followUp of runInfo.followUps || []) {
+    lines.push(`  ${chalk.yellow('💡')} ${chalk.hex('#95afc0')(followUp)}`);
+  }
+  lines.push('');
+  lines.push(`  ${chalk.gray('Path:')} ${chalk.cyan(info.projectPath)}`);
+
+  console.log('\n' + boxen(lines.join('\n'), {
+    padding: 1,
+    borderStyle: 'round',
+    borderColor: 'cyan'
+  }));
+}
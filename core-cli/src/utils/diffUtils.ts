@@ -0,0 +1,127 @@
+/**
+ * Diff utilities - Minimal unified diff rendering for dry-run previews
+ *
+ * Kept dependency-free: a classic LCS line diff is plenty for previewing
+ * template merges, and avoids pulling a diff library into the CLI bundle.
+ */
+
+interface DiffOp {
+  kind: 'context' | 'add' | 'remove';
+  line: string;
+}
+
+/**
+ * Compute line-level diff operations between two strings using a
+ * longest-common-subsequence table.
+ */
+function diffLines(oldText: string, newText: string): DiffOp[] {
+  const oldLines = oldText.split('\n');
+  const newLines = newText.split('\n');
+  const n = oldLines.length;
+  const m = newLines.length;
+
+  // LCS length table
+  const lcs: number[][] = Array.from({ length: n + 1 }, () => new Array(m + 1).fill(0));
+  for (let i = n - 1; i >= 0; i--) {
+    for (let j = m - 1; j >= 0; j--) {
+      lcs[i][j] = oldLines[i] === newLines[j]
+        ? lcs[i + 1][j + 1] + 1
+        : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+    }
+  }
+
+  const ops: DiffOp[] = [];
+  let i = 0;
+  let j = 0;
+  while (i < n && j < m) {
+    if (oldLines[i] === newLines[j]) {
+      ops.push({ kind: 'context', line: oldLines[i] });
+      i++;
+      j++;
+    } else if (lcs[i + 1][j] >= lcs[i][j + 1]) {
+      ops.push({ kind: 'remove', line: oldLines[i] });
+      i++;
+    } else {
+      ops.push({ kind: 'add', line: newLines[j] });
+      j++;
+    }
+  }
+  while (i < n) ops.push({ kind: 'remove', line: oldLines[i++] });
+  while (j < m) ops.push({ kind: 'add', line: newLines[j++] });
+  return ops;
+}
+
+/**
+ * Render a unified diff (with `---`/`+++` headers and `@@` hunks) between
+ * the existing and the would-be generated content of a file. Returns an
+ * empty string when the contents are identical.
+ */
+export function createUnifiedDiff(
+  oldText: string,
+  newText: string,
+  filePath: string,
+  contextLines: number = 3
+): string {
+  if (oldText === newText) return '';
+
+  const ops = diffLines(oldText, newText);
+
+  // Collect hunks of consecutive changes padded with context lines
+  interface Hunk { start: number; end: number }
+  const hunks: Hunk[] = [];
+  for (let k = 0; k < ops.length; k++) {
+    if (ops[k].kind === 'context') continue;
+    const start = Math.max(0, k - contextLines);
+    const end = Math.min(ops.length - 1, k + contextLines);
+    const last = hunks[hunks.length - 1];
+    if (last && start <= last.end + 1) {
+      last.end = end;
+    } else {
+      hunks.push({ start, end });
+    }
+  }
+
+  const out: string[] = [`--- a/${filePath}`, `+++ b/${filePath}`];
+  let oldLineNo = 1;
+  let newLineNo = 1;
+  let opIndex = 0;
+
+  for (const hunk of hunks) {
+    // Advance line counters over ops before the hunk
+    for (; opIndex < hunk.start; opIndex++) {
+      const op = ops[opIndex];
+      if (op.kind !== 'add') oldLineNo++;
+      if (op.kind !== 'remove') newLineNo++;
+    }
+
+    const hunkOldStart = oldLineNo;
+    const hunkNewStart = newLineNo;
+    let oldCount = 0;
+    let newCount = 0;
+    const body: string[] = [];
+
+    for (; opIndex <= hunk.end; opIndex++) {
+      const op = ops[opIndex];
+      if (op.kind === 'context') {
+        body.push(` ${op.line}`);
+        oldCount++;
+        newCount++;
+        oldLineNo++;
+        newLineNo++;
+      } else if (op.kind === 'remove') {
+        body.push(`-${op.line}`);
+        oldCount++;
+        oldLineNo++;
+      } else {
+        body.push(`+${op.line}`);
+        newCount++;
+        newLineNo++;
+      }
+    }
+
+    out.push(`@@ -${hunkOldStart},${oldCount} +${hunkNewStart},${newCount} @@`);
+    out.push(...body);
+  }
+
+  return out.join('\n');
+}
@@ -518,6 +518,164 @@ async function initializeGitRepositoryForCreate(projectPath: string): Promise<vo
     }
 }
 
+export interface MergePreviewEntry {
+    relativePath: string;
+    status: 'add' | 'modify' | 'unchanged';
+    diff?: string;
+}
+
+/**
+ * Resolve the effective template root, flattening a single top-level
+ * directory the same way the copy routines do.
+ */
+async function resolveTemplateRoot(templatePath: string): Promise<string> {
+    const templateContents = await fs.readdir(templatePath);
+    const nonSystemFiles = templateContents.filter(item =>
+        !item.startsWith('.') &&
+        item !== 'node_modules' &&
+        item !== 'dist' &&
+        item !== 'build'
+    );
+
+    if (nonSystemFiles.length === 1) {
+        const singleItemPath = path.join(templatePath, nonSystemFiles[0]);
+        if ((await fs.stat(singleItemPath)).isDirectory()) {
+            return singleItemPath;
+        }
+    }
+    return templatePath;
+}
+
+/**
+ * Collect every template file that would be copied, honoring the same
+ * filters as the copy routines (skips node_modules, .git, build outputs
+ * and system files).
+ */
+async function collectTemplateFiles(templateRoot: string): Promise<string[]> {
+    const files: string[] = [];
+
+    async function walk(dirPath: string): Promise<void> {
+        const items = await fs.readdir(dirPath, { withFileTypes: true });
+        for (const item of items) {
+            const fullPath = path.join(dirPath, item.name);
+            if (item.isDirectory()) {
+                if (['node_modules', '.git', 'dist', 'build', '.next'].includes(item.name)) continue;
+                await walk(fullPath);
+            } else {
+                if (['.DS_Store', 'Thumbs.db', '.gitkeep'].includes(item.name)) continue;
+                files.push(path.relative(templateRoot, fullPath));
+            }
+        }
+    }
+
+    await walk(templateRoot);
+    return files.sort();
+}
+
+/**
+ * Apply the same placeholder substitutions the post-copy processing step
+ * performs, so previews show the content that would actually be written.
+ */
+function substituteTemplateContent(relativePath: string, content: string, projectName: string): string {
+    const fileName = path.basename(relativePath);
+    const kebabName = projectName.toLowerCase().replace(/\s+/g, '-');
+
+    if (fileName === 'package.json') {
+        try {
+            const packageJson = JSON.parse(content);
+            packageJson.name = projectName;
+            if (packageJson.description === 'Template project' ||
+                packageJson.description === 'Generated from template') {
+                packageJson.description = `${projectName} - Generated by Package Installer CLI`;
+            }
+            return JSON.stringify(packageJson, null, 2) + '\n';
+        } catch {
+            return content;
+        }
+    }
+
+    if (fileName === 'README.md') {
+        return content
+            .replace(/{{PROJECT_NAME}}/g, projectName)
+            .replace(/{{project-name}}/g, projectName.toLowerCase())
+            .replace(/Template Project/g, projectName)
+            .replace(/template-project/g, kebabName);
+    }
+
+    if (fileName === 'docker-compose.yml' || fileName === '.env.example' || fileName === 'env.example') {
+        return content
+            .replace(/{{PROJECT_NAME}}/g, projectName)
+            .replace(/template-project/g, kebabName);
+    }
+
+    return content;
+}
+
+/**
+ * Preview merging a template into an existing directory: marks files that
+ * do not exist in the target as additions, and renders a unified diff of
+ * the would-be generated content for files that do.
+ */
+export async function previewTemplateMerge(
+    templatePath: string,
+    targetDir: string,
+    projectName: string
+): Promise<MergePreviewEntry[]> {
+    const { createUnifiedDiff } = await import('./diffUtils.js');
+    const templateRoot = await resolveTemplateRoot(templatePath);
+    const relativePaths = await collectTemplateFiles(templateRoot);
+    const entries: MergePreviewEntry[] = [];
+
+    for (const relativePath of relativePaths) {
+        const templateContent = await fs.readFile(path.join(templateRoot, relativePath), 'utf-8');
+        const generated = substituteTemplateContent(relativePath, templateContent, projectName);
+        const targetPath = path.join(targetDir, relativePath);
+
+        if (!await fs.pathExists(targetPath)) {
+            entries.push({ relativePath, status: 'add' });
+            continue;
+        }
+
+        const existing = await fs.readFile(targetPath, 'utf-8');
+        const diff = createUnifiedDiff(existing, generated, relativePath);
+        entries.push(diff
+            ? { relativePath, status: 'modify', diff }
+            : { relativePath, status: 'unchanged' });
+    }
+
+    return entries;
+}
+
+/**
+ * Merge a template into an existing directory, writing new files and
+ * leaving conflicting ones untouched. Returns the written and skipped
+ * paths so callers can report exactly what happened.
+ */
+export async function mergeTemplateIntoDirectory(
+    templatePath: string,
+    targetDir: string,
+    projectName: string
+): Promise<{ written: string[]; skipped: string[] }> {
+    const templateRoot = await resolveTemplateRoot(templatePath);
+    const relativePaths = await collectTemplateFiles(templateRoot);
+    const written: string[] = [];
+    const skipped: string[] = [];
+
+    for (const relativePath of relativePaths) {
+        const targetPath = path.join(targetDir, relativePath);
+        if (await fs.pathExists(targetPath)) {
+            skipped.push(relativePath);
+            continue;
+        }
+        const templateContent = await fs.readFile(path.join(templateRoot, relativePath), 'utf-8');
+        await fs.ensureDir(path.dirname(targetPath));
+        await fs.writeFile(targetPath, substituteTemplateContent(relativePath, templateContent, projectName));
+        written.push(relativePath);
+    }
+
+    return { written, skipped };
+}
+
 /**
  * Validate template structure before creation
  */
@@ -1,5 +1,22 @@
+use bevy::asset::{LoadState, UntypedHandle};
 use bevy::prelude::*;
 
+/// Assets that must finish loading before the main scene is spawned.
+/// Replace these with your real glTF/audio files under `assets/`; missing
+/// files are logged and skipped so the starter still reaches `Running`.
+const ASSET_PATHS: &[&str] = &["models/scene.glb", "sounds/startup.ogg"];
+
+/// Top-level app flow: stay in `Loading` until every tracked asset is
+/// ready, then transition to `Running` and spawn the scene. Using assets
+/// before they are loaded is a very common source of beginner bugs —
+/// this state machine makes it impossible.
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+enum AppState {
+    #[default]
+    Loading,
+    Running,
+}
+
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
@@ -11,35 +28,114 @@ fn main() {
             }),
             ..Default::default()
         }))
-        .add_systems(Startup, setup)
-        .add_systems(Update, rotate_cube)
+        .init_state::<AppState>()
+        .add_systems(OnEnter(AppState::Loading), (start_loading, spawn_loading_screen))
+        .add_systems(Update, check_loading.run_if(in_state(AppState::Loading)))
+        .add_systems(OnExit(AppState::Loading), despawn_loading_screen)
+        .add_systems(OnEnter(AppState::Running), setup)
+        .add_systems(Update, rotate_cube.run_if(in_state(AppState::Running)))
         .run();
 }
 
+/// Handles for every asset the loading screen is waiting on.
+#[derive(Resource, Default)]
+struct AssetsLoading(Vec<UntypedHandle>);
+
+/// Marker for entities that only live during the loading screen.
+#[derive(Component)]
+struct LoadingScreen;
+
+/// Marker for the progress text so `check_loading` can update it.
+#[derive(Component)]
+struct LoadingText;
+
 #[derive(Component)]
 struct Rotates;
 
+fn start_loading(mut commands: Commands, server: Res<AssetServer>) {
+    let mut loading = AssetsLoading::default();
+    for path in ASSET_PATHS {
+        loading.0.push(server.load_untyped(*path).untyped());
+    }
+    commands.insert_resource(loading);
+}
+
+fn spawn_loading_screen(mut commands: Commands) {
+    commands.spawn((Camera2d, LoadingScreen));
+    commands.spawn((
+        Text::new(format!("Loading assets... 0/{}", ASSET_PATHS.len())),
+        TextFont {
+            font_size: 32.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(35.0),
+            top: Val::Percent(45.0),
+            ..Default::default()
+        },
+        LoadingScreen,
+        LoadingText,
+    ));
+}
+
+/// Polls `AssetServer::get_load_state` for every tracked handle and shows
+/// progress; once everything is accounted for, moves on to `Running`.
+/// Assets that fail to load (e.g. the placeholder paths above) are logged
+/// and counted as done so a missing file cannot hang the loading screen.
+fn check_loading(
+    server: Res<AssetServer>,
+    loading: Res<AssetsLoading>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut progress: Query<&mut Text, With<LoadingText>>,
+) {
+    let mut done = 0;
+    for handle in &loading.0 {
+        match server.get_load_state(handle.id()) {
+            Some(LoadState::Loaded) => done += 1,
+            Some(LoadState::Failed(_)) => {
+                warn!("asset failed to load; continuing without it");
+                done += 1;
+            }
+            _ => {}
+        }
+    }
+
+    for mut text in &mut progress {
+        text.0 = format!("Loading assets... {}/{}", done, loading.0.len());
+    }
+
+    if done == loading.0.len() {
+        next_state.set(AppState::Running);
+    }
+}
+
+fn despawn_loading_screen(mut commands: Commands, screen: Query<Entity, With<LoadingScreen>>) {
+    for entity in &screen {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     // Camera
-    commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0.0, 1.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ..Default::default()
-    });
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
 
     // Light
-    commands.spawn(PointLightBundle {
-        point_light: PointLight {
+    commands.spawn((
+        PointLight {
             intensity: 2000.0,
             shadows_enabled: true,
             ..Default::default()
         },
-        transform: Transform::from_xyz(3.0, 5.0, 3.0),
-        ..Default::default()
-    });
+        Transform::from_xyz(3.0, 5.0, 3.0),
+    ));
 
     // Cube
     let cube_mesh = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
@@ -49,12 +145,9 @@ fn setup(
     });
 
     commands.spawn((
-        PbrBundle {
-            mesh: cube_mesh,
-            material: cube_material,
-            transform: Transform::from_xyz(0.0, 0.5, 0.0),
-            ..Default::default()
-        },
+        Mesh3d(cube_mesh),
+        MeshMaterial3d(cube_material),
+        Transform::from_xyz(0.0, 0.5, 0.0),
         Rotates,
     ));
 
@@ -62,17 +155,16 @@ fn setup(
     let plane_mesh = meshes.add(Plane3d::default().mesh().size(10.0, 10.0));
     let plane_mat = materials.add(Color::srgb(0.1, 0.1, 0.15));
 
-    commands.spawn(PbrBundle {
-        mesh: plane_mesh,
-        material: plane_mat,
-        transform: Transform::from_xyz(0.0, 0.0, 0.0),
-        ..Default::default()
-    });
+    commands.spawn((
+        Mesh3d(plane_mesh),
+        MeshMaterial3d(plane_mat),
+        Transform::from_xyz(0.0, 0.0, 0.0),
+    ));
 }
 
 fn rotate_cube(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
     for mut transform in &mut query {
-        transform.rotate_y(1.0 * time.delta_seconds());
-        transform.rotate_x(0.5 * time.delta_seconds());
+        transform.rotate_y(1.0 * time.delta_secs());
+        transform.rotate_x(0.5 * time.delta_secs());
     }
 }
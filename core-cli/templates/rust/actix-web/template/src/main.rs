@@ -1,11 +1,51 @@
-use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder};
+use actix_files::Files;
+use actix_web::dev::{Service, ServiceRequest};
+use actix_web::{
+    get, middleware::Logger, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder,
+};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 #[derive(Serialize, Deserialize)]
 struct Message {
     text: String,
 }
 
+/// State shared by every worker: a request counter and the process
+/// start time. Extend it with connection pools, caches, etc.
+struct AppState {
+    requests_served: AtomicU64,
+    started: Instant,
+}
+
+impl AppState {
+    fn new() -> AppState {
+        AppState {
+            requests_served: AtomicU64::new(0),
+            started: Instant::now(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Stats {
+    requests_served: u64,
+    uptime_seconds: u64,
+}
+
+/// Counting middleware for `wrap_fn`: bumps the shared counter before
+/// the request reaches its handler.
+fn counted<S>(req: ServiceRequest, srv: &S) -> S::Future
+where
+    S: Service<ServiceRequest>,
+{
+    if let Some(state) = req.app_data::<web::Data<AppState>>() {
+        state.requests_served.fetch_add(1, Ordering::Relaxed);
+    }
+    srv.call(req)
+}
+
 #[get("/")]
 async fn index() -> impl Responder {
     HttpResponse::Ok().body("Hello from Actix Web!")
@@ -16,10 +56,186 @@ async fn echo(msg: web::Json<Message>) -> impl Responder {
     HttpResponse::Ok().json(&msg.0)
 }
 
+#[get("/hello/{name}")]
+async fn hello(name: web::Path<String>) -> impl Responder {
+    HttpResponse::Ok().body(format!("Hello, {}!", name))
+}
+
+/// JSON body for unknown routes, instead of actix's empty 404.
+async fn not_found(req: HttpRequest) -> impl Responder {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "not_found",
+        "path": req.path(),
+    }))
+}
+
+#[get("/stats")]
+async fn stats(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(Stats {
+        requests_served: state.requests_served.load(Ordering::Relaxed),
+        uptime_seconds: state.started.elapsed().as_secs(),
+    })
+}
+
+/// The bind address from `HOST`/`PORT`, defaulting to `127.0.0.1:8080`.
+fn bind_address() -> String {
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
+    format!("{}:{}", host, port)
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    HttpServer::new(|| App::new().service(index).service(echo))
-        .bind("127.0.0.1:8080")?
-        .run()
-        .await
+    // `RUST_LOG` controls verbosity; request lines log at info
+    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+
+    // Construct the shared state ONCE, outside the factory closure:
+    // the closure runs per worker, and state built inside it would be
+    // a separate copy per worker — a classic pitfall
+    let state = web::Data::new(AppState::new());
+
+    let addr = bind_address();
+    log::info!("listening on http://{}", addr);
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .wrap(Logger::default())
+            .wrap_fn(counted)
+            .service(index)
+            .service(echo)
+            .service(hello)
+            .service(stats)
+            // The frontend bundle; drop your build output into static/
+            .service(Files::new("/static", "static").index_file("index.html"))
+            .default_service(web::route().to(not_found))
+    })
+    .bind(&addr)
+    .map_err(|e| std::io::Error::new(e.kind(), format!("cannot bind {addr}: {e}")))?
+    .run()
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test};
+
+    /// The app under test, with a fresh shared state per call.
+    macro_rules! test_app {
+        () => {
+            test::init_service(
+                App::new()
+                    .app_data(web::Data::new(AppState::new()))
+                    .wrap_fn(counted)
+                    .service(index)
+                    .service(echo)
+                    .service(hello)
+                    .service(stats)
+                    .service(Files::new("/static", "static").index_file("index.html"))
+                    .default_service(web::route().to(not_found)),
+            )
+            .await
+        };
+    }
+
+    #[actix_web::test]
+    async fn index_responds_with_200() {
+        let app = test_app!();
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn echo_round_trips_json() {
+        let app = test_app!();
+        let request = test::TestRequest::post()
+            .uri("/echo")
+            .set_json(Message {
+                text: "hi".to_string(),
+            })
+            .to_request();
+        let message: Message = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(message.text, "hi");
+    }
+
+    #[actix_web::test]
+    async fn invalid_json_is_a_400() {
+        let app = test_app!();
+        let request = test::TestRequest::post()
+            .uri("/echo")
+            .insert_header(("content-type", "application/json"))
+            .set_payload(r#"{"text":"#)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn the_static_index_is_served() {
+        let app = test_app!();
+        let response = test::call_service(
+            &app,
+            test::TestRequest::get().uri("/static/").to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = test::read_body(response).await;
+        assert!(String::from_utf8_lossy(&body).contains("Hello from static/"));
+    }
+
+    #[actix_web::test]
+    async fn unknown_routes_get_a_json_404_naming_the_path() {
+        let app = test_app!();
+        let response = test::call_service(
+            &app,
+            test::TestRequest::get().uri("/no/such/route").to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = test::read_body(response).await;
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed["error"], "not_found");
+        assert_eq!(parsed["path"], "/no/such/route");
+    }
+
+    #[actix_web::test]
+    async fn the_path_parameter_route_greets_by_name() {
+        let app = test_app!();
+        let body = test::call_and_read_body(
+            &app,
+            test::TestRequest::get().uri("/hello/world").to_request(),
+        )
+        .await;
+        assert_eq!(body, "Hello, world!");
+    }
+
+    #[actix_web::test]
+    async fn the_counter_increases_across_sequential_requests() {
+        let app = test_app!();
+        for _ in 0..2 {
+            test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+        }
+        // The /stats request itself is the third counted request
+        let body: Stats = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::get().uri("/stats").to_request(),
+        )
+        .await;
+        assert_eq!(body.requests_served, 3);
+    }
+
+    #[actix_web::test]
+    async fn stats_returns_well_formed_json() {
+        let app = test_app!();
+        let body = test::call_and_read_body(
+            &app,
+            test::TestRequest::get().uri("/stats").to_request(),
+        )
+        .await;
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(parsed["requests_served"].is_u64());
+        assert!(parsed["uptime_seconds"].is_u64());
+    }
 }
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS todos (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    text TEXT NOT NULL,
+    done BOOLEAN NOT NULL DEFAULT 0
+);
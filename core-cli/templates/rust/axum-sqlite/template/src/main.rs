@@ -0,0 +1,327 @@
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Json, Path, Request, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::str::FromStr;
+use tokio::net::TcpListener;
+use tower_http::trace::TraceLayer;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Serialize, sqlx::FromRow)]
+struct Todo {
+    id: i64,
+    text: String,
+    done: bool,
+}
+
+#[derive(Deserialize)]
+struct NewTodo {
+    text: String,
+}
+
+/// An application error rendered as a consistent JSON envelope:
+/// `{"error": {"code": ..., "message": ...}}`. Add constructors for
+/// your own failure modes as the API grows.
+struct AppError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl AppError {
+    fn bad_request(status: StatusCode, message: impl Into<String>) -> AppError {
+        AppError {
+            status,
+            code: "bad_request",
+            message: message.into(),
+        }
+    }
+
+    fn not_found(what: &str) -> AppError {
+        AppError {
+            status: StatusCode::NOT_FOUND,
+            code: "not_found",
+            message: format!("{} not found", what),
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> AppError {
+        AppError {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "internal",
+            message: message.into(),
+        }
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> AppError {
+        match error {
+            sqlx::Error::RowNotFound => AppError::not_found("todo"),
+            other => AppError::internal(other.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": { "code": self.code, "message": self.message }
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// `Json` with the default plain-text rejection replaced by the JSON
+/// error envelope, keeping axum's status codes (415 for a missing
+/// content type, 400/422 for broken or mismatched bodies).
+struct AppJson<T>(T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for AppJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(AppJson(value)),
+            Err(rejection) => Err(AppError::bad_request(rejection.status(), rejection.body_text())),
+        }
+    }
+}
+
+async fn create_todo(
+    State(pool): State<SqlitePool>,
+    AppJson(new): AppJson<NewTodo>,
+) -> Result<(StatusCode, Json<Todo>), AppError> {
+    let todo = sqlx::query_as::<_, Todo>(
+        "INSERT INTO todos (text) VALUES (?) RETURNING id, text, done",
+    )
+    .bind(&new.text)
+    .fetch_one(&pool)
+    .await?;
+    Ok((StatusCode::CREATED, Json(todo)))
+}
+
+async fn list_todos(State(pool): State<SqlitePool>) -> Result<Json<Vec<Todo>>, AppError> {
+    let todos = sqlx::query_as::<_, Todo>("SELECT id, text, done FROM todos ORDER BY id")
+        .fetch_all(&pool)
+        .await?;
+    Ok(Json(todos))
+}
+
+async fn get_todo(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> Result<Json<Todo>, AppError> {
+    let todo = sqlx::query_as::<_, Todo>("SELECT id, text, done FROM todos WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("todo"))?;
+    Ok(Json(todo))
+}
+
+async fn delete_todo(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    let deleted = sqlx::query("DELETE FROM todos WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::not_found("todo"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn fallback() -> AppError {
+    AppError::not_found("route")
+}
+
+fn app(pool: SqlitePool) -> Router {
+    Router::new()
+        .route("/todos", post(create_todo).get(list_todos))
+        .route("/todos/:id", get(get_todo))
+        .route("/todos/:id", delete(delete_todo))
+        .fallback(fallback)
+        .layer(TraceLayer::new_for_http())
+        .with_state(pool)
+}
+
+/// Opens the database named by `url` (creating a file database if
+/// needed) and applies the embedded migrations.
+async fn init_db(url: &str) -> Result<SqlitePool, Box<dyn std::error::Error>> {
+    let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+    // Each `sqlite::memory:` connection is its own empty database, so
+    // the in-memory default keeps a single connection alive
+    let pool = SqlitePoolOptions::new()
+        .max_connections(if url.contains(":memory:") { 1 } else { 5 })
+        .connect_with(options)
+        .await?;
+    sqlx::migrate!().run(&pool).await?;
+    Ok(pool)
+}
+
+/// The bind address from `HOST`/`PORT`, defaulting to `127.0.0.1:3000`.
+fn bind_address() -> String {
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
+    format!("{}:{}", host, port)
+}
+
+async fn shutdown_signal() {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("failed to listen for ctrl-c");
+    tracing::info!("shutting down");
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    // `cargo run` needs zero setup: without DATABASE_URL the todos
+    // live in memory and vanish on exit
+    let url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite::memory:".to_string());
+    let pool = match init_db(&url).await {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("Cannot open database {url}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let addr = bind_address();
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Cannot bind {addr}: {e}");
+            std::process::exit(1);
+        }
+    };
+    tracing::info!(
+        "listening on http://{}",
+        listener.local_addr().expect("listener has a local address")
+    );
+
+    axum::serve(listener, app(pool))
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request};
+    use tower::ServiceExt;
+
+    async fn test_app() -> Router {
+        app(init_db("sqlite::memory:").await.unwrap())
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn post_todo(text: &str) -> Request<Body> {
+        Request::post("/todos")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(format!(r#"{{"text":"{}"}}"#, text)))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn the_full_create_list_get_delete_cycle_works() {
+        let app = test_app().await;
+
+        // Create
+        let response = app.clone().oneshot(post_todo("write tests")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created = json_body(response).await;
+        let id = created["id"].as_i64().unwrap();
+        assert_eq!(created["text"], "write tests");
+        assert_eq!(created["done"], false);
+
+        // List
+        let response = app
+            .clone()
+            .oneshot(Request::get("/todos").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let listed = json_body(response).await;
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+
+        // Get
+        let response = app
+            .clone()
+            .oneshot(Request::get(format!("/todos/{id}")).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["id"], id);
+
+        // Delete, then the todo is gone
+        let response = app
+            .clone()
+            .oneshot(Request::delete(format!("/todos/{id}")).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = app
+            .oneshot(Request::get(format!("/todos/{id}")).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn missing_todos_and_routes_get_the_json_404_envelope() {
+        let app = test_app().await;
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/todos/999").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(json_body(response).await["error"]["code"], "not_found");
+
+        let response = app
+            .oneshot(Request::get("/nope").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn a_bad_body_gets_the_json_error_envelope() {
+        let app = test_app().await;
+        let request = Request::post("/todos")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json_body(response).await["error"]["code"], "bad_request");
+    }
+}
@@ -1,37 +1,287 @@
 use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Json, Request},
+    http::{HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
     routing::{get, post},
-    extract::Json,
     Router,
 };
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tower_http::cors::CorsLayer;
+use tower_http::trace::TraceLayer;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 struct Message {
     text: String,
 }
 
+/// An application error rendered as a consistent JSON envelope:
+/// `{"error": {"code": ..., "message": ...}}`. Add constructors for
+/// your own failure modes as the API grows.
+struct AppError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl AppError {
+    fn bad_request(status: StatusCode, message: impl Into<String>) -> AppError {
+        AppError {
+            status,
+            code: "bad_request",
+            message: message.into(),
+        }
+    }
+
+    fn not_found() -> AppError {
+        AppError {
+            status: StatusCode::NOT_FOUND,
+            code: "not_found",
+            message: "no such route".to_string(),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": { "code": self.code, "message": self.message }
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// `Json` with the default plain-text rejection replaced by the JSON
+/// error envelope, keeping axum's status codes (415 for a missing
+/// content type, 400/422 for broken or mismatched bodies).
+struct AppJson<T>(T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for AppJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(AppJson(value)),
+            Err(rejection) => Err(AppError::bad_request(rejection.status(), rejection.body_text())),
+        }
+    }
+}
+
 async fn index() -> &'static str {
     "Hello from Axum!"
 }
 
-async fn echo(Json(msg): Json<Message>) -> Json<Message> {
+async fn echo(AppJson(msg): AppJson<Message>) -> Json<Message> {
     Json(Message {
         text: format!("You said: {}", msg.text),
     })
 }
 
+async fn not_found() -> AppError {
+    AppError::not_found()
+}
+
+/// Attaches an `x-request-id` header to the request (generating one if
+/// the client did not send any) and mirrors it onto the response, so
+/// log lines and clients can correlate.
+async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get("x-request-id")
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&Uuid::new_v4().to_string())
+                .expect("a UUID is a valid header value")
+        });
+    request.headers_mut().insert("x-request-id", id.clone());
+    let mut response = next.run(request).await;
+    response.headers_mut().insert("x-request-id", id);
+    response
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/", get(index))
+        .route("/echo", post(echo))
+        .fallback(not_found)
+        .layer(middleware::from_fn(request_id))
+        // Permissive CORS is convenient in development; for production
+        // restrict it, e.g.:
+        //   CorsLayer::new().allow_origin("https://app.example.com".parse::<HeaderValue>().unwrap())
+        .layer(CorsLayer::permissive())
+        .layer(TraceLayer::new_for_http())
+}
+
+/// The bind address from `HOST`/`PORT`, defaulting to `127.0.0.1:3000`.
+fn bind_address_from(host: Option<&str>, port: Option<&str>) -> String {
+    format!("{}:{}", host.unwrap_or("127.0.0.1"), port.unwrap_or("3000"))
+}
+
+fn bind_address() -> String {
+    let host = std::env::var("HOST").ok();
+    let port = std::env::var("PORT").ok();
+    bind_address_from(host.as_deref(), port.as_deref())
+}
+
+async fn shutdown_signal() {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("failed to listen for ctrl-c");
+    tracing::info!("shutting down");
+}
+
 #[tokio::main]
 async fn main() {
-    let app = Router::new()
-        .route("/", get(index))
-        .route("/echo", post(echo));
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    println!("Running at http://{}", addr);
+    let addr = bind_address();
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Cannot bind {addr}: {e}");
+            std::process::exit(1);
+        }
+    };
+    tracing::info!(
+        "listening on http://{}",
+        listener.local_addr().expect("listener has a local address")
+    );
 
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+    axum::serve(listener, app())
+        .with_graceful_shutdown(shutdown_signal())
         .await
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Method, Request, StatusCode};
+    use tower::ServiceExt;
+
+    async fn error_envelope(response: Response) -> serde_json::Value {
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).expect("error responses are JSON")
+    }
+
+    #[tokio::test]
+    async fn index_responds_with_200() {
+        let response = app()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn every_response_carries_a_request_id() {
+        let response = app()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert!(response.headers().contains_key("x-request-id"));
+
+        // A client-supplied id is echoed back unchanged
+        let request = Request::get("/")
+            .header("x-request-id", "abc-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(
+            response.headers().get("x-request-id").unwrap(),
+            "abc-123"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_custom_port_is_honored() {
+        let addr = bind_address_from(None, Some("0"));
+        let listener = TcpListener::bind(&addr).await.unwrap();
+        let local = listener.local_addr().unwrap();
+        assert_eq!(local.ip().to_string(), "127.0.0.1");
+        assert_ne!(local.port(), 3000, "port 0 must pick an ephemeral port");
+
+        // Defaults stay as documented
+        assert_eq!(bind_address_from(None, None), "127.0.0.1:3000");
+        assert_eq!(bind_address_from(Some("0.0.0.0"), Some("8080")), "0.0.0.0:8080");
+    }
+
+    #[tokio::test]
+    async fn echo_round_trips_json() {
+        let request = Request::post("/echo")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"text":"hi"}"#))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let message: Message = serde_json::from_slice(&body).unwrap();
+        assert_eq!(message.text, "You said: hi");
+    }
+
+    #[tokio::test]
+    async fn a_missing_content_type_gets_the_json_error_envelope() {
+        let response = app()
+            .oneshot(Request::post("/echo").body(Body::from("hi")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let envelope = error_envelope(response).await;
+        assert_eq!(envelope["error"]["code"], "bad_request");
+        assert!(envelope["error"]["message"].is_string());
+    }
+
+    #[tokio::test]
+    async fn truncated_json_gets_the_json_error_envelope() {
+        let request = Request::post("/echo")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"text": "hi"#))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let envelope = error_envelope(response).await;
+        assert_eq!(envelope["error"]["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn unknown_routes_get_a_json_404() {
+        let response = app()
+            .oneshot(Request::get("/nope").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let envelope = error_envelope(response).await;
+        assert_eq!(envelope["error"]["code"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn cors_preflight_is_answered() {
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/echo")
+            .header(header::ORIGIN, "https://app.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert!(response.status().is_success(), "got {}", response.status());
+        assert!(response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+}
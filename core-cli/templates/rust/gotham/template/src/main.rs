@@ -1,20 +1,197 @@
-use gotham::hyper::{Body, Response, StatusCode};
-use gotham::plain::PlainTextResponse;
-use gotham::state::State;
+use futures_util::future::{self, FutureExt};
+use gotham::handler::HandlerFuture;
+use gotham::helpers::http::response::create_response;
+use gotham::hyper::{body, Body, Response, StatusCode};
+use gotham::middleware::Middleware;
+use gotham::pipeline::{new_pipeline, single_pipeline};
 use gotham::router::builder::*;
 use gotham::router::Router;
+use gotham::state::{FromState, State};
+use gotham_derive::{NewMiddleware, StateData};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 
-fn hello(state: State) -> (State, PlainTextResponse<&'static str>) {
-    (state, PlainTextResponse::new("Hello from Gotham!"))
+#[derive(Serialize, Deserialize)]
+struct Message {
+    text: String,
+}
+
+/// Shared counter handed to every request via the pipeline middleware.
+/// `StateData` is what lets handlers borrow it back out of `State`.
+#[derive(Clone, StateData)]
+struct RequestCounter {
+    requests_served: Arc<AtomicU64>,
+    started: Instant,
+}
+
+impl RequestCounter {
+    fn new() -> RequestCounter {
+        RequestCounter {
+            requests_served: Arc::new(AtomicU64::new(0)),
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Pipeline middleware: bumps the counter and puts it into `State` so
+/// any handler downstream can read it.
+#[derive(Clone, NewMiddleware)]
+struct CounterMiddleware {
+    counter: RequestCounter,
+}
+
+impl Middleware for CounterMiddleware {
+    fn call<Chain>(self, mut state: State, chain: Chain) -> Pin<Box<HandlerFuture>>
+    where
+        Chain: FnOnce(State) -> Pin<Box<HandlerFuture>>,
+    {
+        self.counter.requests_served.fetch_add(1, Ordering::Relaxed);
+        state.put(self.counter.clone());
+        chain(state)
+    }
+}
+
+fn hello(state: State) -> (State, &'static str) {
+    (state, "Hello from Gotham!")
+}
+
+/// Reads the request body and echoes the parsed `Message` back, with a
+/// 400 JSON error body when it doesn't deserialize.
+fn echo(mut state: State) -> Pin<Box<HandlerFuture>> {
+    body::to_bytes(Body::take_from(&mut state))
+        .then(|full_body| match full_body {
+            Ok(bytes) => {
+                let response = match serde_json::from_slice::<Message>(&bytes) {
+                    Ok(message) => create_response(
+                        &state,
+                        StatusCode::OK,
+                        mime::APPLICATION_JSON,
+                        serde_json::to_vec(&message).expect("Message serializes"),
+                    ),
+                    Err(e) => create_response(
+                        &state,
+                        StatusCode::BAD_REQUEST,
+                        mime::APPLICATION_JSON,
+                        serde_json::json!({
+                            "error": { "code": "bad_request", "message": e.to_string() }
+                        })
+                        .to_string(),
+                    ),
+                };
+                future::ok((state, response))
+            }
+            Err(e) => future::err((state, e.into())),
+        })
+        .boxed()
+}
+
+fn stats(state: State) -> (State, Response<Body>) {
+    let counter = RequestCounter::borrow_from(&state);
+    let body = serde_json::json!({
+        "requests_served": counter.requests_served.load(Ordering::Relaxed),
+        "uptime_seconds": counter.started.elapsed().as_secs(),
+    })
+    .to_string();
+    let response = create_response(&state, StatusCode::OK, mime::APPLICATION_JSON, body);
+    (state, response)
 }
 
 fn router() -> Router {
-    build_simple_router(|route| {
+    let (chain, pipelines) = single_pipeline(
+        new_pipeline()
+            .add(CounterMiddleware {
+                counter: RequestCounter::new(),
+            })
+            .build(),
+    );
+    build_router(chain, pipelines, |route| {
         route.get("/").to(hello);
+        route.post("/echo").to(echo);
+        route.get("/stats").to(stats);
     })
 }
 
 fn main() {
     println!("Running at http://127.0.0.1:7878");
-    gotham::start("127.0.0.1:7878", router());
+    gotham::start("127.0.0.1:7878", router()).expect("cannot start server");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gotham::test::TestServer;
+
+    #[test]
+    fn index_responds_with_the_greeting() {
+        let test_server = TestServer::new(router()).unwrap();
+        let response = test_server
+            .client()
+            .get("http://localhost/")
+            .perform()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(&response.read_body().unwrap()[..], b"Hello from Gotham!");
+    }
+
+    #[test]
+    fn echo_round_trips_json() {
+        let test_server = TestServer::new(router()).unwrap();
+        let response = test_server
+            .client()
+            .post(
+                "http://localhost/echo",
+                r#"{"text":"hi"}"#,
+                mime::APPLICATION_JSON,
+            )
+            .perform()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let parsed: serde_json::Value =
+            serde_json::from_slice(&response.read_body().unwrap()).unwrap();
+        assert_eq!(parsed["text"], "hi");
+    }
+
+    #[test]
+    fn the_counter_increases_across_sequential_requests() {
+        let test_server = TestServer::new(router()).unwrap();
+        for _ in 0..2 {
+            test_server
+                .client()
+                .get("http://localhost/")
+                .perform()
+                .unwrap();
+        }
+        // The /stats request itself is the third counted request
+        let response = test_server
+            .client()
+            .get("http://localhost/stats")
+            .perform()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let parsed: serde_json::Value =
+            serde_json::from_slice(&response.read_body().unwrap()).unwrap();
+        assert_eq!(parsed["requests_served"], 3);
+        assert!(parsed["uptime_seconds"].is_u64());
+    }
+
+    #[test]
+    fn bad_json_gets_a_400_with_a_json_error_body() {
+        let test_server = TestServer::new(router()).unwrap();
+        let response = test_server
+            .client()
+            .post(
+                "http://localhost/echo",
+                r#"{"text":"#,
+                mime::APPLICATION_JSON,
+            )
+            .perform()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let parsed: serde_json::Value =
+            serde_json::from_slice(&response.read_body().unwrap()).unwrap();
+        assert_eq!(parsed["error"]["code"], "bad_request");
+    }
 }
@@ -1,26 +1,173 @@
-use poem::{get, handler, post, web::Json, Route, Server};
-use serde::{Deserialize, Serialize};
+use poem::http::StatusCode;
+use poem::middleware::{CatchPanic, Cors, Tracing};
+use poem::{listener::TcpListener, Endpoint, EndpointExt, Route, Server};
+use poem_openapi::payload::{Json, PlainText};
+use poem_openapi::{Object, OpenApi, OpenApiService};
+use tracing_subscriber::EnvFilter;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Object)]
 struct Message {
+    /// The validator turns an empty `text` into a structured 400
+    /// before the handler runs.
+    #[oai(validator(min_length = 1))]
     text: String,
 }
 
-#[handler]
-fn index() -> &'static str {
-    "Hello from Poem!"
+struct Api;
+
+#[OpenApi]
+impl Api {
+    /// Say hello.
+    #[oai(path = "/", method = "get")]
+    async fn index(&self) -> PlainText<&'static str> {
+        PlainText("Hello from Poem!")
+    }
+
+    /// Echo a message back.
+    #[oai(path = "/echo", method = "post")]
+    async fn echo(&self, msg: Json<Message>) -> Json<Message> {
+        Json(Message {
+            text: format!("You said: {}", msg.0.text),
+        })
+    }
 }
 
-#[handler]
-fn echo(Json(msg): Json<Message>) -> Json<Message> {
-    Json(Message {
-        text: format!("You said: {}", msg.text),
-    })
+/// A test-only route proving `CatchPanic` turns panics into 500s.
+#[cfg(test)]
+#[poem::handler]
+fn panic_route() -> &'static str {
+    panic!("boom")
+}
+
+/// The routes: the OpenAPI service, its generated spec at `/spec`, and
+/// Swagger UI at `/docs`. Plain poem routes can still be added to the
+/// same `Route` alongside the nested API.
+fn routes() -> Route {
+    let api = OpenApiService::new(Api, "template", env!("CARGO_PKG_VERSION"))
+        .server("http://127.0.0.1:4000");
+    let spec = api.spec_endpoint();
+    let docs = api.swagger_ui();
+    let route = Route::new().at("/spec", spec).nest("/docs", docs).nest("/", api);
+    #[cfg(test)]
+    let route = route.at("/panic", poem::get(panic_route));
+    route
+}
+
+/// The routes wrapped in the middleware stack: request tracing, CORS,
+/// and a panic catcher that answers with a JSON 500 instead of
+/// dropping the connection.
+fn app() -> impl Endpoint {
+    routes()
+        .with(Tracing)
+        // Permissive CORS suits local development; restrict it before
+        // shipping, e.g. Cors::new().allow_origin("https://example.com")
+        .with(Cors::new())
+        .with(CatchPanic::new().with_handler(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                poem::web::Json(serde_json::json!({
+                    "error": { "code": "internal", "message": "something went wrong" }
+                })),
+            )
+        }))
+}
+
+/// The bind address from `HOST`/`PORT`, defaulting to `127.0.0.1:4000`.
+fn bind_address() -> String {
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = std::env::var("PORT").unwrap_or_else(|_| "4000".to_string());
+    format!("{}:{}", host, port)
 }
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
-    let app = Route::new().at("/", get(index)).at("/echo", post(echo));
-    println!("Running at http://127.0.0.1:4000");
-    Server::new("127.0.0.1:4000").run(app).await
+    // `RUST_LOG` controls verbosity; requests trace at info
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let addr = bind_address();
+    println!("Running at http://{} (docs at /docs, spec at /spec)", addr);
+    Server::new(TcpListener::bind(addr)).run(app()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::test::TestClient;
+
+    #[tokio::test]
+    async fn index_responds_with_the_greeting() {
+        let client = TestClient::new(app());
+        let response = client.get("/").send().await;
+        response.assert_status_is_ok();
+        response.assert_text("Hello from Poem!").await;
+    }
+
+    #[tokio::test]
+    async fn echo_round_trips_json() {
+        let client = TestClient::new(app());
+        let response = client
+            .post("/echo")
+            .body_json(&serde_json::json!({ "text": "hi" }))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        let json = response.json().await;
+        json.value()
+            .object()
+            .get("text")
+            .assert_string("You said: hi");
+    }
+
+    #[tokio::test]
+    async fn an_empty_text_fails_validation_with_a_400() {
+        let client = TestClient::new(app());
+        let response = client
+            .post("/echo")
+            .body_json(&serde_json::json!({ "text": "" }))
+            .send()
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn a_preflight_request_gets_the_cors_headers() {
+        let client = TestClient::new(app());
+        let response = client
+            .options("/echo")
+            .header("origin", "https://example.com")
+            .header("access-control-request-method", "POST")
+            .send()
+            .await;
+        response.assert_header("access-control-allow-origin", "https://example.com");
+    }
+
+    #[tokio::test]
+    async fn a_panicking_handler_becomes_a_json_500() {
+        let client = TestClient::new(app());
+        let response = client.get("/panic").send().await;
+        response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        let json = response.json().await;
+        json.value()
+            .object()
+            .get("error")
+            .object()
+            .get("code")
+            .assert_string("internal");
+    }
+
+    #[tokio::test]
+    async fn the_spec_documents_both_paths() {
+        let client = TestClient::new(app());
+        let response = client.get("/spec").send().await;
+        response.assert_status_is_ok();
+        let json = response.json().await;
+        let paths = json.value().object().get("paths").object();
+        // `get` panics with a clear message if the path is missing
+        paths.get("/");
+        paths.get("/echo");
+    }
 }
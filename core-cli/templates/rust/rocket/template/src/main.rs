@@ -1,24 +1,432 @@
 #[macro_use] extern crate rocket;
+use std::sync::{Arc, Mutex};
+
+use rocket::data::Data;
+use rocket::fairing::{AdHoc, Fairing, Info, Kind};
+use rocket::figment::Figment;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
 use rocket::serde::{json::Json, Deserialize, Serialize};
+use rocket::Request;
+use serde_json::Value;
 
 #[derive(Serialize, Deserialize)]
 struct Message {
     text: String,
 }
 
+/// The JSON error envelope every failure shares:
+/// `{"error": {"code": ..., "message": ...}}`.
+#[derive(Serialize)]
+struct ErrorBody {
+    error: ErrorDetail,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail {
+    code: &'static str,
+    message: String,
+}
+
+impl ErrorBody {
+    fn new(code: &'static str, message: impl Into<String>) -> ErrorBody {
+        ErrorBody {
+            error: ErrorDetail {
+                code,
+                message: message.into(),
+            },
+        }
+    }
+}
+
+/// Logs the JSON request body for `/echo`, replacing the values of any
+/// configured sensitive fields with `"[REDACTED]"` before the line is
+/// written. The field list comes from the `log_redact_fields` config key
+/// (e.g. in `Rocket.toml`) and defaults to `password`, `token`, `secret`.
+struct BodyLogger {
+    redact_fields: Vec<String>,
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl BodyLogger {
+    fn new(redact_fields: Vec<String>) -> Self {
+        BodyLogger {
+            redact_fields,
+            lines: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Handle to the captured log lines, used by the test suite.
+    #[cfg(test)]
+    fn lines_handle(&self) -> Arc<Mutex<Vec<String>>> {
+        Arc::clone(&self.lines)
+    }
+}
+
+/// Recursively replaces the value of every sensitive field with
+/// `"[REDACTED]"`, descending into nested objects and arrays.
+fn redact(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                if fields.iter().any(|f| f.eq_ignore_ascii_case(key)) {
+                    *entry = Value::String("[REDACTED]".into());
+                } else {
+                    redact(entry, fields);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                redact(item, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for BodyLogger {
+    fn info(&self) -> Info {
+        Info {
+            name: "JSON Body Logger",
+            kind: Kind::Request,
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, data: &mut Data<'_>) {
+        if req.uri().path() != "/echo" {
+            return;
+        }
+        // Peeking buffers the bytes, so the `Json` data guard still sees
+        // the full body afterwards.
+        let bytes = data.peek(512).await;
+        let Ok(mut body) = serde_json::from_slice::<Value>(bytes) else {
+            return;
+        };
+        redact(&mut body, &self.redact_fields);
+        let line = format!("{} {} {}", req.method(), req.uri(), body);
+        println!("{}", line);
+        self.lines.lock().unwrap().push(line);
+    }
+}
+
 #[get("/")]
 fn index() -> &'static str {
     "Hello from Rocket!"
 }
 
-#[post("/echo", format = "json", data = "<msg>")]
-fn echo(msg: Json<Message>) -> Json<Message> {
-    Json(Message {
-        text: format!("You said: {}", msg.text),
+#[derive(Serialize)]
+struct Health {
+    status: &'static str,
+    version: &'static str,
+}
+
+/// The crate version baked in at compile time, surfaced by `/health` so
+/// load balancers (and humans) can see which build is serving traffic.
+fn crate_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+#[get("/health")]
+fn health() -> Json<Health> {
+    Json(Health {
+        status: "ok",
+        version: crate_version(),
     })
 }
 
+#[post("/echo", format = "json", data = "<msg>")]
+fn echo(msg: Json<Message>) -> Result<Json<Message>, (Status, Json<ErrorBody>)> {
+    let text = msg.text.trim();
+    if text.is_empty() {
+        return Err((
+            Status::UnprocessableEntity,
+            Json(ErrorBody::new("unprocessable_entity", "text must not be empty")),
+        ));
+    }
+    Ok(Json(Message {
+        text: format!("You said: {}", text),
+    }))
+}
+
+/// The expected API key, resolved once at build time from the
+/// `api_key` config entry (Rocket.toml / `ROCKET_API_KEY`) or the plain
+/// `API_KEY` env var. `None` disables the guard so the template still
+/// runs out of the box.
+struct ApiKeyConfig(Option<String>);
+
+/// Request guard for routes under `/admin`: the `x-api-key` header must
+/// match the configured key. A missing header forwards as 401, a wrong
+/// key as 403, so the registered catchers render the JSON envelope.
+struct ApiKey;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiKey {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let expected = req
+            .rocket()
+            .state::<ApiKeyConfig>()
+            .and_then(|config| config.0.as_deref());
+        let Some(expected) = expected else {
+            // No key configured: the guard is disabled
+            return Outcome::Success(ApiKey);
+        };
+        match req.headers().get_one("x-api-key") {
+            None => Outcome::Error((Status::Unauthorized, ())),
+            Some(key) if key == expected => Outcome::Success(ApiKey),
+            Some(_) => Outcome::Error((Status::Forbidden, ())),
+        }
+    }
+}
+
+#[get("/admin/ping")]
+fn admin_ping(_key: ApiKey) -> &'static str {
+    "pong"
+}
+
+// Catchers replace Rocket's default HTML error pages with the shared
+// JSON envelope; Rocket keeps the status code they are registered for.
+
+#[catch(404)]
+fn not_found(req: &Request) -> Json<ErrorBody> {
+    Json(ErrorBody::new(
+        "not_found",
+        format!("{} is not a known path", req.uri()),
+    ))
+}
+
+#[catch(401)]
+fn unauthorized() -> Json<ErrorBody> {
+    Json(ErrorBody::new(
+        "unauthorized",
+        "missing x-api-key header",
+    ))
+}
+
+#[catch(403)]
+fn forbidden() -> Json<ErrorBody> {
+    Json(ErrorBody::new("forbidden", "invalid api key"))
+}
+
+#[catch(422)]
+fn unprocessable() -> Json<ErrorBody> {
+    Json(ErrorBody::new(
+        "unprocessable_entity",
+        "the request body could not be parsed",
+    ))
+}
+
+#[catch(500)]
+fn internal_error() -> Json<ErrorBody> {
+    Json(ErrorBody::new("internal", "something went wrong"))
+}
+
+/// Assembles the rocket from an explicit figment, so tests can layer
+/// their own overrides on top of the usual configuration.
+fn build_rocket(figment: Figment) -> rocket::Rocket<rocket::Build> {
+    let rocket = rocket::custom(figment);
+    let redact_fields: Vec<String> = rocket
+        .figment()
+        .extract_inner("log_redact_fields")
+        .unwrap_or_else(|_| vec!["password".into(), "token".into(), "secret".into()]);
+    // The plain API_KEY env var wins over config, so deployments can
+    // inject the secret without touching Rocket.toml
+    let api_key: Option<String> = std::env::var("API_KEY")
+        .ok()
+        .filter(|key| !key.is_empty())
+        .or_else(|| rocket.figment().extract_inner("api_key").ok());
+    if api_key.is_none() {
+        eprintln!("warning: no api_key configured; /admin routes accept every request");
+    }
+    rocket
+        .manage(ApiKeyConfig(api_key))
+        .attach(BodyLogger::new(redact_fields))
+        .attach(AdHoc::on_response("Request Logger", |req, res| {
+            Box::pin(async move {
+                println!("{} {} -> {}", req.method(), req.uri().path(), res.status());
+            })
+        }))
+        .attach(AdHoc::on_liftoff("Bind Banner", |rocket| {
+            Box::pin(async move {
+                let config = rocket.config();
+                println!(
+                    "{} profile, listening on {}:{}",
+                    config.profile, config.address, config.port
+                );
+            })
+        }))
+        .mount("/", routes![index, health, echo, admin_ping])
+        .register(
+            "/",
+            catchers![not_found, unauthorized, forbidden, unprocessable, internal_error],
+        )
+}
+
 #[launch]
 fn rocket() -> _ {
-    rocket::build().mount("/", routes![index, echo])
+    // `Config::figment()` already layers Rocket.toml's profiles under
+    // `ROCKET_*` env vars (`ROCKET_ADDRESS`, `ROCKET_PORT`, ...), so
+    // deployments override the file without touching it.
+    build_rocket(rocket::Config::figment())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        Client::tracked(rocket()).expect("valid rocket instance")
+    }
+
+    fn error_code(body: Option<String>) -> String {
+        let parsed: Value = serde_json::from_str(&body.expect("a response body")).unwrap();
+        parsed["error"]["code"].as_str().expect("an error code").to_string()
+    }
+
+    #[test]
+    fn index_responds_with_the_greeting() {
+        let client = client();
+        let response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "Hello from Rocket!");
+    }
+
+    #[test]
+    fn health_responds_even_with_a_port_override() {
+        // Port 0 exercises the figment layering without binding a socket
+        let figment = rocket::Config::figment().merge(("port", 0));
+        let client = Client::tracked(build_rocket(figment)).expect("valid rocket instance");
+        let response = client.get("/health").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let parsed: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+        assert_eq!(parsed["status"], "ok");
+        assert_eq!(parsed["version"], crate_version());
+    }
+
+    #[test]
+    fn the_reported_version_is_the_crate_version() {
+        assert_eq!(crate_version(), env!("CARGO_PKG_VERSION"));
+        assert!(crate_version().contains('.'));
+    }
+
+    #[test]
+    fn echo_round_trips_json() {
+        let client = client();
+        let response = client
+            .post("/echo")
+            .header(ContentType::JSON)
+            .body(r#"{"text":"hi"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let message: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+        assert_eq!(message["text"], "You said: hi");
+    }
+
+    #[test]
+    fn broken_json_gets_the_422_envelope() {
+        let client = client();
+        // A type mismatch, not a syntax error: Rocket answers truncated
+        // JSON with a plain 400 before the catcher sees it
+        let response = client
+            .post("/echo")
+            .header(ContentType::JSON)
+            .body(r#"{"text": 42}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        assert_eq!(error_code(response.into_string()), "unprocessable_entity");
+    }
+
+    #[test]
+    fn a_blank_text_is_rejected_through_the_same_envelope() {
+        let client = client();
+        let response = client
+            .post("/echo")
+            .header(ContentType::JSON)
+            .body(r#"{"text":"   "}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        assert_eq!(error_code(response.into_string()), "unprocessable_entity");
+    }
+
+    #[test]
+    fn unknown_paths_get_the_json_404_envelope() {
+        let client = client();
+        let response = client.get("/no/such/path").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let body = response.into_string().unwrap();
+        let parsed: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["error"]["code"], "not_found");
+        assert!(parsed["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("/no/such/path"));
+    }
+
+    fn keyed_client() -> Client {
+        let figment = rocket::Config::figment().merge(("api_key", "sekrit"));
+        Client::tracked(build_rocket(figment)).expect("valid rocket instance")
+    }
+
+    #[test]
+    fn the_right_api_key_opens_the_admin_route() {
+        let client = keyed_client();
+        let response = client
+            .get("/admin/ping")
+            .header(rocket::http::Header::new("x-api-key", "sekrit"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "pong");
+    }
+
+    #[test]
+    fn a_missing_key_gets_the_json_401_envelope() {
+        let client = keyed_client();
+        let response = client.get("/admin/ping").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert_eq!(error_code(response.into_string()), "unauthorized");
+    }
+
+    #[test]
+    fn a_wrong_key_gets_the_json_403_envelope() {
+        let client = keyed_client();
+        let response = client
+            .get("/admin/ping")
+            .header(rocket::http::Header::new("x-api-key", "guess"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        assert_eq!(error_code(response.into_string()), "forbidden");
+    }
+
+    #[test]
+    fn without_a_configured_key_the_admin_route_stays_open() {
+        let client = client();
+        let response = client.get("/admin/ping").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn echo_body_is_logged_with_sensitive_fields_redacted() {
+        let logger = BodyLogger::new(vec!["password".into(), "token".into()]);
+        let lines = logger.lines_handle();
+        let rocket = rocket::build().attach(logger).mount("/", routes![index, echo]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client
+            .post("/echo")
+            .header(ContentType::JSON)
+            .body(r#"{"text":"hi","password":"hunter2","nested":{"token":"abc123"}}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("[REDACTED]"));
+        assert!(!lines[0].contains("hunter2"));
+        assert!(!lines[0].contains("abc123"));
+    }
 }
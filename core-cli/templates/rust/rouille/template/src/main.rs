@@ -1,4 +1,4 @@
-use rouille::Request;
+use rouille::{router, try_or_400, Request, Response};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -6,19 +6,166 @@ struct Message {
     text: String,
 }
 
+/// Routes a single request; separate from the server loop so tests can
+/// call it without binding a socket. `Response::json` sets the
+/// `application/json` content type on every JSON body.
+fn handle_request(request: &Request) -> Response {
+    router!(request,
+        (GET) (/) => {
+            Response::text("Hello from Rouille!")
+        },
+        (POST) (/echo) => {
+            // try_or_400! answers bad JSON with a 400 error body
+            // instead of panicking the worker thread
+            let data: Message = try_or_400!(rouille::input::json_input(request));
+            Response::json(&data)
+        },
+        (GET) (/hello/{name: String}) => {
+            Response::json(&serde_json::json!({ "greeting": format!("Hello, {}!", name) }))
+        },
+        (GET) (/users/{id: u32}) => {
+            // A non-numeric id fails the u32 parse, so the route simply
+            // doesn't match and the request falls through to the 404
+            Response::json(&serde_json::json!({ "id": id }))
+        },
+        _ => Response::json(&serde_json::json!({ "error": "not_found" })).with_status_code(404)
+    )
+}
+
+/// The bind address from `HOST`/`PORT`, defaulting to `127.0.0.1:8000`.
+fn bind_address_from(host: Option<String>, port: Option<String>) -> String {
+    let host = host
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "127.0.0.1".to_string());
+    let port = port
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| "8000".to_string());
+    format!("{}:{}", host, port)
+}
+
+/// The worker pool size from `ROUILLE_WORKERS`, defaulting to rouille's
+/// own heuristic of eight threads per CPU.
+fn worker_count_from(raw: Option<String>) -> usize {
+    raw.and_then(|w| w.parse().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                * 8
+        })
+}
+
 fn main() {
-    println!("Running at http://127.0.0.1:8000");
-    rouille::start_server("127.0.0.1:8000", move |request| {
-        rouille::log(&request, std::io::stdout());
-        router!(request,
-            (GET) (/) => {
-                rouille::Response::text("Hello from Rouille!")
-            },
-            (POST) (/echo) => {
-                let data: Message = rouille::input::json_input(request).unwrap();
-                rouille::Response::json(&data)
-            },
-            _ => rouille::Response::empty_404()
+    let addr = bind_address_from(std::env::var("HOST").ok(), std::env::var("PORT").ok());
+    let workers = worker_count_from(std::env::var("ROUILLE_WORKERS").ok());
+    println!("Running at http://{} with {} workers", addr, workers);
+    let server = rouille::Server::new(addr, move |request| {
+        rouille::log(request, std::io::stdout(), || handle_request(request))
+    })
+    .expect("cannot start server")
+    .pool_size(workers);
+    server.run();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn json_request(body: &str) -> Request {
+        Request::fake_http(
+            "POST",
+            "/echo",
+            vec![("Content-Type".to_string(), "application/json".to_string())],
+            body.as_bytes().to_vec(),
         )
-    });
+    }
+
+    fn body_string(response: Response) -> String {
+        let (mut reader, _) = response.data.into_reader_and_size();
+        let mut body = String::new();
+        reader.read_to_string(&mut body).unwrap();
+        body
+    }
+
+    fn content_type(response: &Response) -> String {
+        response
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.to_string())
+            .expect("a content type")
+    }
+
+    #[test]
+    fn echo_round_trips_json_with_the_right_content_type() {
+        let response = handle_request(&json_request(r#"{"text":"hi"}"#));
+        assert_eq!(response.status_code, 200);
+        assert!(content_type(&response).starts_with("application/json"));
+        let parsed: serde_json::Value = serde_json::from_str(&body_string(response)).unwrap();
+        assert_eq!(parsed["text"], "hi");
+    }
+
+    #[test]
+    fn garbage_input_gets_a_400_instead_of_a_panic() {
+        let response = handle_request(&json_request(r#"{"text":"#));
+        assert_eq!(response.status_code, 400);
+        // try_or_400! renders the parse error as a JSON body
+        let parsed: serde_json::Value = serde_json::from_str(&body_string(response)).unwrap();
+        assert!(parsed.is_object());
+    }
+
+    #[test]
+    fn the_hello_route_greets_by_name() {
+        let request = Request::fake_http("GET", "/hello/world", vec![], vec![]);
+        let response = handle_request(&request);
+        assert_eq!(response.status_code, 200);
+        let parsed: serde_json::Value = serde_json::from_str(&body_string(response)).unwrap();
+        assert_eq!(parsed["greeting"], "Hello, world!");
+    }
+
+    #[test]
+    fn the_users_route_parses_a_numeric_id() {
+        let request = Request::fake_http("GET", "/users/42", vec![], vec![]);
+        let response = handle_request(&request);
+        assert_eq!(response.status_code, 200);
+        let parsed: serde_json::Value = serde_json::from_str(&body_string(response)).unwrap();
+        assert_eq!(parsed["id"], 42);
+    }
+
+    #[test]
+    fn a_non_numeric_id_falls_through_to_the_404() {
+        let request = Request::fake_http("GET", "/users/forty-two", vec![], vec![]);
+        let response = handle_request(&request);
+        assert_eq!(response.status_code, 404);
+    }
+
+    #[test]
+    fn the_bind_address_defaults_and_honors_overrides() {
+        assert_eq!(bind_address_from(None, None), "127.0.0.1:8000");
+        assert_eq!(
+            bind_address_from(Some("0.0.0.0".to_string()), Some("3000".to_string())),
+            "0.0.0.0:3000"
+        );
+    }
+
+    #[test]
+    fn the_worker_count_falls_back_on_garbage_or_zero() {
+        assert_eq!(worker_count_from(Some("4".to_string())), 4);
+        let fallback = worker_count_from(None);
+        assert!(fallback > 0);
+        assert_eq!(worker_count_from(Some("many".to_string())), fallback);
+        assert_eq!(worker_count_from(Some("0".to_string())), fallback);
+    }
+
+    #[test]
+    fn unknown_paths_get_the_json_404_body() {
+        let request = Request::fake_http("GET", "/no/such/path", vec![], vec![]);
+        let response = handle_request(&request);
+        assert_eq!(response.status_code, 404);
+        assert!(content_type(&response).starts_with("application/json"));
+        let parsed: serde_json::Value = serde_json::from_str(&body_string(response)).unwrap();
+        assert_eq!(parsed["error"], "not_found");
+    }
 }
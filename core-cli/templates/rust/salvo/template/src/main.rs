@@ -1,3 +1,5 @@
+use salvo::catcher::Catcher;
+use salvo::logging::Logger;
 use salvo::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -12,15 +14,144 @@ async fn index() -> &'static str {
 }
 
 #[handler]
-async fn echo(JsonBody(msg): JsonBody<Message>) -> Json<Message> {
-    Json(msg)
+async fn echo(req: &mut Request, res: &mut Response) {
+    // A body that doesn't deserialize is the client's fault, not a 500
+    match req.parse_json::<Message>().await {
+        Ok(msg) => res.render(Json(msg)),
+        Err(e) => {
+            res.status_code(StatusCode::BAD_REQUEST);
+            res.render(Json(serde_json::json!({
+                "error": { "code": "bad_request", "message": e.to_string() }
+            })));
+        }
+    }
+}
+
+#[handler]
+async fn get_user(req: &mut Request, res: &mut Response) {
+    match req.param::<i64>("id") {
+        Some(id) => res.render(Json(serde_json::json!({ "id": id }))),
+        None => {
+            res.status_code(StatusCode::BAD_REQUEST);
+            res.render(Json(serde_json::json!({
+                "error": { "code": "bad_request", "message": "id must be an integer" }
+            })));
+        }
+    }
+}
+
+/// Catcher hoop: renders any otherwise-unhandled error status (404s,
+/// panics surfaced as 500s, ...) as the shared JSON envelope.
+#[handler]
+async fn json_errors(res: &mut Response, ctrl: &mut FlowCtrl) {
+    let status = res.status_code.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let code = match status {
+        StatusCode::NOT_FOUND => "not_found",
+        s if s.is_client_error() => "bad_request",
+        _ => "internal",
+    };
+    res.render(Json(serde_json::json!({
+        "error": {
+            "code": code,
+            "message": status.canonical_reason().unwrap_or("error"),
+        }
+    })));
+    ctrl.skip_rest();
+}
+
+/// The router with all routes, separate from `main` so tests can drive
+/// it without binding a port.
+fn route() -> Router {
+    Router::new()
+        .hoop(Logger::new())
+        .get(index)
+        .push(Router::with_path("echo").post(echo))
+        .push(Router::with_path("users").push(Router::with_path("{id}").get(get_user)))
+}
+
+/// The router wrapped in a `Service` with the JSON error catcher.
+fn service() -> Service {
+    Service::new(route()).catcher(Catcher::default().hoop(json_errors))
 }
 
 #[tokio::main]
 async fn main() {
-    let router = Router::new().get(index).push(Router::with_path("echo").post(echo));
+    tracing_subscriber::fmt().init();
+    let acceptor = TcpListener::new("127.0.0.1:7878").bind().await;
     println!("Running at http://127.0.0.1:7878");
-    Server::new(TcpListener::bind("127.0.0.1:7878"))
-        .serve(router)
-        .await;
+    Server::new(acceptor).serve(service()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+
+    #[tokio::test]
+    async fn index_responds_with_the_greeting() {
+        let body = TestClient::get("http://127.0.0.1:7878/")
+            .send(&service())
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(body, "Hello from Salvo!");
+    }
+
+    #[tokio::test]
+    async fn echo_round_trips_json() {
+        let message = TestClient::post("http://127.0.0.1:7878/echo")
+            .json(&Message {
+                text: "hi".to_string(),
+            })
+            .send(&service())
+            .await
+            .take_json::<Message>()
+            .await
+            .unwrap();
+        assert_eq!(message.text, "hi");
+    }
+
+    #[tokio::test]
+    async fn bad_json_gets_a_400_with_a_json_error_body() {
+        let mut response = TestClient::post("http://127.0.0.1:7878/echo")
+            .add_header("content-type", "application/json", true)
+            .body(r#"{"text":"#.to_string())
+            .send(&service())
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::BAD_REQUEST));
+        let parsed = response.take_json::<serde_json::Value>().await.unwrap();
+        assert_eq!(parsed["error"]["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn the_param_route_returns_the_id_as_json() {
+        let parsed = TestClient::get("http://127.0.0.1:7878/users/42")
+            .send(&service())
+            .await
+            .take_json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(parsed["id"], 42);
+    }
+
+    #[tokio::test]
+    async fn a_non_numeric_id_gets_the_400_envelope() {
+        let mut response = TestClient::get("http://127.0.0.1:7878/users/forty-two")
+            .send(&service())
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::BAD_REQUEST));
+        let parsed = response.take_json::<serde_json::Value>().await.unwrap();
+        assert_eq!(parsed["error"]["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn unknown_paths_get_the_json_404_envelope() {
+        let mut response = TestClient::get("http://127.0.0.1:7878/no/such/path")
+            .send(&service())
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::NOT_FOUND));
+        let parsed = response.take_json::<serde_json::Value>().await.unwrap();
+        assert_eq!(parsed["error"]["code"], "not_found");
+    }
 }
@@ -1,30 +1,198 @@
-use thruster::{context::basic_context::*, m, middleware_fn, App, Request};
+use std::env;
+
 use serde::{Deserialize, Serialize};
+use thruster::context::basic_hyper_context::{generate_context, BasicHyperContext, HyperRequest};
+use thruster::context::context_ext::ContextExt;
+use thruster::hyper_server::HyperServer;
+use thruster::{m, middleware_fn, App, Context, MiddlewareNext, MiddlewareResult, ThrusterServer};
 
 #[derive(Serialize, Deserialize)]
 struct Message {
     text: String,
 }
 
+/// Server configuration read from the environment, with sensible defaults:
+/// `HOST` (default `127.0.0.1`), `PORT` (default `4321`) and `THREADS`
+/// (default `1`).
+#[derive(Debug)]
+struct Config {
+    host: String,
+    port: u16,
+    threads: usize,
+}
+
+impl Config {
+    /// Reads and validates every field, aggregating all errors so a bad
+    /// environment is reported in one pass instead of one variable at a
+    /// time.
+    fn from_env() -> Result<Config, Vec<String>> {
+        let mut errors = Vec::new();
+
+        let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        if host.trim().is_empty() {
+            errors.push("HOST must not be empty".to_string());
+        }
+
+        let port = match env::var("PORT") {
+            Ok(raw) => match raw.parse::<u16>() {
+                Ok(0) => {
+                    errors.push("PORT must be between 1 and 65535".to_string());
+                    0
+                }
+                Ok(port) => port,
+                Err(_) => {
+                    errors.push(format!("PORT must be a number between 1 and 65535, got {:?}", raw));
+                    0
+                }
+            },
+            Err(_) => 4321,
+        };
+
+        let threads = match env::var("THREADS") {
+            Ok(raw) => match raw.parse::<usize>() {
+                Ok(0) => {
+                    errors.push("THREADS must be at least 1".to_string());
+                    0
+                }
+                Ok(threads) => threads,
+                Err(_) => {
+                    errors.push(format!("THREADS must be a positive number, got {:?}", raw));
+                    0
+                }
+            },
+            Err(_) => 1,
+        };
+
+        if errors.is_empty() {
+            Ok(Config { host, port, threads })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[middleware_fn]
+async fn hello(
+    mut context: BasicHyperContext,
+    _next: MiddlewareNext<BasicHyperContext>,
+) -> MiddlewareResult<BasicHyperContext> {
+    context.body("Hello from Thruster!");
+    Ok(context)
+}
+
 #[middleware_fn]
-async fn hello(mut context: BasicContext, _next: MiddlewareNext<BasicContext>) -> BasicContext {
-    context.body = "Hello from Thruster!".to_string();
-    context
+async fn greet(
+    mut context: BasicHyperContext,
+    _next: MiddlewareNext<BasicHyperContext>,
+) -> MiddlewareResult<BasicHyperContext> {
+    let name = context
+        .params()
+        .get("name")
+        .map(|name| name.param.clone())
+        .unwrap_or_default();
+    context.body(&format!("Hello, {}!", name));
+    Ok(context)
 }
 
 #[middleware_fn]
-async fn echo(mut context: BasicContext, _next: MiddlewareNext<BasicContext>) -> BasicContext {
-    let body = context.body_string().await.unwrap_or_default();
-    context.body = body;
-    context
+async fn echo(
+    mut context: BasicHyperContext,
+    _next: MiddlewareNext<BasicHyperContext>,
+) -> MiddlewareResult<BasicHyperContext> {
+    // A body that doesn't read or deserialize is the client's fault,
+    // not an empty echo
+    let raw = context.body_string().await.unwrap_or_default();
+    match serde_json::from_str::<Message>(&raw) {
+        Ok(message) => {
+            context.set("Content-Type", "application/json");
+            context.body(&serde_json::to_string(&message).expect("Message serializes"));
+        }
+        Err(e) => {
+            context.status(400);
+            context.set("Content-Type", "application/json");
+            context.body(
+                &serde_json::json!({
+                    "error": { "code": "bad_request", "message": e.to_string() }
+                })
+                .to_string(),
+            );
+        }
+    }
+    Ok(context)
 }
 
-#[tokio::main]
-async fn main() {
-    let app = App::<Request, BasicContext, ()>::new_basic()
+/// The app with all routes, separate from `main` so tests can resolve
+/// requests against it without binding a port. `commit` finalizes the
+/// route tree; the server does it again on start, which is harmless.
+fn app() -> App<HyperRequest, BasicHyperContext, ()> {
+    App::<HyperRequest, BasicHyperContext, ()>::create(generate_context, ())
         .get("/", m!(hello))
-        .post("/echo", m!(echo));
+        .get("/hello/:name", m!(greet))
+        .post("/echo", m!(echo))
+        .commit()
+}
+
+fn main() {
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("Invalid configuration:");
+            for error in &errors {
+                eprintln!("  - {}", error);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    println!(
+        "Running at http://{}:{} ({} thread{})",
+        config.host,
+        config.port,
+        config.threads,
+        if config.threads == 1 { "" } else { "s" }
+    );
+
+    // THREADS sizes the tokio runtime the server runs on
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(config.threads)
+        .enable_all()
+        .build()
+        .expect("cannot build runtime");
+    runtime.block_on(HyperServer::new(app()).build(&config.host, config.port));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use thruster::Testable;
+
+    #[tokio::test]
+    async fn echo_round_trips_json() {
+        let app = app();
+        let response = Testable::post(&app, "/echo", vec![], r#"{"text":"hi"}"#.as_bytes().to_vec())
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        let parsed: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(parsed["text"], "hi");
+    }
+
+    #[tokio::test]
+    async fn bad_json_gets_a_400_with_a_json_error_body() {
+        let app = app();
+        let response = Testable::post(&app, "/echo", vec![], r#"{"text":"#.as_bytes().to_vec())
+            .await
+            .unwrap();
+        assert_eq!(response.status, 400);
+        let parsed: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(parsed["error"]["code"], "bad_request");
+    }
 
-    println!("Running at http://127.0.0.1:4321");
-    thruster::server::Server::new(app).start("127.0.0.1", 4321, 1).await;
+    #[tokio::test]
+    async fn the_param_route_greets_by_name() {
+        let app = app();
+        let response = Testable::get(&app, "/hello/world", vec![]).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body_string(), "Hello, world!");
+    }
 }
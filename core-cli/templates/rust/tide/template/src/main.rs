@@ -1,17 +1,239 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 use tide::{Request, Response, StatusCode};
 
-#[async_std::main]
-async fn main() -> tide::Result<()> {
-    let mut app = tide::new();
+#[derive(Serialize, Deserialize)]
+struct Message {
+    text: String,
+}
+
+/// State shared by every handler: a request counter and the process
+/// start time. Tide requires state to be `Clone`, hence the `Arc` in
+/// the server type. Extend it with connection pools, caches, etc.
+struct AppState {
+    requests_served: AtomicU64,
+    started: Instant,
+}
+
+impl AppState {
+    fn new() -> AppState {
+        AppState {
+            requests_served: AtomicU64::new(0),
+            started: Instant::now(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Stats {
+    requests_served: u64,
+    uptime_seconds: u64,
+}
+
+/// The app with its state and routes, separate from `main` so tests
+/// can drive it without binding a port.
+fn app() -> tide::Server<Arc<AppState>> {
+    // `tide::with_state` already bundles `tide::log::LogMiddleware`;
+    // the `tide::log::start()` call in `main` is what wires it to a
+    // logger
+    let mut app = tide::with_state(Arc::new(AppState::new()));
+    // Counts every request before it reaches its handler
+    app.with(tide::utils::Before(
+        |req: Request<Arc<AppState>>| async move {
+            req.state().requests_served.fetch_add(1, Ordering::Relaxed);
+            req
+        },
+    ));
     app.at("/").get(|_| async { Ok("Hello from Tide!") });
     app.at("/echo").post(echo);
-    app.listen("127.0.0.1:8080").await?;
-    Ok(())
+    app.at("/stats").get(stats);
+    app
 }
 
-async fn echo(mut req: Request<()>) -> tide::Result {
-    let body: serde_json::Value = req.body_json().await?;
+/// The bind address from `HOST`/`PORT`, defaulting to
+/// `127.0.0.1:8080`. A port that isn't a number is reported instead of
+/// panicking deep inside `listen`.
+fn bind_address_from(host: Option<String>, port: Option<String>) -> Result<String, String> {
+    let host = host
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "127.0.0.1".to_string());
+    let port = match port.filter(|p| !p.is_empty()) {
+        Some(raw) => raw
+            .parse::<u16>()
+            .map_err(|_| format!("invalid PORT {:?} (expected a number 0-65535)", raw))?,
+        None => 8080,
+    };
+    Ok(format!("{}:{}", host, port))
+}
+
+/// Resolves once ctrl-c or SIGTERM arrives, so `main` can race it
+/// against the server and let in-flight requests finish.
+async fn shutdown_signal() {
+    use async_std::prelude::StreamExt;
+    use signal_hook::consts::{SIGINT, SIGTERM};
+
+    let mut signals =
+        signal_hook_async_std::Signals::new([SIGINT, SIGTERM]).expect("cannot install signal handlers");
+    signals.next().await;
+    tide::log::info!("shutting down");
+}
+
+#[async_std::main]
+async fn main() -> std::io::Result<()> {
+    // Honor RUST_LOG when it parses as a level, default to info
+    match std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+    {
+        Some(level) => tide::log::with_level(level),
+        None => tide::log::start(),
+    }
+
+    let addr = match bind_address_from(std::env::var("HOST").ok(), std::env::var("PORT").ok()) {
+        Ok(addr) => addr,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    };
+    println!("Running at http://{}", addr);
+
+    let server = app().listen(addr);
+    let shutdown = async {
+        shutdown_signal().await;
+        Ok(())
+    };
+    futures_lite::future::race(server, shutdown).await
+}
+
+async fn echo(mut req: Request<Arc<AppState>>) -> tide::Result {
+    let message: Message = match req.body_json().await {
+        Ok(message) => message,
+        Err(e) => {
+            // A body that doesn't deserialize is the client's fault,
+            // not a 500
+            let body = serde_json::json!({
+                "error": { "code": "bad_request", "message": e.to_string() }
+            });
+            return Ok(Response::builder(StatusCode::BadRequest)
+                .content_type(tide::http::mime::JSON)
+                .body(body.to_string())
+                .build());
+        }
+    };
+    Ok(Response::builder(StatusCode::Ok)
+        .content_type(tide::http::mime::JSON)
+        .body(serde_json::to_string(&message)?)
+        .build())
+}
+
+async fn stats(req: Request<Arc<AppState>>) -> tide::Result {
+    let state = req.state();
+    let payload = Stats {
+        requests_served: state.requests_served.load(Ordering::Relaxed),
+        uptime_seconds: state.started.elapsed().as_secs(),
+    };
     Ok(Response::builder(StatusCode::Ok)
-        .body(serde_json::to_string(&body)?)
+        .content_type(tide::http::mime::JSON)
+        .body(serde_json::to_string(&payload)?)
         .build())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tide_testing::surf::Body;
+    use tide_testing::TideTestingExt;
+
+    #[async_std::test]
+    async fn echo_round_trips_json_with_the_right_content_type() -> tide::Result<()> {
+        let app = app();
+        let mut response = app
+            .post("/echo")
+            .body(Body::from_string(r#"{"text":"hi"}"#.to_string()))
+            .await?;
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert_eq!(
+            response.content_type().expect("a content type").essence(),
+            "application/json"
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&response.body_string().await?)?;
+        assert_eq!(parsed["text"], "hi");
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn bad_json_gets_a_400_with_a_json_error_body() -> tide::Result<()> {
+        let app = app();
+        let mut response = app
+            .post("/echo")
+            .body(Body::from_string(r#"{"text":"#.to_string()))
+            .await?;
+        assert_eq!(response.status(), StatusCode::BadRequest);
+        assert_eq!(
+            response.content_type().expect("a content type").essence(),
+            "application/json"
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&response.body_string().await?)?;
+        assert_eq!(parsed["error"]["code"], "bad_request");
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn index_responds_with_the_greeting() -> tide::Result<()> {
+        let app = app();
+        let body = app.get("/").recv_string().await?;
+        assert_eq!(body, "Hello from Tide!");
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn the_counter_increases_across_sequential_requests() -> tide::Result<()> {
+        let app = app();
+        for _ in 0..2 {
+            app.get("/").await?;
+        }
+        // The /stats request itself is also counted
+        let parsed: serde_json::Value =
+            serde_json::from_str(&app.get("/stats").recv_string().await?)?;
+        assert!(parsed["requests_served"].as_u64().unwrap() >= 2);
+        Ok(())
+    }
+
+    #[test]
+    fn the_bind_address_defaults_and_honors_overrides() {
+        assert_eq!(bind_address_from(None, None).unwrap(), "127.0.0.1:8080");
+        assert_eq!(
+            bind_address_from(Some("0.0.0.0".to_string()), Some("3000".to_string())).unwrap(),
+            "0.0.0.0:3000"
+        );
+    }
+
+    #[test]
+    fn an_invalid_port_is_reported_not_panicked_on() {
+        let error = bind_address_from(None, Some("eighty".to_string())).unwrap_err();
+        assert!(error.contains("eighty"));
+    }
+
+    #[async_std::test]
+    async fn the_listen_future_can_be_cancelled_cleanly() {
+        let handle = async_std::task::spawn(app().listen("127.0.0.1:0".to_string()));
+        async_std::task::sleep(std::time::Duration::from_millis(50)).await;
+        // Cancelling before completion yields None; in-flight state is
+        // dropped cleanly
+        assert!(handle.cancel().await.is_none());
+    }
+
+    #[test]
+    fn the_stats_payload_serializes_with_both_fields() {
+        let payload = Stats {
+            requests_served: 7,
+            uptime_seconds: 42,
+        };
+        let value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(value["requests_served"], 7);
+        assert_eq!(value["uptime_seconds"], 42);
+    }
+}
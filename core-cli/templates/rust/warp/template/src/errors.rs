@@ -0,0 +1,74 @@
+use serde::Serialize;
+use std::convert::Infallible;
+use warp::filters::body::BodyDeserializeError;
+use warp::http::StatusCode;
+use warp::reject::{InvalidQuery, MethodNotAllowed};
+use warp::{Rejection, Reply};
+
+/// An application-level failure carried through warp's rejection
+/// machinery; raise one from a handler with
+/// `warp::reject::custom(AppError { ... })` and it comes out of
+/// `handle_rejection` in the shared envelope.
+#[derive(Debug)]
+pub struct AppError {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl warp::reject::Reject for AppError {}
+
+/// The JSON error envelope every failure shares:
+/// `{"error": {"code": ..., "message": ...}}`.
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: ErrorDetail<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail<'a> {
+    code: &'a str,
+    message: &'a str,
+}
+
+/// Maps rejections to JSON instead of warp's plain-text defaults.
+/// Attach it once, after the last `.or(...)`, with
+/// `.recover(handle_rejection)`.
+pub async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let (status, code, message) = if rejection.is_not_found() {
+        (
+            StatusCode::NOT_FOUND,
+            "not_found",
+            "no route matches the request".to_string(),
+        )
+    } else if let Some(error) = rejection.find::<AppError>() {
+        (error.status, error.code, error.message.clone())
+    } else if let Some(error) = rejection.find::<BodyDeserializeError>() {
+        (StatusCode::BAD_REQUEST, "bad_request", error.to_string())
+    } else if rejection.find::<InvalidQuery>().is_some() {
+        (
+            StatusCode::BAD_REQUEST,
+            "bad_request",
+            "the query string is missing or malformed".to_string(),
+        )
+    } else if rejection.find::<MethodNotAllowed>().is_some() {
+        (
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method_not_allowed",
+            "the method is not allowed for this path".to_string(),
+        )
+    } else {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal",
+            format!("unhandled rejection: {:?}", rejection),
+        )
+    };
+    let body = warp::reply::json(&ErrorBody {
+        error: ErrorDetail {
+            code,
+            message: &message,
+        },
+    });
+    Ok(warp::reply::with_status(body, status))
+}
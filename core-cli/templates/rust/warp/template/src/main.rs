@@ -1,22 +1,272 @@
-use warp::Filter;
+mod errors;
+
+use errors::{handle_rejection, AppError};
+use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use warp::http::StatusCode;
+use warp::ws::{Message as WsMessage, WebSocket};
+use warp::Filter;
+
+/// Upper bound for a single websocket frame; larger messages fail the
+/// read instead of buffering unbounded input.
+const MAX_WS_MESSAGE_BYTES: usize = 64 * 1024;
 
 #[derive(Deserialize, Serialize)]
 struct Message {
     text: String,
 }
 
-#[tokio::main]
-async fn main() {
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+}
+
+/// State shared by every handler: a request counter. Extend it with
+/// connection pools, caches, etc.
+#[derive(Default)]
+struct AppState {
+    requests_served: AtomicU64,
+}
+
+/// Clones the shared state into a filter chain, so handlers can take
+/// it as an ordinary argument.
+fn with_state(
+    state: Arc<AppState>,
+) -> impl Filter<Extract = (Arc<AppState>,), Error = Infallible> + Clone {
+    warp::any().map(move || Arc::clone(&state))
+}
+
+/// All routes combined, with every rejection rendered as the JSON
+/// envelope from `errors::handle_rejection`.
+fn routes(
+    state: Arc<AppState>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Infallible> + Clone {
+    // Counts every request, matched or not, before routing happens
+    let count = with_state(Arc::clone(&state))
+        .map(|state: Arc<AppState>| {
+            state.requests_served.fetch_add(1, Ordering::Relaxed);
+        })
+        .untuple_one();
+
     let hello = warp::path::end().map(|| "Hello from Warp!");
 
+    let greet = warp::path!("hello" / String)
+        .and(warp::get())
+        .map(|name: String| format!("Hello, {}!", name));
+
     let echo = warp::path("echo")
         .and(warp::post())
         .and(warp::body::json())
         .map(|msg: Message| warp::reply::json(&msg));
 
-    let routes = hello.or(echo);
+    let search = warp::path("search")
+        .and(warp::get())
+        .and(warp::query::<SearchQuery>())
+        .and_then(|query: SearchQuery| async move {
+            let q = query.q.trim().to_string();
+            if q.is_empty() {
+                return Err(warp::reject::custom(AppError {
+                    status: StatusCode::BAD_REQUEST,
+                    code: "bad_request",
+                    message: "q must not be empty".to_string(),
+                }));
+            }
+            Ok::<_, warp::Rejection>(warp::reply::json(&serde_json::json!({
+                "query": q,
+                "results": [],
+            })))
+        });
+
+    let ws = warp::path("ws").and(warp::ws()).map(|ws: warp::ws::Ws| {
+        ws.max_message_size(MAX_WS_MESSAGE_BYTES)
+            .on_upgrade(echo_socket)
+    });
+
+    let stats = warp::path("stats")
+        .and(warp::get())
+        .and(with_state(state))
+        .map(|state: Arc<AppState>| {
+            warp::reply::json(&serde_json::json!({
+                "requests_served": state.requests_served.load(Ordering::Relaxed),
+            }))
+        });
+
+    count
+        .and(hello.or(greet).or(echo).or(search).or(ws).or(stats))
+        .recover(handle_rejection)
+}
+
+/// Echoes text frames back with an `echo: ` prefix until the peer
+/// closes. Binary frames get a polite text reply; pings and pongs are
+/// left to warp.
+async fn echo_socket(socket: WebSocket) {
+    let (mut tx, mut rx) = socket.split();
+    println!("websocket client connected");
+    while let Some(received) = rx.next().await {
+        let msg = match received {
+            Ok(msg) => msg,
+            Err(e) => {
+                eprintln!("websocket error: {}", e);
+                break;
+            }
+        };
+        if msg.is_close() {
+            break;
+        }
+        let reply = if let Ok(text) = msg.to_str() {
+            WsMessage::text(format!("echo: {}", text))
+        } else if msg.is_binary() {
+            WsMessage::text("binary frames are not supported; send text")
+        } else {
+            continue;
+        };
+        if tx.send(reply).await.is_err() {
+            break;
+        }
+    }
+    println!("websocket client disconnected");
+}
 
+#[tokio::main]
+async fn main() {
+    let state = Arc::new(AppState::default());
     println!("Running at http://127.0.0.1:3030");
-    warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
+    warp::serve(routes(state)).run(([127, 0, 0, 1], 3030)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_routes() -> impl Filter<Extract = (impl warp::Reply,), Error = Infallible> + Clone {
+        routes(Arc::new(AppState::default()))
+    }
+
+    fn error_code(body: &[u8]) -> String {
+        let parsed: serde_json::Value = serde_json::from_slice(body).unwrap();
+        parsed["error"]["code"]
+            .as_str()
+            .expect("an error code")
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn echo_round_trips_json() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/echo")
+            .json(&Message {
+                text: "hi".to_string(),
+            })
+            .reply(&test_routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let parsed: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(parsed["text"], "hi");
+    }
+
+    #[tokio::test]
+    async fn malformed_json_gets_the_400_envelope() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/echo")
+            .header("content-type", "application/json")
+            .body(r#"{"text":"#)
+            .reply(&test_routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error_code(response.body()), "bad_request");
+    }
+
+    #[tokio::test]
+    async fn unknown_paths_get_the_json_404() {
+        let response = warp::test::request()
+            .path("/no/such/path")
+            .reply(&test_routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error_code(response.body()), "not_found");
+    }
+
+    #[tokio::test]
+    async fn the_wrong_method_gets_a_json_405() {
+        let response = warp::test::request()
+            .path("/echo")
+            .reply(&test_routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(error_code(response.body()), "method_not_allowed");
+    }
+
+    #[tokio::test]
+    async fn the_param_route_greets_by_name() {
+        let response = warp::test::request()
+            .path("/hello/world")
+            .reply(&test_routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), "Hello, world!");
+    }
+
+    #[tokio::test]
+    async fn a_search_returns_the_normalized_query() {
+        let response = warp::test::request()
+            .path("/search?q=%20warp%20")
+            .reply(&test_routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let parsed: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(parsed["query"], "warp");
+    }
+
+    #[tokio::test]
+    async fn an_empty_query_gets_the_json_400() {
+        let response = warp::test::request()
+            .path("/search?q=")
+            .reply(&test_routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error_code(response.body()), "bad_request");
+    }
+
+    #[tokio::test]
+    async fn the_websocket_echoes_text_and_closes_cleanly() {
+        let mut client = warp::test::ws()
+            .path("/ws")
+            .handshake(test_routes())
+            .await
+            .expect("websocket handshake");
+        client.send_text("hi").await;
+        let reply = client.recv().await.expect("an echo reply");
+        assert_eq!(reply.to_str().unwrap(), "echo: hi");
+        client.send(WsMessage::close()).await;
+        client.recv_closed().await.expect("a clean close");
+    }
+
+    #[tokio::test]
+    async fn binary_frames_get_the_fallback_reply() {
+        let mut client = warp::test::ws()
+            .path("/ws")
+            .handshake(test_routes())
+            .await
+            .expect("websocket handshake");
+        client.send(WsMessage::binary(vec![1, 2, 3])).await;
+        let reply = client.recv().await.expect("a fallback reply");
+        assert!(reply.to_str().unwrap().contains("binary frames"));
+    }
+
+    #[tokio::test]
+    async fn the_counter_increases_across_sequential_requests() {
+        let filter = test_routes();
+        for _ in 0..2 {
+            warp::test::request().path("/").reply(&filter).await;
+        }
+        // The /stats request itself is the third counted request
+        let response = warp::test::request().path("/stats").reply(&filter).await;
+        let parsed: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(parsed["requests_served"], 3);
+    }
 }
@@ -0,0 +1,48 @@
+//! Keeps the embedded template manifest honest: every template
+//! directory in the repository's template trees must have an entry in
+//! `templates.json`, or the build fails with the path to add. When the
+//! crate is built outside the repository (a published tarball without
+//! `core-cli/`), the check is skipped — the checked-in manifest is
+//! then simply trusted.
+
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=templates.json");
+    println!("cargo:rerun-if-changed=../../core-cli/templates");
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR");
+    let templates_root = Path::new(&manifest_dir).join("../../core-cli/templates");
+    if !templates_root.is_dir() {
+        return;
+    }
+    let manifest = std::fs::read_to_string(Path::new(&manifest_dir).join("templates.json"))
+        .expect("wrappers/rust/templates.json must exist next to Cargo.toml");
+
+    for category in subdirectories(&templates_root) {
+        for framework in subdirectories(&templates_root.join(&category)) {
+            // No JSON parser in the build script: the manifest writes
+            // each path exactly once in this form, so a substring
+            // check is enough to prove the entry exists
+            let needle = format!("\"path\": \"templates/{}/{}\"", category, framework);
+            assert!(
+                manifest.contains(&needle),
+                "template directory templates/{}/{} is not listed in wrappers/rust/templates.json; \
+                 add an entry with a one-line description",
+                category,
+                framework
+            );
+        }
+    }
+}
+
+fn subdirectories(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect()
+}
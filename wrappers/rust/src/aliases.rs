@@ -0,0 +1,181 @@
+//! User-defined command aliases.
+//!
+//! `~/.config/package-installer/aliases.toml` (honoring
+//! `XDG_CONFIG_HOME`) maps a single token to the argument list it
+//! stands for:
+//!
+//! ```toml
+//! c = ["create"]
+//! ad = ["analyze", "--detailed"]
+//! ```
+//!
+//! When the first forwarded argument matches an alias, it is replaced
+//! in place by the alias's arguments and anything the user typed after
+//! it is appended, so `pi ad src/` runs `pi analyze --detailed src/`.
+//! Expansion is a single pass — aliases cannot reference other
+//! aliases — and happens after the wrapper's own subcommands have been
+//! dispatched, so an alias can never shadow `pi wrapper ...`. Names
+//! that collide with wrapper-owned tokens are refused at load time for
+//! good measure. A malformed file is reported once and otherwise
+//! ignored; aliases must never break the wrapped CLI.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use package_installer_cli::config;
+use package_installer_cli::debug::debug_log;
+
+use crate::ui;
+
+/// Tokens an alias may never claim: the wrapper's own entry points and
+/// flag-looking names, which the CLI would misparse anyway.
+const RESERVED: &[&str] = &["wrapper"];
+
+/// Alias file location (`~/.config/package-installer/aliases.toml`).
+fn aliases_path() -> Option<PathBuf> {
+    Some(config::user_config_dir()?.join("aliases.toml"))
+}
+
+/// The alias table from disk. Missing file: empty. Malformed file or
+/// entries: warned about and dropped, never fatal.
+fn load() -> BTreeMap<String, Vec<String>> {
+    let Some(path) = aliases_path() else {
+        return BTreeMap::new();
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return BTreeMap::new();
+    };
+    let parsed: BTreeMap<String, Vec<String>> = match toml::from_str(&contents) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!(
+                "{}",
+                ui::Style::for_stderr().warn(&format!(
+                    "Ignoring malformed alias file {}: {}",
+                    path.display(),
+                    e
+                ))
+            );
+            return BTreeMap::new();
+        }
+    };
+    let mut table = BTreeMap::new();
+    for (name, expansion) in parsed {
+        if let Some(reason) = rejection(&name, &expansion) {
+            eprintln!(
+                "{}",
+                ui::Style::for_stderr()
+                    .warn(&format!("Ignoring alias `{}` in {}: {}", name, path.display(), reason))
+            );
+            continue;
+        }
+        table.insert(name, expansion);
+    }
+    table
+}
+
+/// Why `name = expansion` is not a usable alias, if it is not.
+fn rejection(name: &str, expansion: &[String]) -> Option<&'static str> {
+    if RESERVED.contains(&name) {
+        return Some("it would shadow a wrapper command");
+    }
+    if name.starts_with('-') {
+        return Some("alias names cannot look like flags");
+    }
+    if expansion.is_empty() {
+        return Some("the argument list is empty");
+    }
+    None
+}
+
+/// Expands the first argument when it names an alias; everything the
+/// user typed after it is appended untouched. Non-UTF-8 first
+/// arguments cannot name an alias and pass through as-is.
+pub fn expand(cli_args: Vec<OsString>) -> Vec<OsString> {
+    let Some(first) = cli_args.first().and_then(|arg| arg.to_str()) else {
+        return cli_args;
+    };
+    let table = load();
+    let Some(expansion) = table.get(first) else {
+        return cli_args;
+    };
+    debug_log!("alias {} -> {}", first, expansion.join(" "));
+    let mut expanded: Vec<OsString> = expansion.iter().map(OsString::from).collect();
+    expanded.extend(cli_args.into_iter().skip(1));
+    expanded
+}
+
+/// `pi wrapper aliases`: prints the alias table.
+pub fn run_list() -> i32 {
+    let table = load();
+    if table.is_empty() {
+        let location = aliases_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "~/.config/package-installer/aliases.toml".to_string());
+        println!("No aliases defined (add them to {})", location);
+        return 0;
+    }
+    for (name, expansion) in table {
+        println!("{} = {}", name, expansion.join(" "));
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_with(table: &BTreeMap<String, Vec<String>>, args: &[&str]) -> Vec<String> {
+        // Mirrors `expand` against an in-memory table, so tests need no
+        // config file on disk
+        let mut args: Vec<OsString> = args.iter().map(OsString::from).collect();
+        if let Some(expansion) = args
+            .first()
+            .and_then(|arg| arg.to_str())
+            .and_then(|first| table.get(first))
+        {
+            let mut expanded: Vec<OsString> = expansion.iter().map(OsString::from).collect();
+            expanded.extend(args.into_iter().skip(1));
+            args = expanded;
+        }
+        args.iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn table() -> BTreeMap<String, Vec<String>> {
+        let contents = "c = [\"create\"]\nad = [\"analyze\", \"--detailed\"]\n";
+        toml::from_str(contents).unwrap()
+    }
+
+    #[test]
+    fn the_first_argument_expands_in_place() {
+        assert_eq!(expand_with(&table(), &["c", "my-app"]), ["create", "my-app"]);
+    }
+
+    #[test]
+    fn later_arguments_are_appended_after_the_expansion() {
+        assert_eq!(
+            expand_with(&table(), &["ad", "src/", "--json"]),
+            ["analyze", "--detailed", "src/", "--json"]
+        );
+    }
+
+    #[test]
+    fn only_the_first_argument_is_considered() {
+        assert_eq!(
+            expand_with(&table(), &["create", "c"]),
+            ["create", "c"],
+            "an alias token past position zero must pass through"
+        );
+    }
+
+    #[test]
+    fn reserved_and_degenerate_names_are_rejected() {
+        assert!(rejection("wrapper", &["anything".into()]).is_some());
+        assert!(rejection("--fast", &["create".into()]).is_some());
+        assert!(rejection("empty", &[]).is_some());
+        assert!(rejection("c", &["create".into()]).is_none());
+    }
+}
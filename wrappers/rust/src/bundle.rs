@@ -0,0 +1,236 @@
+//! Lazy extraction of a compressed standalone bundle.
+//!
+//! Distribution can ship a single `pi` binary plus a
+//! `bundle-standalone.tar.gz` (`.zip` on Windows) next to it instead
+//! of a loose `bundle-standalone/` directory. When the bundled step is
+//! probed and the loose directory is absent, the archive is extracted
+//! once into `~/.cache/package-installer/bundles/<sha256>/` and reused
+//! from there for as long as the archive's hash is unchanged — a
+//! replaced archive simply lands in a fresh hash directory. Extraction
+//! goes through a temp directory and a rename, so a crashed extraction
+//! never looks like a usable bundle.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use package_installer_cli::debug::debug_log;
+use package_installer_cli::resolver;
+
+use crate::{cache, lock, verify};
+
+/// The archive name probed next to the wrapper binary.
+fn archive_name() -> &'static str {
+    if cfg!(windows) {
+        "bundle-standalone.zip"
+    } else {
+        "bundle-standalone.tar.gz"
+    }
+}
+
+/// Extra bundled-executable candidates from compressed archives next
+/// to the binary, extracting on first use. Called lazily by the
+/// resolver, only once the loose `bundle-standalone/` probes ran dry.
+pub fn extracted_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    for dir in crate::executable_dirs() {
+        let archive = dir.join(archive_name());
+        if !archive.is_file() {
+            continue;
+        }
+        // A loose bundle next to the archive always wins; don't even
+        // extract in that case
+        let loose = resolver::pi_executable_candidates(&dir.join("bundle-standalone"), cfg!(windows));
+        if loose.iter().any(|path| path.is_file()) {
+            continue;
+        }
+        let Some(root) = ensure_extracted(&archive) else {
+            continue;
+        };
+        // Accept both archive layouts: `pi` at the top level, or a
+        // `bundle-standalone/` directory wrapping it
+        candidates.extend(resolver::pi_executable_candidates(&root, cfg!(windows)));
+        candidates.extend(resolver::pi_executable_candidates(
+            &root.join("bundle-standalone"),
+            cfg!(windows),
+        ));
+    }
+    candidates
+}
+
+/// Where extracted archives live, keyed by archive hash.
+fn bundles_dir() -> Option<PathBuf> {
+    Some(cache::cache_dir()?.join("bundles"))
+}
+
+/// True for executables running out of the extraction cache. These are
+/// kept out of the resolution cache: a cached hit would keep running a
+/// stale extraction after the archive was swapped, and the hash check
+/// in [`extracted_candidates`] is cheap enough to redo every run.
+pub fn is_extracted_path(path: &Path) -> bool {
+    bundles_dir().is_some_and(|dir| path.starts_with(dir))
+}
+
+/// Extracts `archive` into the per-user bundle cache (or reuses the
+/// existing extraction for this archive hash) and returns the
+/// extraction root. Any failure is logged and reads as "no bundle".
+fn ensure_extracted(archive: &Path) -> Option<PathBuf> {
+    let hash = match verify::sha256_hex(archive) {
+        Ok(hash) => hash,
+        Err(e) => {
+            debug_log!("cannot hash {}: {}", archive.display(), e);
+            return None;
+        }
+    };
+    let root = bundles_dir()?.join(&hash);
+    if root.is_dir() {
+        debug_log!("reusing extracted bundle {}", root.display());
+        return Some(root);
+    }
+
+    // One extractor at a time; whoever loses the race finds the
+    // finished extraction under the lock
+    let _lock = lock::for_write(&root)?;
+    if root.is_dir() {
+        return Some(root);
+    }
+    match extract_to(archive, &root) {
+        Ok(()) => {
+            debug_log!("extracted {} to {}", archive.display(), root.display());
+            Some(root)
+        }
+        Err(reason) => {
+            debug_log!("cannot extract {}: {}", archive.display(), reason);
+            None
+        }
+    }
+}
+
+/// Extracts `archive` into `root` via a temp directory and a rename,
+/// restoring the executable bit on `pi` afterwards (tar preserves it,
+/// zip archives may not carry one).
+fn extract_to(archive: &Path, root: &Path) -> Result<(), String> {
+    let parent = root.parent().ok_or("bundle cache has no parent")?;
+    std::fs::create_dir_all(parent).map_err(|e| format!("cannot create {}: {}", parent.display(), e))?;
+    let temp = parent.join(format!(".extract-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&temp);
+    std::fs::create_dir_all(&temp).map_err(|e| format!("cannot create {}: {}", temp.display(), e))?;
+
+    // bsdtar and GNU tar both auto-detect the compression (and bsdtar
+    // reads zip), so one invocation covers every platform we ship to
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(&temp)
+        .status()
+        .map_err(|e| format!("cannot run tar: {}", e))?;
+    if !status.success() {
+        let _ = std::fs::remove_dir_all(&temp);
+        return Err(format!("tar exited with {}", status));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        for dir in [temp.clone(), temp.join("bundle-standalone")] {
+            for candidate in resolver::pi_executable_candidates(&dir, false) {
+                if candidate.is_file() {
+                    let _ = std::fs::set_permissions(
+                        &candidate,
+                        std::fs::Permissions::from_mode(0o755),
+                    );
+                }
+            }
+        }
+    }
+
+    std::fs::rename(&temp, root).map_err(|e| {
+        let _ = std::fs::remove_dir_all(&temp);
+        format!("cannot move extraction into place: {}", e)
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pi-wrapper-bundle-test-{}-{}",
+            tag,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A tar.gz containing a single `pi` script printing `payload`.
+    fn fixture_archive(dir: &Path, payload: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let stage = dir.join("stage");
+        std::fs::create_dir_all(&stage).unwrap();
+        let pi = stage.join("pi");
+        std::fs::write(&pi, format!("#!/bin/sh\necho {}\n", payload)).unwrap();
+        std::fs::set_permissions(&pi, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let archive = dir.join("bundle-standalone.tar.gz");
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&stage)
+            .arg("pi")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        archive
+    }
+
+    fn extracted_roots(bundles: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(bundles) else {
+            return Vec::new();
+        };
+        let mut roots: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        roots.sort();
+        roots
+    }
+
+    #[test]
+    fn extraction_is_keyed_by_hash_and_reused_until_the_archive_changes() {
+        let dir = temp_dir("extract");
+        let archive = fixture_archive(&dir, "one");
+
+        let hash = verify::sha256_hex(&archive).unwrap();
+        let root = dir.join("bundles").join(&hash);
+        extract_to(&archive, &root).unwrap();
+        let pi = root.join("pi");
+        assert!(pi.is_file(), "extraction must produce the executable");
+        let output = Command::new(&pi).output().expect("executable bit preserved");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "one\n");
+        assert_eq!(extracted_roots(&dir.join("bundles")).len(), 1);
+
+        // A changed archive hashes differently, so it lands in a new root
+        std::fs::remove_file(&archive).unwrap();
+        let replaced = fixture_archive(&dir, "two");
+        let new_hash = verify::sha256_hex(&replaced).unwrap();
+        assert_ne!(hash, new_hash);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn failed_extractions_leave_no_root_behind() {
+        let dir = temp_dir("corrupt");
+        let archive = dir.join("bundle-standalone.tar.gz");
+        std::fs::write(&archive, "this is not a tar archive").unwrap();
+
+        let root = dir.join("bundles").join("deadbeef");
+        assert!(extract_to(&archive, &root).is_err());
+        assert!(!root.exists(), "a failed extraction must not look usable");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,255 @@
+//! Resolution cache for the wrapper.
+//!
+//! Walking parent directories and several node_modules layouts on every
+//! invocation is slow on network filesystems, so the winning resolution
+//! is cached in `~/.cache/package-installer/resolved.json`, keyed by
+//! the working directory that produced it. A cached entry is only used
+//! while the resolved file still exists with an unchanged mtime; any
+//! validation failure falls back to a full probe, which rewrites the
+//! entry. The cache is best-effort throughout: a missing, unwritable or
+//! corrupted cache file never breaks resolution.
+
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+/// How a cached entrypoint must be launched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CliKind {
+    /// A JS entrypoint run under a JS runtime.
+    Node,
+    /// A standalone executable run directly.
+    Executable,
+}
+
+/// One cached resolution result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResolution {
+    pub path: PathBuf,
+    pub kind: CliKind,
+    /// Seconds since the epoch when the resolved file was last modified.
+    pub mtime: u64,
+    /// Best-effort CLI version recorded at resolution time, so the
+    /// compatibility check never re-probes on a cache hit.
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// On-disk cache layout: one entry per working directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    entries: HashMap<String, CachedResolution>,
+}
+
+/// The wrapper's cache directory (`~/.cache/package-installer/`),
+/// honoring `XDG_CACHE_HOME`.
+pub fn cache_dir() -> Option<PathBuf> {
+    let cache_home = env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| env::home_dir().map(|home| home.join(".cache")))?;
+    Some(cache_home.join("package-installer"))
+}
+
+/// Cache file location for resolved CLIs.
+pub fn cache_file_path() -> Option<PathBuf> {
+    Some(cache_dir()?.join("resolved.json"))
+}
+
+/// The resolved file's mtime in whole seconds, or `None` when it cannot
+/// be read (which also invalidates any cached entry for it).
+pub fn file_mtime(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+/// Parses the cache file; a missing or corrupted file is an empty cache.
+fn load(file: &Path) -> CacheFile {
+    std::fs::read_to_string(file)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Returns the still-valid cached resolution for `cwd`, if any: the
+/// entry must exist and the file's mtime must match what was recorded.
+pub fn lookup_in(file: &Path, cwd: &Path) -> Option<CachedResolution> {
+    let cache = load(file);
+    let entry = cache.entries.get(&cwd.display().to_string())?;
+    if file_mtime(&entry.path) != Some(entry.mtime) {
+        return None;
+    }
+    Some(entry.clone())
+}
+
+/// Records `resolution` for `cwd`, silently doing nothing on any I/O
+/// failure.
+pub fn store_in(file: &Path, cwd: &Path, resolution: CachedResolution) {
+    let mut cache = load(file);
+    cache.entries.insert(cwd.display().to_string(), resolution);
+    let Ok(rendered) = serde_json::to_string_pretty(&cache) else {
+        return;
+    };
+    if let Some(parent) = file.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(file, rendered);
+}
+
+/// Cache lookup for the current working directory. A cache locked by
+/// a concurrent writer reads as a miss, so the invocation degrades to
+/// a full resolution instead of waiting.
+pub fn lookup(cwd: &Path) -> Option<CachedResolution> {
+    let file = cache_file_path()?;
+    let _lock = crate::lock::for_read(&file)?;
+    lookup_in(&file, cwd)
+}
+
+/// Records the winning resolution for the current working directory.
+pub fn store(cwd: &Path, path: &Path, kind: CliKind, version: Option<String>) {
+    let (Some(file), Some(mtime)) = (cache_file_path(), file_mtime(path)) else {
+        return;
+    };
+    // Serialize the read-modify-write against concurrent wrapper
+    // processes; losing the lock just means this run goes unrecorded
+    let Some(_lock) = crate::lock::for_write(&file) else {
+        return;
+    };
+    store_in(
+        &file,
+        cwd,
+        CachedResolution {
+            path: path.to_path_buf(),
+            kind,
+            mtime,
+            version,
+        },
+    );
+}
+
+/// Implements `pi wrapper clear-cache`; returns the process exit code.
+pub fn clear_command() -> i32 {
+    let Some(file) = cache_file_path() else {
+        eprintln!("Cannot determine the cache location");
+        return 1;
+    };
+    let _lock = crate::lock::for_write(&file);
+    match std::fs::remove_file(&file) {
+        Ok(()) => {
+            println!("Removed {}", file.display());
+            0
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("Cache is already empty ({})", file.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to remove {}: {}", file.display(), e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("pi-wrapper-cache-test-{}-{}", tag, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn stored_entries_are_returned_while_the_file_is_unchanged() {
+        let dir = temp_dir("hit");
+        let resolved = dir.join("index.js");
+        std::fs::write(&resolved, "// cli").unwrap();
+        let cache = dir.join("resolved.json");
+
+        store_in(
+            &cache,
+            Path::new("/project"),
+            CachedResolution {
+                path: resolved.clone(),
+                kind: CliKind::Node,
+                mtime: file_mtime(&resolved).unwrap(),
+                version: None,
+            },
+        );
+
+        let hit = lookup_in(&cache, Path::new("/project")).expect("expected a cache hit");
+        assert_eq!(hit.path, resolved);
+        assert_eq!(hit.kind, CliKind::Node);
+        // A different working directory is a different key
+        assert_eq!(lookup_in(&cache, Path::new("/other")), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn stale_and_deleted_entries_are_rejected() {
+        let dir = temp_dir("stale");
+        let resolved = dir.join("index.js");
+        std::fs::write(&resolved, "// cli").unwrap();
+        let cache = dir.join("resolved.json");
+
+        // Recorded mtime disagrees with the file on disk
+        store_in(
+            &cache,
+            Path::new("/project"),
+            CachedResolution {
+                path: resolved.clone(),
+                kind: CliKind::Node,
+                mtime: file_mtime(&resolved).unwrap() + 1,
+                version: None,
+            },
+        );
+        assert_eq!(lookup_in(&cache, Path::new("/project")), None);
+
+        // Resolved file no longer exists at all
+        store_in(
+            &cache,
+            Path::new("/project"),
+            CachedResolution {
+                path: dir.join("gone.js"),
+                kind: CliKind::Executable,
+                mtime: 0,
+                version: None,
+            },
+        );
+        assert_eq!(lookup_in(&cache, Path::new("/project")), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn corrupted_cache_files_read_as_empty_and_are_rewritten() {
+        let dir = temp_dir("corrupt");
+        let resolved = dir.join("index.js");
+        std::fs::write(&resolved, "// cli").unwrap();
+        let cache = dir.join("resolved.json");
+        std::fs::write(&cache, "{ not json").unwrap();
+
+        assert_eq!(lookup_in(&cache, Path::new("/project")), None);
+
+        // Storing over a corrupted file recovers it
+        store_in(
+            &cache,
+            Path::new("/project"),
+            CachedResolution {
+                path: resolved.clone(),
+                kind: CliKind::Node,
+                mtime: file_mtime(&resolved).unwrap(),
+                version: None,
+            },
+        );
+        assert!(lookup_in(&cache, Path::new("/project")).is_some());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,123 @@
+//! Tee of the child's output into a log file (`--wrapper-capture`).
+//!
+//! Long CI runs scroll the interesting output out of the buffer; with
+//! capture enabled the child's stdout and stderr still stream live to
+//! the terminal, while every line is also appended to a log file with
+//! a stream label and a timestamp relative to the child's start. The
+//! log path defaults to `./pi-run-<timestamp>.log` and is printed when
+//! the wrapper exits.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The log destination, set once from the flag.
+static TARGET: OnceLock<PathBuf> = OnceLock::new();
+
+/// Turns capture on, into `path` or the default log name.
+pub fn enable(path: Option<PathBuf>) {
+    let _ = TARGET.set(path.unwrap_or_else(default_path));
+}
+
+/// `./pi-run-<unix seconds>.log`, unique enough for consecutive runs.
+fn default_path() -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    PathBuf::from(format!("pi-run-{}.log", stamp))
+}
+
+/// The log path when capture is enabled.
+pub fn target() -> Option<&'static Path> {
+    TARGET.get().map(PathBuf::as_path)
+}
+
+/// Printed from `finish` so the path is the last thing on screen even
+/// after a noisy run.
+pub fn report() {
+    if let Some(path) = target() {
+        eprintln!(
+            "{}",
+            crate::ui::Style::for_stderr().ok(&format!("Output captured to {}", path.display()))
+        );
+    }
+}
+
+/// The per-line log prefix: elapsed time since the child started, then
+/// the stream label.
+fn prefix(label: &str, elapsed: Duration) -> String {
+    format!("[{:8.3}s {}] ", elapsed.as_secs_f64(), label)
+}
+
+/// Copies `reader` (one of the child's output pipes) line by line to
+/// `live` — unmodified, flushed per line so interleaving stays close to
+/// real time — and appends the labeled form to the shared log file.
+/// Lines are handled as raw bytes; non-UTF8 child output passes through
+/// untouched.
+pub fn tee(
+    reader: impl Read,
+    mut live: impl Write,
+    label: &str,
+    started: Instant,
+    log: &Mutex<File>,
+) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        let _ = live.write_all(&line);
+        let _ = live.flush();
+        if let Ok(mut log) = log.lock() {
+            let _ = log.write_all(prefix(label, started.elapsed()).as_bytes());
+            let _ = log.write_all(&line);
+            if !line.ends_with(b"\n") {
+                let _ = log.write_all(b"\n");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_carry_the_label_and_a_second_resolution_timestamp() {
+        let rendered = prefix("stdout", Duration::from_millis(1234));
+        assert_eq!(rendered, "[   1.234s stdout] ");
+        assert!(prefix("stderr", Duration::ZERO).contains("stderr"));
+    }
+
+    #[test]
+    fn teeing_writes_live_output_verbatim_and_labeled_log_lines() {
+        let dir = std::env::temp_dir().join(format!("pi-wrapper-capture-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("tee.log");
+        let log = Mutex::new(File::create(&log_path).unwrap());
+
+        let mut live = Vec::new();
+        tee(
+            &b"first\nsecond (no newline)"[..],
+            &mut live,
+            "stdout",
+            Instant::now(),
+            &log,
+        );
+
+        assert_eq!(live, b"first\nsecond (no newline)");
+        let logged = std::fs::read_to_string(&log_path).unwrap();
+        let lines: Vec<&str> = logged.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("stdout] first"), "got: {}", lines[0]);
+        assert!(lines[1].ends_with("second (no newline)"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
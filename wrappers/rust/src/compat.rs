@@ -0,0 +1,138 @@
+//! Wrapper/CLI semver compatibility policy.
+//!
+//! The wrapper forwards arguments blindly, so an old wrapper driving a
+//! much newer TypeScript CLI (or the reverse) fails with confusing
+//! downstream errors. The supported CLI range is embedded here; when
+//! the resolved CLI's version falls outside it, a loud warning goes to
+//! stderr, and `PI_WRAPPER_STRICT_VERSION=1` turns the warning into a
+//! refusal to run. A version that cannot be determined or parsed never
+//! blocks execution — the check is advisory first.
+
+use crate::debug::debug_log;
+use crate::ui;
+
+/// CLI versions this wrapper is known to work with (caret range).
+pub const SUPPORTED_CLI_RANGE: &str = "^3.0";
+
+/// How a resolved CLI version relates to the supported range.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Compatibility {
+    /// The version is inside the supported range.
+    Compatible,
+    /// The version parsed but falls outside the supported range.
+    Outside,
+    /// No version was available, or it did not parse as semver.
+    Unknown,
+}
+
+/// Parses `major.minor[.patch]` with an optional leading `v` and an
+/// ignored `-prerelease`/`+build` suffix. Pre-releases are compared by
+/// their core triple: `3.1.0-beta.2` counts as `3.1`.
+fn parse(version: &str) -> Option<(u64, u64)> {
+    let trimmed = version.trim();
+    let core = trimmed.strip_prefix('v').unwrap_or(trimmed);
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.');
+    let major: u64 = parts.next()?.parse().ok()?;
+    let minor: u64 = match parts.next() {
+        Some(minor) => minor.parse().ok()?,
+        None => 0,
+    };
+    if let Some(patch) = parts.next() {
+        patch.parse::<u64>().ok()?;
+    }
+    Some((major, minor))
+}
+
+/// Classifies `version` against [`SUPPORTED_CLI_RANGE`].
+pub fn assess(version: Option<&str>) -> Compatibility {
+    let Some((range_major, range_minor)) =
+        parse(SUPPORTED_CLI_RANGE.trim_start_matches('^'))
+    else {
+        return Compatibility::Unknown;
+    };
+    match version.and_then(parse) {
+        None => Compatibility::Unknown,
+        Some((major, minor)) if major == range_major && minor >= range_minor => {
+            Compatibility::Compatible
+        }
+        Some(_) => Compatibility::Outside,
+    }
+}
+
+/// Applies the policy: warns on stderr for out-of-range versions, and
+/// returns the offending version as an error when `strict` is set. An
+/// unknown version is always allowed, even in strict mode.
+pub fn enforce_with(version: Option<&str>, strict: bool) -> Result<(), String> {
+    match assess(version) {
+        Compatibility::Compatible => Ok(()),
+        Compatibility::Unknown => {
+            debug_log!("CLI version could not be determined; skipping the compatibility check");
+            Ok(())
+        }
+        Compatibility::Outside => {
+            let version = version.unwrap_or_default().to_string();
+            if strict {
+                return Err(version);
+            }
+            eprintln!(
+                "{}",
+                ui::Style::for_stderr().warn(&format!(
+                    "CLI version {} is outside this wrapper's supported range {} — flags may be rejected or silently ignored (set PI_WRAPPER_STRICT_VERSION=1 to refuse instead)",
+                    version, SUPPORTED_CLI_RANGE
+                ))
+            );
+            Ok(())
+        }
+    }
+}
+
+/// [`enforce_with`] driven by `PI_WRAPPER_STRICT_VERSION=1`.
+pub fn enforce(version: Option<&str>) -> Result<(), String> {
+    let strict = std::env::var("PI_WRAPPER_STRICT_VERSION")
+        .map(|v| v == "1")
+        .unwrap_or(false);
+    enforce_with(version, strict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_range_versions_are_compatible() {
+        for version in ["3.0.0", "3.19.0", "v3.2.1", "3.19.0-beta.2"] {
+            assert_eq!(
+                assess(Some(version)),
+                Compatibility::Compatible,
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn out_of_range_versions_are_flagged() {
+        for version in ["2.9.9", "4.0.0", "v4.0.0-rc.1"] {
+            assert_eq!(assess(Some(version)), Compatibility::Outside, "{version}");
+        }
+    }
+
+    #[test]
+    fn unparsable_versions_never_block_even_in_strict_mode() {
+        for version in [None, Some("main"), Some(""), Some("3.x")] {
+            assert_eq!(assess(version), Compatibility::Unknown, "{version:?}");
+            assert_eq!(enforce_with(version, true), Ok(()));
+        }
+    }
+
+    #[test]
+    fn strict_mode_refuses_out_of_range_versions() {
+        assert_eq!(enforce_with(Some("3.5.0"), true), Ok(()));
+        assert_eq!(
+            enforce_with(Some("4.0.0"), true),
+            Err("4.0.0".to_string())
+        );
+        // Non-strict only warns
+        assert_eq!(enforce_with(Some("4.0.0"), false), Ok(()));
+    }
+}
@@ -0,0 +1,144 @@
+//! `pi wrapper completions <shell>`: shell completion scripts.
+//!
+//! The wrapper's surface is described declaratively with clap here —
+//! purely to feed `clap_complete`; runtime argument handling stays in
+//! `main.rs` — and the downstream TypeScript CLI's commands are listed
+//! in a static table so first-level completion covers them too.
+
+use clap::{Arg, ArgAction, Command};
+use clap_complete::{generate, Shell};
+
+/// First-level commands implemented by the TypeScript CLI. Extend this
+/// table when the CLI grows a command; each entry is (name, about).
+const DOWNSTREAM_COMMANDS: &[(&str, &str)] = &[
+    ("add", "Add features to an existing project"),
+    ("ai", "AI-assisted project tooling"),
+    ("analyze", "Analyze the current project"),
+    ("auth", "Manage authentication"),
+    ("benchmark", "Run project benchmarks"),
+    ("cache", "Manage the CLI cache"),
+    ("check", "Check project health"),
+    ("clean", "Clean build artifacts"),
+    ("clone", "Clone a template repository"),
+    ("compare", "Compare frameworks or templates"),
+    ("create", "Create a new project from a template"),
+    ("deploy", "Deploy the project"),
+    ("docs", "Open the documentation"),
+    ("doctor", "Diagnose project problems"),
+    ("email", "Send project reports by email"),
+    ("env", "Manage environment files"),
+    ("explain", "Explain project structure"),
+    ("migrate", "Migrate between frameworks"),
+    ("security", "Run security checks"),
+    ("size", "Report bundle and dependency sizes"),
+    ("update", "Update project dependencies"),
+    ("upgrade-cli", "Upgrade the CLI itself"),
+];
+
+/// The wrapper-owned subcommands under `pi wrapper`.
+const WRAPPER_SUBCOMMANDS: &[(&str, &str)] = &[
+    ("doctor", "Explain CLI resolution without running anything"),
+    ("which", "Print the resolved CLI path"),
+    ("update", "Download the standalone CLI bundle"),
+    ("use", "Select an installed CLI version"),
+    ("list", "List installed CLI versions"),
+    ("clear-cache", "Remove the resolution cache"),
+    ("completions", "Generate shell completions"),
+];
+
+/// The wrapper's CLI surface, described for completion generation only.
+fn completion_model() -> Command {
+    let mut wrapper = Command::new("wrapper").about("Wrapper-owned commands");
+    for (name, about) in WRAPPER_SUBCOMMANDS {
+        wrapper = wrapper.subcommand(Command::new(*name).about(*about));
+    }
+
+    let mut root = Command::new("pi")
+        .about("Package Installer CLI")
+        .disable_help_subcommand(true)
+        .arg(Arg::new("wrapper-quiet").long("wrapper-quiet").action(ArgAction::SetTrue))
+        .arg(Arg::new("wrapper-verbose").long("wrapper-verbose").action(ArgAction::SetTrue))
+        .arg(Arg::new("wrapper-no-cache").long("wrapper-no-cache").action(ArgAction::SetTrue))
+        .arg(Arg::new("wrapper-allow-npx").long("wrapper-allow-npx").action(ArgAction::SetTrue))
+        .arg(
+            Arg::new("wrapper-non-interactive")
+                .long("wrapper-non-interactive")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(Arg::new("wrapper-no-local").long("wrapper-no-local").action(ArgAction::SetTrue))
+        .arg(Arg::new("wrapper-dry-run").long("wrapper-dry-run").action(ArgAction::SetTrue))
+        .arg(Arg::new("wrapper-version").long("wrapper-version").action(ArgAction::SetTrue))
+        .arg(Arg::new("cwd").short('C').long("cwd").value_name("DIR"))
+        .subcommand(wrapper)
+        .subcommand(Command::new("completions").about("Generate shell completions"));
+    for (name, about) in DOWNSTREAM_COMMANDS {
+        root = root.subcommand(Command::new(*name).about(*about));
+    }
+    root
+}
+
+/// Renders the completion script for `shell` into `out`.
+fn write_script(shell: Shell, out: &mut impl std::io::Write) {
+    let mut model = completion_model();
+    generate(shell, &mut model, "pi", out);
+}
+
+/// Implements `pi completions <shell>` (and the `pi wrapper
+/// completions` spelling); returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    let style = crate::ui::Style::for_stderr();
+    let Some(name) = args.first() else {
+        eprintln!(
+            "{}",
+            style.error("completions requires a shell argument: bash, zsh, fish, powershell or elvish")
+        );
+        return 1;
+    };
+    let Ok(shell) = name.parse::<Shell>() else {
+        eprintln!(
+            "{}",
+            style.error(&format!(
+                "Unknown shell '{}' (expected bash, zsh, fish, powershell or elvish)",
+                name
+            ))
+        );
+        return 1;
+    };
+    write_script(shell, &mut std::io::stdout().lock());
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_for(shell: Shell) -> String {
+        let mut out = Vec::new();
+        write_script(shell, &mut out);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn every_shell_script_mentions_the_downstream_commands() {
+        for shell in [
+            Shell::Bash,
+            Shell::Zsh,
+            Shell::Fish,
+            Shell::PowerShell,
+            Shell::Elvish,
+        ] {
+            let script = script_for(shell);
+            for command in ["create", "analyze", "update", "add"] {
+                assert!(script.contains(command), "{shell} script misses {command}");
+            }
+        }
+    }
+
+    #[test]
+    fn wrapper_subcommands_and_flags_are_completed() {
+        let script = script_for(Shell::Bash);
+        for token in ["wrapper", "which", "clear-cache", "--wrapper-dry-run", "--cwd"] {
+            assert!(script.contains(token), "bash script misses {token}");
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Native offline scaffolding for Rust templates.
+//!
+//! The wrapper's promise is "no Node needed for the bundled version" —
+//! but with neither node nor a JS CLI on the machine, `pi create`
+//! could not scaffold anything, even though the repository ships
+//! complete Rust templates. Those template trees are embedded into the
+//! binary at compile time, and when resolution has failed and the
+//! command is `create` with a `rust/<framework>` template, the wrapper
+//! scaffolds it entirely in Rust: copy the tree, rewrite the package
+//! name in `Cargo.toml`, print next steps. This is explicitly the
+//! offline fallback, not the full CLI — every banner says so — and it
+//! refuses to touch a non-empty target directory without `--force`.
+
+use std::path::Path;
+
+use include_dir::{include_dir, Dir, DirEntry};
+
+use crate::{report, ui};
+
+/// The repository's Rust web templates (`<framework>/template/...`).
+static RUST_TEMPLATES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/../../core-cli/templates/rust");
+
+/// Bevy lives under the game templates but is a Rust project all the
+/// same, so the offline fallback offers it with the rest.
+static BEVY_TEMPLATE: Dir<'_> =
+    include_dir!("$CARGO_MANIFEST_DIR/../../core-cli/templates/game/bevy/template");
+
+/// Every framework the fallback can scaffold, sorted.
+fn available() -> Vec<String> {
+    let mut names: Vec<String> = RUST_TEMPLATES
+        .dirs()
+        .filter_map(|dir| dir.path().file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+    names.push("bevy".to_string());
+    names.sort();
+    names
+}
+
+/// The embedded tree for `framework`, when it exists.
+fn template_root(framework: &str) -> Option<&'static Dir<'static>> {
+    if framework == "bevy" {
+        return Some(&BEVY_TEMPLATE);
+    }
+    RUST_TEMPLATES.get_dir(format!("{}/template", framework))
+}
+
+/// Copies `dir`'s entries under `target`, stripping the embedded path
+/// prefix so `axum/template/src/main.rs` lands at `src/main.rs`.
+fn extract_stripped(dir: &Dir<'_>, prefix: &Path, target: &Path) -> std::io::Result<()> {
+    for entry in dir.entries() {
+        let relative = entry
+            .path()
+            .strip_prefix(prefix)
+            .expect("embedded entries live under the template root");
+        match entry {
+            DirEntry::Dir(sub) => {
+                std::fs::create_dir_all(target.join(relative))?;
+                extract_stripped(sub, prefix, target)?;
+            }
+            DirEntry::File(file) => {
+                if let Some(parent) = relative.parent() {
+                    std::fs::create_dir_all(target.join(parent))?;
+                }
+                std::fs::write(target.join(relative), file.contents())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Tries to handle a failed `create` natively. `Some(exit_code)` means
+/// the fallback ran (or definitively failed); `None` means the normal
+/// resolution error should still be shown — with a hint that offline
+/// scaffolding exists when the command at least was `create`.
+pub fn offline_fallback(args: &[String]) -> Option<i32> {
+    if args.first().map(String::as_str) != Some("create") {
+        return None;
+    }
+    let style = ui::Style::for_stderr();
+    let mut name: Option<&str> = None;
+    let mut template: Option<&str> = None;
+    let mut force = false;
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--template" => template = iter.next().map(String::as_str),
+            "--force" => force = true,
+            other if other.starts_with('-') => {}
+            other => name = name.or(Some(other)),
+        }
+    }
+
+    let framework = match template.and_then(|value| value.strip_prefix("rust/")) {
+        Some(framework) => framework,
+        None => {
+            // Not something we can scaffold; point at what we could —
+            // but never corrupt machine-readable output with a hint
+            if report::json_enabled() {
+                return None;
+            }
+            eprintln!(
+                "{}",
+                style.warn(&format!(
+                    "The full CLI is unavailable, but the wrapper can scaffold Rust projects \
+                     offline: pi create <name> --template rust/<framework> (available: {})",
+                    available().join(", ")
+                ))
+            );
+            return None;
+        }
+    };
+    let Some(root) = template_root(framework) else {
+        report::WrapperMessage::Error {
+            message: format!(
+                "Unknown offline template rust/{} (available: {})",
+                framework,
+                available()
+                    .iter()
+                    .map(|name| format!("rust/{}", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+        .emit();
+        return Some(1);
+    };
+    let Some(name) = name else {
+        report::WrapperMessage::Error {
+            message: "create needs a project name: pi create <name> --template rust/<framework>"
+                .to_string(),
+        }
+        .emit();
+        return Some(1);
+    };
+
+    Some(scaffold(root, framework, name, force))
+}
+
+/// Copies the embedded tree to `<name>/` and personalizes it.
+fn scaffold(root: &'static Dir<'static>, framework: &str, name: &str, force: bool) -> i32 {
+    let style = ui::Style::for_stderr();
+    eprintln!(
+        "{}",
+        style.warn(&format!(
+            "Offline fallback: scaffolding rust/{} natively — this is not the full CLI, \
+             only the project files are created",
+            framework
+        ))
+    );
+
+    let target = Path::new(name);
+    let occupied = std::fs::read_dir(target)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if occupied && !force {
+        report::WrapperMessage::Error {
+            message: format!(
+                "{} already exists and is not empty (pass --force to scaffold into it anyway)",
+                target.display()
+            ),
+        }
+        .emit();
+        return 1;
+    }
+    if let Err(e) =
+        std::fs::create_dir_all(target).and_then(|_| extract_stripped(root, root.path(), target))
+    {
+        report::WrapperMessage::Error {
+            message: format!("Cannot write the template to {}: {}", target.display(), e),
+        }
+        .emit();
+        return 1;
+    }
+    rewrite_package_name(&target.join("Cargo.toml"), name);
+
+    println!("Created {}/ from the embedded rust/{} template", name, framework);
+    println!("Next steps:");
+    println!("  cd {}", name);
+    println!("  cargo run");
+    0
+}
+
+/// Replaces the template's placeholder package name with the project's.
+fn rewrite_package_name(manifest: &Path, name: &str) {
+    let Ok(contents) = std::fs::read_to_string(manifest) else {
+        return;
+    };
+    let mut replaced = false;
+    let rewritten: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            if !replaced && line.trim_start().starts_with("name = ") {
+                replaced = true;
+                format!("name = \"{}\"", name)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let _ = std::fs::write(manifest, rewritten.join("\n") + "\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file inside `root`, addressed relative to the template itself
+    /// (embedded entry paths stay relative to the include root).
+    fn template_file(
+        root: &'static Dir<'static>,
+        relative: &str,
+    ) -> Option<&'static include_dir::File<'static>> {
+        root.get_file(root.path().join(relative))
+    }
+
+    #[test]
+    fn every_promised_framework_is_embedded() {
+        let names = available();
+        for framework in [
+            "actix-web", "axum", "bevy", "gotham", "poem", "rocket", "rouille", "salvo",
+            "thruster", "tide", "warp",
+        ] {
+            assert!(names.contains(&framework.to_string()), "missing {framework}");
+            let root = template_root(framework).unwrap_or_else(|| panic!("no tree for {framework}"));
+            assert!(template_file(root, "Cargo.toml").is_some(), "{framework} has no manifest");
+            assert!(
+                template_file(root, "src/main.rs").is_some(),
+                "{framework} has no entrypoint"
+            );
+        }
+    }
+
+    #[test]
+    fn the_package_name_is_rewritten_once() {
+        let dir = std::env::temp_dir().join(format!("pi-wrapper-create-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = dir.join("Cargo.toml");
+        std::fs::write(
+            &manifest,
+            "[package]\nname = \"template\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { package = \"serde\", version = \"1\" }\n",
+        )
+        .unwrap();
+
+        rewrite_package_name(&manifest, "my-app");
+
+        let contents = std::fs::read_to_string(&manifest).unwrap();
+        assert!(contents.contains("name = \"my-app\""));
+        assert!(!contents.contains("name = \"template\""));
+        assert!(contents.contains("package = \"serde\""), "only [package] name changes");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,80 @@
+//! Debug logging for CLI resolution.
+//!
+//! Enabled with `PI_WRAPPER_DEBUG=1` or `--wrapper-verbose`; every line
+//! goes to stderr with a `[pi-wrapper]` prefix so it is easy to grep
+//! out of mixed output. Callers log through the [`debug_log!`] macro,
+//! which checks [`enabled`] before formatting anything, so the logging
+//! is free when it is off.
+//!
+//! A host binary can additionally register a [sink](set_sink) that
+//! every line is copied to regardless of the stderr switch — the
+//! wrapper uses this for its persistent log file — so one
+//! instrumentation point feeds both destinations.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Set when `--wrapper-verbose` was passed on the command line.
+static FORCED: AtomicBool = AtomicBool::new(false);
+
+/// Enables debug logging for the rest of this process.
+pub fn force_enable() {
+    FORCED.store(true, Ordering::Relaxed);
+}
+
+/// True when debug logging is active, via either the flag or
+/// `PI_WRAPPER_DEBUG=1`.
+pub fn enabled() -> bool {
+    static FROM_ENV: OnceLock<bool> = OnceLock::new();
+    FORCED.load(Ordering::Relaxed)
+        || *FROM_ENV.get_or_init(|| {
+            std::env::var("PI_WRAPPER_DEBUG")
+                .map(|value| value == "1")
+                .unwrap_or(false)
+        })
+}
+
+/// A destination lines can be copied to besides stderr.
+pub type Sink = Box<dyn Fn(&str) + Send + Sync>;
+
+/// An extra destination for every log line, independent of the stderr
+/// switch. At most one can be registered per process; later calls are
+/// ignored.
+static SINK: OnceLock<Sink> = OnceLock::new();
+
+/// Registers a sink that every log line is copied to from now on.
+pub fn set_sink(sink: Sink) {
+    let _ = SINK.set(sink);
+}
+
+/// True when a sink is registered, so lines must be formatted even
+/// with stderr logging off.
+pub fn sink_active() -> bool {
+    SINK.get().is_some()
+}
+
+/// Writes one line to every active destination. Use [`debug_log!`]
+/// instead of calling this directly so the message is never formatted
+/// while logging is off.
+pub fn log(message: std::fmt::Arguments<'_>) {
+    let line = message.to_string();
+    if enabled() {
+        eprintln!("[pi-wrapper] {}", line);
+    }
+    if let Some(sink) = SINK.get() {
+        sink(&line);
+    }
+}
+
+/// Logs a formatted line when debug logging is enabled or a sink is
+/// registered; expands to a plain flag check otherwise, with no
+/// formatting work behind it.
+#[macro_export]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        if $crate::debug::enabled() || $crate::debug::sink_active() {
+            $crate::debug::log(format_args!($($arg)*));
+        }
+    };
+}
+pub use crate::debug_log;
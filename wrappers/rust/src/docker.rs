@@ -0,0 +1,136 @@
+//! Container-based execution for machines without a usable runtime.
+//!
+//! On locked-down hosts where Node.js cannot be installed and downloaded
+//! binaries cannot run, `--wrapper-docker` (or `PI_WRAPPER_DOCKER=1`)
+//! hands the invocation to an official `node:lts-slim` container: the
+//! working directory is mounted at `/work`, a local `node_modules`
+//! install of the CLI is used when present, and `npx --yes` fetches the
+//! published package otherwise. A missing or stopped Docker daemon is
+//! reported as an error so the caller can fall back to the normal
+//! resolution chain.
+
+use std::env;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use package_installer_cli::debug::debug_log;
+use package_installer_cli::resolver::LOCAL_ENTRY_PATHS;
+
+use crate::runner;
+
+/// The image the CLI runs in; LTS matches the wrapper's own pinned
+/// runtime policy, slim keeps the first pull small.
+const IMAGE: &str = "node:lts-slim";
+
+/// True when `PI_WRAPPER_DOCKER=1` asks for container execution.
+pub fn requested_by_env() -> bool {
+    env::var("PI_WRAPPER_DOCKER").map(|v| v == "1").unwrap_or(false)
+}
+
+/// Probes for a reachable Docker daemon. `docker info` fails both when
+/// the binary is missing and when the daemon is not running, which are
+/// the same situation for our purposes.
+fn daemon_available() -> bool {
+    Command::new("docker")
+        .args(["info", "--format", "{{.ServerVersion}}"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// The container-side path of a local CLI install under `cwd`, when one
+/// exists. The same entrypoints the host resolver probes, but reached
+/// through the `/work` mount.
+fn mounted_local_entrypoint(cwd: &Path) -> Option<String> {
+    LOCAL_ENTRY_PATHS
+        .iter()
+        .find(|entry| cwd.join(entry).exists())
+        .map(|entry| format!("/work/{}", entry))
+}
+
+/// Runs the CLI inside the container, propagating its exit code. `Err`
+/// means the container never started (no daemon, no working directory)
+/// and the normal resolution chain should take over.
+pub fn run(cli_args: &[OsString]) -> Result<i32, String> {
+    if !daemon_available() {
+        return Err(
+            "Docker execution requested, but no running Docker daemon was found; \
+             trying the normal resolution chain instead"
+                .to_string(),
+        );
+    }
+    let cwd = env::current_dir()
+        .map_err(|e| format!("Cannot determine the directory to mount: {}", e))?;
+
+    let mut command = Command::new("docker");
+    command.arg("run").arg("--rm");
+    // Interactive prompts from the CLI need a TTY inside the container,
+    // but only when we actually have one to give
+    if std::io::stdin().is_terminal() {
+        command.arg("--interactive").arg("--tty");
+    }
+    // Files the CLI scaffolds must belong to the invoking user, not root
+    #[cfg(unix)]
+    {
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        command.arg("--user").arg(format!("{}:{}", uid, gid));
+    }
+    command
+        .arg("--volume")
+        .arg(mount_spec(&cwd))
+        .arg("--workdir")
+        .arg("/work")
+        .arg(IMAGE);
+    match mounted_local_entrypoint(&cwd) {
+        Some(entry) => {
+            debug_log!("docker: running mounted local install {}", entry);
+            command.arg("node").arg(entry);
+        }
+        None => {
+            debug_log!("docker: no local install mounted, using npx");
+            command.arg("npx").arg("--yes").arg("@0xshariq/package-installer");
+        }
+    }
+    command.args(cli_args);
+
+    crate::note_resolution_source("docker container");
+    crate::note_resolution_channel("docker");
+    runner::exec_or_run(command)
+        .map_err(|e| format!("Docker is available but `docker run` failed: {}", e))
+}
+
+/// The `--volume` argument mounting `cwd` at `/work`.
+fn mount_spec(cwd: &Path) -> String {
+    format!("{}:/work", cwd.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_working_directory_is_mounted_at_work() {
+        assert_eq!(mount_spec(Path::new("/home/dev/app")), "/home/dev/app:/work");
+    }
+
+    #[test]
+    fn a_local_install_wins_over_npx_inside_the_container() {
+        let dir = env::temp_dir().join(format!("pi-wrapper-docker-test-{}", std::process::id()));
+        let entry = dir.join("node_modules/@0xshariq/package-installer/dist/index.js");
+        std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+        std::fs::write(&entry, "// entrypoint\n").unwrap();
+
+        assert_eq!(
+            mounted_local_entrypoint(&dir).as_deref(),
+            Some("/work/node_modules/@0xshariq/package-installer/dist/index.js")
+        );
+        assert_eq!(mounted_local_entrypoint(Path::new("/nonexistent")), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
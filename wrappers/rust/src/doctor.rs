@@ -0,0 +1,233 @@
+//! `pi wrapper doctor`: explains CLI resolution without running anything.
+//!
+//! Walks every candidate the resolver would probe — local node_modules
+//! paths (including parent directories), global package manager roots,
+//! and the bundled executable locations — and reports which one would
+//! win, alongside the node version, OS/arch, and the environment
+//! variables that influence the wrapper. `--json` emits the same report
+//! as JSON so it can be attached to bug reports verbatim.
+
+use std::env;
+
+use serde::Serialize;
+
+use crate::config::ResolutionStep;
+
+/// Environment variables that change the wrapper's behavior, in the
+/// order they are reported.
+const RELEVANT_ENV_VARS: [&str; 9] = [
+    "PI_CLI_PATH",
+    "PI_WRAPPER_QUIET",
+    "PI_JS_RUNTIME",
+    "PI_WRAPPER_NO_EXEC",
+    "PI_WRAPPER_STRICT_VERSION",
+    "PI_WRAPPER_SKIP_NODE_CHECK",
+    "PI_WRAPPER_NO_LOCAL",
+    "PI_NO_EMOJI",
+    "NO_COLOR",
+];
+
+/// One path the resolver would probe, in priority order.
+#[derive(Debug, Serialize)]
+struct CandidateReport {
+    priority: usize,
+    step: ResolutionStep,
+    path: String,
+    exists: bool,
+    winner: bool,
+    /// True when the step is disabled (e.g. `--wrapper-no-local`): the
+    /// probe is listed but can never win.
+    skipped: bool,
+}
+
+/// Everything `pi wrapper doctor` reports.
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    wrapper_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    node_version: Option<String>,
+    /// Where the persistent wrapper log lives (None when unresolvable).
+    log_file: Option<String>,
+    cli_path_override: Option<String>,
+    env: Vec<(String, Option<String>)>,
+    candidates: Vec<CandidateReport>,
+}
+
+impl DoctorReport {
+    fn gather() -> DoctorReport {
+        let candidates = candidate_reports();
+        DoctorReport {
+            wrapper_version: env!("CARGO_PKG_VERSION"),
+            os: env::consts::OS,
+            arch: env::consts::ARCH,
+            node_version: crate::command_stdout("node", &["--version"]),
+            log_file: crate::logging::log_path().map(|path| path.display().to_string()),
+            cli_path_override: env::var("PI_CLI_PATH").ok(),
+            env: RELEVANT_ENV_VARS
+                .iter()
+                .map(|name| (name.to_string(), env::var(name).ok()))
+                .collect(),
+            candidates,
+        }
+    }
+
+    fn render_text(&self) -> String {
+        let mut lines = vec![
+            format!("pi wrapper doctor (wrapper {})", self.wrapper_version),
+            format!("platform: {} {}", self.os, self.arch),
+            format!(
+                "node: {}",
+                self.node_version.as_deref().unwrap_or("not found")
+            ),
+            format!(
+                "log file: {}",
+                self.log_file.as_deref().unwrap_or("not resolvable")
+            ),
+            String::new(),
+            "environment:".to_string(),
+        ];
+        for (name, value) in &self.env {
+            match value {
+                Some(value) => lines.push(format!("  {}={}", name, value)),
+                None => lines.push(format!("  {} (unset)", name)),
+            }
+        }
+        lines.push(String::new());
+
+        if let Some(override_path) = &self.cli_path_override {
+            lines.push(format!(
+                "PI_CLI_PATH override is active — the candidates below are bypassed: {}",
+                override_path
+            ));
+            lines.push(String::new());
+        }
+
+        lines.push("resolution candidates (in priority order):".to_string());
+        for candidate in &self.candidates {
+            let status = if candidate.skipped {
+                "skipped (disabled)"
+            } else if candidate.exists {
+                "exists"
+            } else {
+                "missing"
+            };
+            let marker = if candidate.winner { "  <-- winner" } else { "" };
+            lines.push(format!(
+                "  {:>2}. [{:<7}] {} — {}{}",
+                candidate.priority,
+                candidate.step.name(),
+                candidate.path,
+                status,
+                marker
+            ));
+        }
+        if !self.candidates.iter().any(|candidate| candidate.winner) {
+            lines.push("  (no candidate exists — the wrapper would report CLI not found)".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// Probes every candidate and marks the first existing one as winner.
+fn candidate_reports() -> Vec<CandidateReport> {
+    let mut winner_found = false;
+    crate::resolution_candidates()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (step, path))| {
+            let exists = path.exists();
+            let skipped = step == ResolutionStep::Local && crate::local_step_disabled();
+            let winner = exists && !skipped && !winner_found;
+            winner_found |= winner;
+            CandidateReport {
+                priority: index + 1,
+                step,
+                path: path.display().to_string(),
+                exists,
+                winner,
+                skipped,
+            }
+        })
+        .collect()
+}
+
+/// Runs the doctor command and returns the process exit code.
+pub fn run(json: bool) -> i32 {
+    let report = DoctorReport::gather();
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(rendered) => println!("{}", rendered),
+            Err(e) => {
+                eprintln!("Failed to serialize doctor report: {}", e);
+                return 1;
+            }
+        }
+    } else {
+        println!("{}", report.render_text());
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> DoctorReport {
+        DoctorReport {
+            wrapper_version: "2.5.0",
+            os: "linux",
+            arch: "x86_64",
+            node_version: Some("v20.0.0".to_string()),
+            log_file: Some("/home/u/.cache/package-installer/wrapper.log".to_string()),
+            cli_path_override: None,
+            env: vec![("PI_CLI_PATH".to_string(), None)],
+            candidates: vec![
+                CandidateReport {
+                    priority: 1,
+                    step: ResolutionStep::Local,
+                    path: "/project/node_modules/@0xshariq/package-installer/dist/index.js"
+                        .to_string(),
+                    exists: false,
+                    winner: false,
+                    skipped: false,
+                },
+                CandidateReport {
+                    priority: 2,
+                    step: ResolutionStep::Bundled,
+                    path: "/opt/pi/bundle-standalone/pi".to_string(),
+                    exists: true,
+                    winner: true,
+                    skipped: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn text_report_marks_the_winning_candidate() {
+        let text = sample_report().render_text();
+        let winner_line = text
+            .lines()
+            .find(|line| line.contains("<-- winner"))
+            .expect("report must mark a winner");
+        assert!(winner_line.contains("/opt/pi/bundle-standalone/pi"));
+        assert!(winner_line.contains("[bundled"));
+    }
+
+    #[test]
+    fn text_report_distinguishes_missing_candidates() {
+        let text = sample_report().render_text();
+        assert!(text.contains("missing"));
+        assert!(text.contains("(unset)"));
+    }
+
+    #[test]
+    fn json_report_round_trips_through_serde() {
+        let rendered = serde_json::to_string(&sample_report()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(parsed["wrapper_version"], "2.5.0");
+        assert_eq!(parsed["candidates"][1]["winner"], true);
+        assert_eq!(parsed["candidates"][1]["step"], "bundled");
+    }
+}
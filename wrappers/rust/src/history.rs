@@ -0,0 +1,334 @@
+//! Per-invocation command history.
+//!
+//! For each forwarded command the wrapper appends one JSON line to
+//! `~/.local/share/package-installer/history.jsonl` (honoring
+//! `PI_HOME`/`XDG_DATA_HOME`): timestamp, working directory, the
+//! forwarded argument vector, the resolution source, the exit code and
+//! the wall-clock duration. On the default Unix `exec()` path the
+//! entry is written just before the wrapper is replaced, so those two
+//! fields stay null — nothing survives to observe them. `pi wrapper
+//! history` reads it back, with `--last N`, `--failed` and `--json` to
+//! slice it.
+//!
+//! Recording is strictly best-effort — no I/O error may ever affect
+//! the command that just ran — `PI_WRAPPER_NO_HISTORY=1` disables it,
+//! arguments to sensitive-looking flags are redacted before they touch
+//! disk, and the file is size-capped with oldest-first truncation.
+
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{lock, ui, versions};
+
+/// Cap on the history file; once crossed, the oldest half goes.
+const MAX_BYTES: u64 = 512 * 1024;
+
+/// How many entries `pi wrapper history` shows by default.
+const DEFAULT_LAST: usize = 20;
+
+/// Flags whose values never reach disk, matched exactly or as
+/// `--flag=value`.
+const SENSITIVE: &[&str] = &["--token", "--password"];
+
+/// What a redacted value is written as.
+const REDACTED: &str = "<redacted>";
+
+/// One recorded invocation.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    /// Unix seconds when the command finished (or, for `exec()`ed
+    /// runs, when it started).
+    timestamp: u64,
+    /// Working directory the command ran from.
+    cwd: String,
+    /// The forwarded argument vector, after redaction.
+    args: Vec<String>,
+    /// Resolution channel the CLI came from (`local-npm`, ...).
+    source: String,
+    /// Exit code the command returned; `None` when the wrapper
+    /// `exec()`ed over itself and never saw the child finish.
+    exit_code: Option<i32>,
+    /// Wall-clock duration, absent for `exec()`ed runs for the same
+    /// reason.
+    duration_ms: Option<u64>,
+}
+
+/// History file location.
+fn history_path() -> Option<PathBuf> {
+    Some(versions::pi_home()?.join("history.jsonl"))
+}
+
+/// True unless `PI_WRAPPER_NO_HISTORY=1` (or any non-empty value)
+/// switched recording off.
+pub fn recording() -> bool {
+    std::env::var_os("PI_WRAPPER_NO_HISTORY").is_none_or(|value| value.is_empty())
+}
+
+/// Appends one entry for a finished command. Every failure mode —
+/// unresolvable home, lock contention, full disk — is silently
+/// ignored; history must never change an exit code.
+pub fn record(cli_args: &[OsString], source: &str, exit_code: i32, duration: Duration) {
+    append(cli_args, source, Some(exit_code), Some(duration.as_millis() as u64));
+}
+
+/// Appends one entry for a command the wrapper is about to `exec()`
+/// into. The process is replaced, so neither the exit code nor the
+/// duration can ever be observed: the entry records that the command
+/// ran, not how it went.
+pub fn record_exec(cli_args: &[OsString], source: &str) {
+    append(cli_args, source, None, None);
+}
+
+fn append(cli_args: &[OsString], source: &str, exit_code: Option<i32>, duration_ms: Option<u64>) {
+    if !recording() {
+        return;
+    }
+    let Some(path) = history_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let Some(_lock) = lock::for_write(&path) else {
+        return;
+    };
+    let entry = Entry {
+        timestamp: unix_now(),
+        cwd: std::env::current_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default(),
+        args: redact(cli_args),
+        source: source.to_string(),
+        exit_code,
+        duration_ms,
+    };
+    let Ok(mut line) = serde_json::to_string(&entry) else {
+        return;
+    };
+    line.push('\n');
+    let appended = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+    if appended.is_ok() {
+        truncate_oldest(&path);
+    }
+}
+
+/// The argument vector as recorded: values of [`SENSITIVE`] flags are
+/// replaced, whether passed as a separate argument or `--flag=value`.
+fn redact(cli_args: &[OsString]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(cli_args.len());
+    let mut hide_next = false;
+    for arg in cli_args {
+        let text = arg.to_string_lossy().into_owned();
+        if hide_next {
+            redacted.push(REDACTED.to_string());
+            hide_next = false;
+            continue;
+        }
+        if SENSITIVE.contains(&text.as_str()) {
+            hide_next = true;
+            redacted.push(text);
+            continue;
+        }
+        match SENSITIVE
+            .iter()
+            .find(|flag| text.starts_with(&format!("{}=", flag)))
+        {
+            Some(flag) => redacted.push(format!("{}={}", flag, REDACTED)),
+            None => redacted.push(text),
+        }
+    }
+    redacted
+}
+
+/// Keeps the file under [`MAX_BYTES`] by dropping whole lines from the
+/// front down to half the cap, so the rewrite does not happen on every
+/// run.
+fn truncate_oldest(path: &Path) {
+    let over = std::fs::metadata(path)
+        .map(|meta| meta.len() > MAX_BYTES)
+        .unwrap_or(false);
+    if !over {
+        return;
+    }
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let mut remaining = contents.as_str();
+    while remaining.len() as u64 > MAX_BYTES / 2 {
+        match remaining.split_once('\n') {
+            Some((_, rest)) => remaining = rest,
+            None => break,
+        }
+    }
+    let _ = std::fs::write(path, remaining);
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Unix seconds as `YYYY-MM-DDTHH:MM:SSZ` (Howard Hinnant's
+/// days-to-civil algorithm; no date crate needed for one format).
+pub(crate) fn iso_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60
+    )
+}
+
+/// `pi wrapper history [--last N] [--failed] [--json]`.
+pub fn run(args: &[String]) -> i32 {
+    let style = ui::Style::for_stderr();
+    let mut last = DEFAULT_LAST;
+    let mut failed_only = false;
+    let mut json = false;
+    let mut index = 0;
+    while index < args.len() {
+        match args[index].as_str() {
+            "--failed" => failed_only = true,
+            "--json" => json = true,
+            "--last" => {
+                index += 1;
+                match args.get(index).and_then(|value| value.parse::<usize>().ok()) {
+                    Some(count) if count > 0 => last = count,
+                    _ => {
+                        eprintln!("{}", style.error("--last requires a positive number"));
+                        return 1;
+                    }
+                }
+            }
+            other => {
+                eprintln!(
+                    "{}",
+                    style.error(&format!(
+                        "Unknown option {} (expected --last N, --failed, --json)",
+                        other
+                    ))
+                );
+                return 1;
+            }
+        }
+        index += 1;
+    }
+
+    let contents = history_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let entries: Vec<Entry> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|entry: &Entry| !failed_only || entry.exit_code.is_some_and(|code| code != 0))
+        .collect();
+    let skipped = entries.len().saturating_sub(last);
+    let selected = &entries[skipped..];
+    if selected.is_empty() {
+        if !json {
+            println!("No history recorded yet");
+        }
+        return 0;
+    }
+    for entry in selected {
+        if json {
+            // One JSON object per line, same shape as the file itself
+            println!("{}", serde_json::to_string(entry).unwrap_or_default());
+        } else {
+            // Entries from the exec() path have no exit code or
+            // duration to show
+            let exit = entry
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let duration = entry
+                .duration_ms
+                .map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(|| "-".to_string());
+            println!(
+                "{}  exit {:<3} {:>8}  pi {}  ({}, in {})",
+                iso_utc(entry.timestamp),
+                exit,
+                duration,
+                entry.args.join(" "),
+                entry.source,
+                entry.cwd
+            );
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redacted(args: &[&str]) -> Vec<String> {
+        redact(&args.iter().map(OsString::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn sensitive_flag_values_are_redacted_in_both_spellings() {
+        assert_eq!(
+            redacted(&["deploy", "--token", "secret", "--verbose"]),
+            ["deploy", "--token", "<redacted>", "--verbose"]
+        );
+        assert_eq!(
+            redacted(&["deploy", "--password=hunter2"]),
+            ["deploy", "--password=<redacted>"]
+        );
+        // Ordinary arguments are untouched
+        assert_eq!(redacted(&["analyze", "--detailed"]), ["analyze", "--detailed"]);
+    }
+
+    #[test]
+    fn truncation_drops_whole_lines_from_the_front() {
+        let dir = std::env::temp_dir().join(format!("pi-wrapper-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.jsonl");
+        let line = format!("{{\"n\":{}}}\n", "x".repeat(1024));
+        let lines: String = (0..((MAX_BYTES / 1024) + 16) as usize)
+            .map(|_| line.as_str())
+            .collect();
+        std::fs::write(&path, &lines).unwrap();
+
+        truncate_oldest(&path);
+
+        let kept = std::fs::read_to_string(&path).unwrap();
+        assert!((kept.len() as u64) <= MAX_BYTES / 2);
+        assert!(kept.ends_with('\n'), "only whole lines survive");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn timestamps_render_as_utc_iso() {
+        assert_eq!(iso_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(iso_utc(951_866_096), "2000-02-29T23:14:56Z");
+        assert_eq!(iso_utc(1_767_225_600), "2026-01-01T00:00:00Z");
+    }
+}
@@ -0,0 +1,253 @@
+//! Interactive auto-install offered when CLI resolution fails.
+//!
+//! Instead of always dumping install instructions and exiting, the
+//! wrapper can ask the user to install the CLI on the spot and then
+//! retry resolution. The prompt only appears in genuinely interactive
+//! sessions: both stdin and stderr must be TTYs, `CI` must not be set,
+//! and `--wrapper-non-interactive` must not be passed. All I/O and the
+//! install invocation are injected so the flow is unit-testable.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use crate::runner;
+
+/// What the user picked at the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallChoice {
+    /// `npm install -g @0xshariq/package-installer`
+    Global,
+    /// `npm install @0xshariq/package-installer` into the project.
+    Local,
+    Declined,
+}
+
+/// The npm invocation for a choice, or `None` when declined. A
+/// configured registry mirror is appended as `--registry <url>` so the
+/// install works behind firewalls that block the default registry.
+pub fn install_args(choice: InstallChoice, registry: Option<&str>) -> Option<Vec<String>> {
+    let mut args: Vec<String> = match choice {
+        InstallChoice::Global => vec!["install".into(), "-g".into(), "@0xshariq/package-installer".into()],
+        InstallChoice::Local => vec!["install".into(), "@0xshariq/package-installer".into()],
+        InstallChoice::Declined => return None,
+    };
+    if let Some(url) = registry {
+        args.push("--registry".into());
+        args.push(url.into());
+    }
+    Some(args)
+}
+
+/// Whether the prompt may be shown, from the individual signals. Pure
+/// so every combination can be tested.
+fn interactivity(non_interactive_flag: bool, ci: Option<&str>, stdin_tty: bool, stderr_tty: bool) -> bool {
+    if non_interactive_flag || !stdin_tty || !stderr_tty {
+        return false;
+    }
+    // Any truthy CI value means a bot is driving
+    !matches!(ci, Some(value) if !value.is_empty() && value != "0" && value != "false")
+}
+
+/// Whether the prompt may be shown in this process.
+pub fn interactive_allowed(non_interactive_flag: bool) -> bool {
+    let ci = std::env::var("CI").ok();
+    interactivity(
+        non_interactive_flag,
+        ci.as_deref(),
+        std::io::stdin().is_terminal(),
+        std::io::stderr().is_terminal(),
+    )
+}
+
+/// Asks whether (and where) to install. The first answer defaults to
+/// yes; when the project has a package.json a second question offers a
+/// local install, defaulting to global.
+fn prompt_for_choice(
+    input: &mut impl BufRead,
+    prompt_out: &mut impl Write,
+    has_package_json: bool,
+) -> std::io::Result<InstallChoice> {
+    write!(
+        prompt_out,
+        "Package Installer CLI not found. Install it now with npm? [Y/n] "
+    )?;
+    prompt_out.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "" | "y" | "yes" => {}
+        _ => return Ok(InstallChoice::Declined),
+    }
+
+    if !has_package_json {
+        return Ok(InstallChoice::Global);
+    }
+    write!(
+        prompt_out,
+        "Install globally or into this project (package.json found)? [G/l] "
+    )?;
+    prompt_out.flush()?;
+    let mut scope = String::new();
+    input.read_line(&mut scope)?;
+    match scope.trim().to_ascii_lowercase().as_str() {
+        "l" | "local" => Ok(InstallChoice::Local),
+        _ => Ok(InstallChoice::Global),
+    }
+}
+
+/// Drives the whole flow with injected I/O and install runner; returns
+/// true when an install completed and resolution should be retried.
+pub fn offer_and_install(
+    interactive: bool,
+    has_package_json: bool,
+    registry: Option<&str>,
+    input: &mut impl BufRead,
+    prompt_out: &mut impl Write,
+    run_install: impl FnOnce(&[String]) -> bool,
+) -> bool {
+    if !interactive {
+        return false;
+    }
+    let choice = match prompt_for_choice(input, prompt_out, has_package_json) {
+        Ok(choice) => choice,
+        Err(_) => return false,
+    };
+    match install_args(choice, registry) {
+        Some(npm_args) => run_install(&npm_args),
+        None => false,
+    }
+}
+
+/// Real entry point used by `main`: prompts on stderr, reads stdin, and
+/// runs npm with inherited stdio so its own progress output is visible.
+pub fn try_auto_install(non_interactive_flag: bool) -> bool {
+    let has_package_json = Path::new("package.json").exists();
+    offer_and_install(
+        interactive_allowed(non_interactive_flag),
+        has_package_json,
+        crate::registry::overridden(),
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+        |npm_args| {
+            let mut command = std::process::Command::new("npm");
+            command.args(npm_args);
+            matches!(runner::run_command(command), Ok(0))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn drive(
+        interactive: bool,
+        has_package_json: bool,
+        input: &str,
+        runner_result: bool,
+    ) -> (bool, Option<Vec<String>>, String) {
+        let mut reader = Cursor::new(input.as_bytes().to_vec());
+        let mut prompts = Vec::new();
+        let mut ran_with = None;
+        let retried = offer_and_install(
+            interactive,
+            has_package_json,
+            None,
+            &mut reader,
+            &mut prompts,
+            |npm_args| {
+                ran_with = Some(npm_args.to_vec());
+                runner_result
+            },
+        );
+        (retried, ran_with, String::from_utf8(prompts).unwrap())
+    }
+
+    #[test]
+    fn default_answer_installs_globally() {
+        let (retry, ran_with, prompts) = drive(true, false, "\n", true);
+        assert!(retry);
+        assert_eq!(
+            ran_with,
+            Some(vec![
+                "install".to_string(),
+                "-g".to_string(),
+                "@0xshariq/package-installer".to_string()
+            ])
+        );
+        assert!(prompts.contains("Install it now with npm? [Y/n]"));
+    }
+
+    #[test]
+    fn declining_runs_nothing() {
+        let (retry, ran_with, _) = drive(true, false, "n\n", true);
+        assert!(!retry);
+        assert_eq!(ran_with, None);
+    }
+
+    #[test]
+    fn local_install_is_offered_when_a_package_json_exists() {
+        let (retry, ran_with, prompts) = drive(true, true, "y\nl\n", true);
+        assert!(retry);
+        assert_eq!(
+            ran_with,
+            Some(vec![
+                "install".to_string(),
+                "@0xshariq/package-installer".to_string()
+            ])
+        );
+        assert!(prompts.contains("globally or into this project"));
+    }
+
+    #[test]
+    fn a_configured_registry_is_appended_to_the_install_command() {
+        assert_eq!(
+            install_args(InstallChoice::Global, Some("https://npm.corp.example")),
+            Some(vec![
+                "install".to_string(),
+                "-g".to_string(),
+                "@0xshariq/package-installer".to_string(),
+                "--registry".to_string(),
+                "https://npm.corp.example".to_string(),
+            ])
+        );
+        // Without an override the command is unchanged
+        assert_eq!(
+            install_args(InstallChoice::Local, None),
+            Some(vec![
+                "install".to_string(),
+                "@0xshariq/package-installer".to_string()
+            ])
+        );
+        assert_eq!(install_args(InstallChoice::Declined, Some("https://npm.corp.example")), None);
+    }
+
+    #[test]
+    fn failed_install_does_not_request_a_retry() {
+        let (retry, ran_with, _) = drive(true, false, "y\n", false);
+        assert!(!retry);
+        assert!(ran_with.is_some());
+    }
+
+    #[test]
+    fn non_interactive_contexts_never_prompt() {
+        let (retry, ran_with, prompts) = drive(false, false, "y\n", true);
+        assert!(!retry);
+        assert_eq!(ran_with, None);
+        assert!(prompts.is_empty());
+    }
+
+    #[test]
+    fn interactivity_requires_ttys_and_no_ci() {
+        assert!(interactivity(false, None, true, true));
+        assert!(!interactivity(true, None, true, true));
+        assert!(!interactivity(false, Some("1"), true, true));
+        assert!(!interactivity(false, Some("true"), true, true));
+        // Explicitly falsy CI values do not block the prompt
+        assert!(interactivity(false, Some("0"), true, true));
+        assert!(interactivity(false, Some(""), true, true));
+        assert!(!interactivity(false, None, false, true));
+        assert!(!interactivity(false, None, true, false));
+    }
+}
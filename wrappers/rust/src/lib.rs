@@ -0,0 +1,8 @@
+//! Library side of the wrapper: configuration, debug logging and the
+//! CLI resolution engine, kept free of process state so they can be
+//! tested without a real filesystem layout or a spawned child. The `pi`
+//! binary in `main.rs` wires them up to the actual environment.
+
+pub mod config;
+pub mod debug;
+pub mod resolver;
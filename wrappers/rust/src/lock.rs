@@ -0,0 +1,185 @@
+//! Advisory file locking for the shared per-user state: the resolution
+//! cache under `~/.cache/package-installer/` and the bundle/version
+//! store under `$PI_HOME`. Parallel wrapper processes — eight CI
+//! shards on one machine is normal — otherwise race on those writes
+//! and leave torn JSON or half-copied bundles behind.
+//!
+//! Locks live in a `.lock` file next to the resource. On Unix the lock
+//! itself is `flock(2)`, which the kernel releases when a crashed
+//! holder dies, so stale locks cannot occur. Elsewhere the lock is the
+//! exclusive creation of the file, and a lock file untouched for
+//! longer than [`STALE_AFTER`] is treated as abandoned and reclaimed.
+//!
+//! Writers wait up to [`WRITER_WAIT`]; readers only wait briefly
+//! ([`READER_WAIT`]) and then give up, so a contended cache degrades
+//! to a full un-cached resolution instead of blocking the invocation.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a writer waits for a competing lock before giving up.
+const WRITER_WAIT: Duration = Duration::from_secs(10);
+
+/// How long a reader waits before falling back to un-cached operation.
+const READER_WAIT: Duration = Duration::from_millis(400);
+
+/// Poll interval while waiting for a lock.
+const POLL: Duration = Duration::from_millis(25);
+
+/// Age beyond which a lock file counts as abandoned by a crashed
+/// process (only relevant where the lock is the file itself).
+#[cfg(not(unix))]
+const STALE_AFTER: Duration = Duration::from_secs(600);
+
+/// A held advisory lock; released on drop.
+#[derive(Debug)]
+pub struct FileLock {
+    /// Keeps the `flock` alive on Unix; closing releases it.
+    #[cfg(unix)]
+    _file: std::fs::File,
+    /// Where the lock file lives, so the fallback can remove it.
+    #[cfg(not(unix))]
+    path: PathBuf,
+}
+
+/// The lock file guarding `resource`: a `.lock`-suffixed sibling, so
+/// the resource itself (often a JSON file that gets renamed over, or a
+/// directory) is never the thing being locked.
+fn lock_path(resource: &Path) -> PathBuf {
+    let mut name = resource.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    resource.with_file_name(name)
+}
+
+/// Takes the exclusive lock for writing to `resource`, waiting up to
+/// [`WRITER_WAIT`]. `None` means another process held it for the whole
+/// wait — the caller should skip the write rather than corrupt it.
+pub fn for_write(resource: &Path) -> Option<FileLock> {
+    acquire(resource, WRITER_WAIT, true)
+}
+
+/// Takes a shared lock for reading `resource`, waiting only briefly.
+/// `None` means a writer is active — the caller should fall back to
+/// un-cached operation.
+pub fn for_read(resource: &Path) -> Option<FileLock> {
+    acquire(resource, READER_WAIT, false)
+}
+
+#[cfg(unix)]
+fn acquire(resource: &Path, wait: Duration, exclusive: bool) -> Option<FileLock> {
+    use std::os::fd::AsRawFd;
+
+    let path = lock_path(resource);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .ok()?;
+    let operation = if exclusive {
+        libc::LOCK_EX
+    } else {
+        libc::LOCK_SH
+    } | libc::LOCK_NB;
+
+    let deadline = std::time::Instant::now() + wait;
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+            return Some(FileLock { _file: file });
+        }
+        if std::time::Instant::now() >= deadline {
+            return None;
+        }
+        std::thread::sleep(POLL);
+    }
+}
+
+#[cfg(not(unix))]
+fn acquire(resource: &Path, wait: Duration, _exclusive: bool) -> Option<FileLock> {
+    let path = lock_path(resource);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let deadline = std::time::Instant::now() + wait;
+    loop {
+        match std::fs::OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(&path)
+        {
+            Ok(_) => return Some(FileLock { path }),
+            Err(_) => {
+                // A crashed holder never removes its lock file; reclaim
+                // it once it is clearly abandoned
+                let stale = std::fs::metadata(&path)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .is_some_and(|age| age > STALE_AFTER);
+                if stale {
+                    let _ = std::fs::remove_file(&path);
+                    continue;
+                }
+                if std::time::Instant::now() >= deadline {
+                    return None;
+                }
+                std::thread::sleep(POLL);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_resource(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pi-wrapper-lock-test-{}-{}", tag, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("resource.json")
+    }
+
+    #[test]
+    fn the_lock_file_sits_next_to_the_resource() {
+        assert_eq!(
+            lock_path(Path::new("/x/y/resolved.json")),
+            PathBuf::from("/x/y/resolved.json.lock")
+        );
+        assert_eq!(
+            lock_path(Path::new("/x/bundle-standalone")),
+            PathBuf::from("/x/bundle-standalone.lock")
+        );
+    }
+
+    #[test]
+    fn a_writer_blocks_other_writers_and_readers() {
+        let resource = temp_resource("writer");
+        let held = for_write(&resource).expect("uncontended lock");
+        // A reader gives up quickly while the writer holds the lock
+        assert!(for_read(&resource).is_none());
+        drop(held);
+        assert!(for_read(&resource).is_some());
+        std::fs::remove_dir_all(resource.parent().unwrap()).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn readers_share_the_lock_with_each_other() {
+        let resource = temp_resource("readers");
+        let first = for_read(&resource).expect("first reader");
+        let second = for_read(&resource).expect("second concurrent reader");
+        drop(first);
+        drop(second);
+        std::fs::remove_dir_all(resource.parent().unwrap()).ok();
+    }
+}
@@ -0,0 +1,116 @@
+//! Persistent diagnostic log.
+//!
+//! "The wrapper picked the wrong CLI yesterday" is impossible to
+//! investigate when every trace dies with the process. So unless
+//! `PI_WRAPPER_NO_LOG=1` is set, every run appends its resolution
+//! decisions, timings and errors to
+//! `~/.cache/package-installer/wrapper.log` as compact single lines
+//! (`<timestamp> [<pid>] <message>`).
+//!
+//! The lines are the same ones `--wrapper-verbose` prints: [`init`]
+//! registers this file as the [`debug`] module's sink, so one
+//! instrumentation point feeds stderr and the file alike. Writing is
+//! strictly best-effort — an unwritable cache directory just means no
+//! log. The file rotates to `wrapper.log.1` past [`MAX_BYTES`] and at
+//! most those two generations are kept.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use package_installer_cli::debug;
+
+use crate::{cache, history};
+
+/// Rotation threshold for the active log file.
+const MAX_BYTES: u64 = 1024 * 1024;
+
+/// Log file location, next to the resolution cache. Doctor prints it.
+pub fn log_path() -> Option<PathBuf> {
+    Some(cache::cache_dir()?.join("wrapper.log"))
+}
+
+/// True when `PI_WRAPPER_NO_LOG` (any non-empty value) opts out.
+fn logging_disabled() -> bool {
+    std::env::var_os("PI_WRAPPER_NO_LOG").is_some_and(|value| !value.is_empty())
+}
+
+/// Rotates `wrapper.log` to `wrapper.log.1` once it is over the
+/// threshold, replacing any previous generation.
+fn rotate_if_needed(path: &Path) {
+    let over = std::fs::metadata(path)
+        .map(|meta| meta.len() > MAX_BYTES)
+        .unwrap_or(false);
+    if over {
+        let _ = std::fs::rename(path, path.with_extension("log.1"));
+    }
+}
+
+/// Opens the log (rotating first) and registers it as the debug sink.
+/// Any failure along the way silently leaves file logging off; the
+/// command must never be affected.
+pub fn init() {
+    if logging_disabled() {
+        return;
+    }
+    let Some(path) = log_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    rotate_if_needed(&path);
+    let Ok(file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+    else {
+        return;
+    };
+    let file = Mutex::new(file);
+    let pid = std::process::id();
+    debug::set_sink(Box::new(move |line| {
+        if let Ok(mut file) = file.lock() {
+            let _ = writeln!(
+                file,
+                "{} [{}] {}",
+                history::iso_utc(history::unix_now()),
+                pid,
+                line
+            );
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_moves_the_oversized_log_aside_and_keeps_two_generations() {
+        let dir = std::env::temp_dir().join(format!("pi-wrapper-logging-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("wrapper.log");
+        let rotated = dir.join("wrapper.log.1");
+
+        // Under the threshold nothing moves
+        std::fs::write(&log, "small\n").unwrap();
+        rotate_if_needed(&log);
+        assert!(log.exists() && !rotated.exists());
+
+        // Over it, the file becomes generation .1
+        std::fs::write(&log, vec![b'x'; (MAX_BYTES + 1) as usize]).unwrap();
+        rotate_if_needed(&log);
+        assert!(!log.exists());
+        assert!(rotated.exists());
+
+        // A second rotation replaces the old generation — never a .2
+        std::fs::write(&log, vec![b'y'; (MAX_BYTES + 1) as usize]).unwrap();
+        rotate_if_needed(&log);
+        let kept = std::fs::read(&rotated).unwrap();
+        assert_eq!(kept[0], b'y');
+        assert!(!dir.join("wrapper.log.2").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,174 +1,2426 @@
 use std::env;
-use std::path::Path;
+use std::ffi::{OsStr, OsString};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use std::sync::OnceLock;
+
+mod aliases;
+mod bundle;
+mod cache;
+mod capture;
+mod compat;
+mod completions;
+mod create;
+mod docker;
+mod doctor;
+mod history;
+mod http;
+mod install;
+mod lock;
+mod logging;
+mod node_install;
+mod nodejs;
+mod notifier;
+mod registry;
+mod report;
+mod runner;
+mod templates;
+mod timing;
+mod ui;
+mod update;
+mod verify;
+mod versions;
+
+// Configuration, debug logging and the resolution engine live in the
+// library crate (`lib.rs`); this binary supplies the process state and
+// the real execution paths.
+use package_installer_cli::config::{self, ResolutionStep, WrapperConfig};
+use package_installer_cli::debug::{self, debug_log};
+use package_installer_cli::resolver::{self, ResolvedCli, Source};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    
-    // Check if the binary name contains "package-installer" or "pi" or if first argument is "pi"
-    let binary_name = &args[0];
-    let should_run_cli = binary_name.contains("package-installer") || 
-                        binary_name.contains("pi") || 
-                        (args.len() > 1 && args[1] == "pi");
-    
-    if should_run_cli {
-        // Get CLI arguments - pass all arguments after the binary name
-        let cli_args = if binary_name.contains("package-installer") || binary_name.contains("pi") {
-            args.iter().skip(1).cloned().collect::<Vec<String>>()
-        } else {
-            args.iter().skip(2).cloned().collect::<Vec<String>>()
-        };
-        
-        // Find and run the bundled CLI
-        match run_bundled_cli(&cli_args) {
-            Ok(exit_code) => {
-                std::process::exit(exit_code);
-            }
-            Err(e) => {
-                println!("❌ Failed to execute the CLI: {}", e);
-                print_usage_instructions();
+    let started = Instant::now();
+    // A child spawned purely to refresh the update-check state: do
+    // that and nothing else
+    if env::var_os(notifier::REFRESH_ENV).is_some() {
+        std::process::exit(notifier::refresh());
+    }
+
+    // File logging first, so even early failures leave a trace
+    logging::init();
+
+    // args_os: non-UTF8 arguments (e.g. Latin-1 file names) must
+    // reach the CLI byte-for-byte instead of panicking here
+    let args: Vec<OsString> = env::args_os().collect();
+
+    match forwarded_cli_args(&args) {
+        Some(cli_args) => {
+            let (cli_args, flags) = extract_wrapper_flags(with_default_flags(cli_args));
+            if let Some(message) = &flags.parse_error {
+                report::WrapperMessage::Error {
+                    message: message.clone(),
+                }
+                .emit();
                 std::process::exit(1);
             }
+            if flags.quiet {
+                WRAPPER_QUIET_FLAG.store(true, Ordering::Relaxed);
+            }
+            if flags.verbose {
+                debug::force_enable();
+            }
+            if flags.no_cache {
+                CACHE_DISABLED.store(true, Ordering::Relaxed);
+            }
+            if flags.allow_npx {
+                NPX_FALLBACK_ALLOWED.store(true, Ordering::Relaxed);
+            }
+            if flags.no_local {
+                LOCAL_STEP_DISABLED.store(true, Ordering::Relaxed);
+            }
+            if flags.dry_run {
+                DRY_RUN.store(true, Ordering::Relaxed);
+            }
+            if flags.json {
+                report::force_json();
+            }
+            if flags.timing {
+                timing::enable(started);
+            }
+            if let Some(path) = &flags.capture {
+                capture::enable(path.clone());
+            }
+            if let Some(name) = &flags.runtime {
+                match JsRuntime::from_name(name) {
+                    Some(runtime) => {
+                        let _ = FORCED_RUNTIME.set(runtime);
+                    }
+                    None => {
+                        report::WrapperMessage::Error {
+                            message: format!(
+                                "Invalid --runtime value {:?} (expected node, bun or deno)",
+                                name
+                            ),
+                        }
+                        .emit();
+                        std::process::exit(1);
+                    }
+                }
+            }
+            if let Some(raw) = &flags.timeout {
+                match parse_wrapper_timeout(raw) {
+                    Some(limit) => {
+                        let _ = WRAPPER_TIMEOUT.set(limit);
+                    }
+                    None => {
+                        report::WrapperMessage::Error {
+                            message: format!(
+                                "Invalid --wrapper-timeout value {:?} (expected e.g. 90s, 10m, 1h)",
+                                raw
+                            ),
+                        }
+                        .emit();
+                        std::process::exit(1);
+                    }
+                }
+            }
+            // `-C` changes directory before anything resolves, so local
+            // node_modules lookups happen relative to the target
+            if let Some(target_dir) = &flags.cwd {
+                if let Err(e) = env::set_current_dir(target_dir) {
+                    report::WrapperMessage::Error {
+                        message: format!(
+                            "Cannot change to directory {}: {}",
+                            target_dir.display(),
+                            e
+                        ),
+                    }
+                    .emit();
+                    std::process::exit(1);
+                }
+                debug_log!("running in {}", target_dir.display());
+            }
+            // Handled by the wrapper itself, before any forwarding, so
+            // it works even when no CLI is installed
+            if wrapper_version_requested(&cli_args) {
+                print_wrapper_version();
+                std::process::exit(0);
+            }
+            if let Some(json) = doctor_requested(&cli_args) {
+                std::process::exit(doctor::run(json || report::json_enabled()));
+            }
+            // Wrapper-owned subcommands are matched on their UTF-8 text;
+            // anything unmatched forwards untouched
+            let arg_at = |index: usize| cli_args.get(index).and_then(|arg| arg.to_str());
+            if cli_args.len() == 2 && arg_at(0) == Some("wrapper") && arg_at(1) == Some("clear-cache") {
+                std::process::exit(cache::clear_command());
+            }
+            if arg_at(0) == Some("wrapper") && arg_at(1) == Some("update") {
+                std::process::exit(update::run(&lossy_args(&cli_args[2..])));
+            }
+            if arg_at(0) == Some("wrapper") && arg_at(1) == Some("use") {
+                std::process::exit(versions::run_use(&lossy_args(&cli_args[2..])));
+            }
+            if cli_args.len() == 2 && arg_at(0) == Some("wrapper") && arg_at(1) == Some("list") {
+                std::process::exit(versions::run_list());
+            }
+            if arg_at(0) == Some("wrapper") && arg_at(1) == Some("install-node") {
+                std::process::exit(node_install::run(&lossy_args(&cli_args[2..])));
+            }
+            if arg_at(0) == Some("wrapper") && arg_at(1) == Some("history") {
+                std::process::exit(history::run(&lossy_args(&cli_args[2..])));
+            }
+            if arg_at(0) == Some("wrapper") && arg_at(1) == Some("completions") {
+                std::process::exit(completions::run(&lossy_args(&cli_args[2..])));
+            }
+            // Also answered at the top level: completion scripts must
+            // not wait for the Node CLI to boot, and the CLI has no
+            // `completions` command to shadow
+            if arg_at(0) == Some("completions") {
+                std::process::exit(completions::run(&lossy_args(&cli_args[1..])));
+            }
+            // `verify` answers at both spellings and at the top level:
+            // the CLI has no `verify` command to shadow, and checking a
+            // cached bundle must not depend on a working CLI
+            if cli_args.len() == 2 && arg_at(0) == Some("wrapper") && arg_at(1) == Some("verify") {
+                std::process::exit(run_verify());
+            }
+            if cli_args.len() == 1 && arg_at(0) == Some("verify") {
+                std::process::exit(run_verify());
+            }
+            if cli_args.len() == 2 && arg_at(0) == Some("wrapper") && arg_at(1) == Some("aliases") {
+                std::process::exit(aliases::run_list());
+            }
+            if arg_at(0) == Some("wrapper") && arg_at(1) == Some("which") {
+                let rest = &cli_args[2..];
+                std::process::exit(run_which(
+                    rest.iter().any(|arg| arg.to_str() == Some("--kind")),
+                    rest.iter().any(|arg| arg.to_str() == Some("--all")),
+                ));
+            }
+            // `--native` forces the embedded template catalog even
+            // with a full CLI installed, so the two can be compared
+            if matches!(arg_at(0), Some("list-templates") | Some("templates"))
+                && cli_args[1..].iter().any(|arg| arg.to_str() == Some("--native"))
+            {
+                std::process::exit(templates::run(&lossy_args(&cli_args[1..])));
+            }
+            // User aliases expand only now, past every wrapper-owned
+            // token, so they can reshape CLI commands but never the
+            // wrapper's
+            let cli_args = aliases::expand(cli_args);
+            note_forwarded_args(&cli_args);
+            // Container execution for hosts that have Docker but no
+            // usable runtime: explicit opt-in, and a missing daemon
+            // degrades to the normal chain instead of a dead end
+            if flags.docker || docker::requested_by_env() {
+                match docker::run(&cli_args) {
+                    Ok(exit_code) => finish(&cli_args, started, exit_code),
+                    Err(message) => {
+                        eprintln!("{}", ui::Style::for_stderr().warn(&message));
+                    }
+                }
+            }
+            // Find and run the bundled CLI
+            match run_bundled_cli(&cli_args) {
+                Ok(exit_code) => {
+                    finish(&cli_args, started, exit_code);
+                }
+                Err(e) => {
+                    // With no CLI anywhere, the template catalog can
+                    // still be served from the embedded manifest
+                    let first = cli_args.first().and_then(|arg| arg.to_str());
+                    if matches!(first, Some("list-templates") | Some("templates")) {
+                        std::process::exit(templates::run(&lossy_args(&cli_args[1..])));
+                    }
+                    // ... and `create` for a Rust template from the
+                    // embedded trees — before any install prompt
+                    if let Some(exit_code) = create::offline_fallback(&lossy_args(&cli_args)) {
+                        std::process::exit(exit_code);
+                    }
+                    // With no CLI anywhere, fetch the standalone bundle
+                    // from GitHub releases into the user bundle
+                    // directory and retry — never during a dry run, and
+                    // switched off by --no-download or the offline
+                    // environment knobs
+                    if !flags.dry_run && auto_download_allowed(flags.no_download) {
+                        match update::install_from_release(None) {
+                            Ok(_) => {
+                                if let Ok(exit_code) = run_bundled_cli(&cli_args) {
+                                    finish(&cli_args, started, exit_code);
+                                }
+                            }
+                            Err(message) => {
+                                eprintln!("{}", ui::Style::for_stderr().warn(&message));
+                            }
+                        }
+                    }
+                    // In interactive sessions, offer to install the CLI
+                    // and retry with the original command (never during
+                    // a dry run, which must not change anything)
+                    if !flags.dry_run && install::try_auto_install(flags.non_interactive) {
+                        if let Ok(exit_code) = run_bundled_cli(&cli_args) {
+                            finish(&cli_args, started, exit_code);
+                        }
+                    }
+                    e.to_message().emit();
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => {
+            eprintln!("Usage: pi [command] [options]");
+            eprintln!("This is a Rust wrapper for the Package Installer CLI.");
+            eprintln!("Use the binary name directly followed by your command to run the CLI.");
+            eprintln!("Example: pi create my-app");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Set when `--wrapper-quiet` was passed; the flag belongs to the
+/// wrapper and is stripped before the arguments reach the CLI.
+static WRAPPER_QUIET_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Set when `--wrapper-no-cache` was passed: resolution runs a full
+/// probe and neither reads nor writes the cache.
+static CACHE_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Set when `--wrapper-allow-npx` was passed: when every other
+/// resolution path fails, fall back to running the CLI through npx.
+static NPX_FALLBACK_ALLOWED: AtomicBool = AtomicBool::new(false);
+
+/// Set when `--wrapper-no-local` was passed: the local node_modules
+/// probes are removed from the resolution chain, so CI runs use exactly
+/// the global or bundled CLI regardless of project dependencies.
+static LOCAL_STEP_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// True when the local resolution step is disabled, by flag or
+/// `PI_WRAPPER_NO_LOCAL=1`.
+fn local_step_disabled() -> bool {
+    LOCAL_STEP_DISABLED.load(Ordering::Relaxed)
+        || env::var("PI_WRAPPER_NO_LOCAL").map(|v| v == "1").unwrap_or(false)
+}
+
+/// Whether a failed resolution may fetch the bundled CLI from GitHub
+/// releases: off under `--no-download` or `PI_WRAPPER_NO_DOWNLOAD=1`,
+/// and under the offline switches that already silence the update
+/// check (`PI_NO_UPDATE_CHECK`, any `CI` value), so test and CI runs
+/// never reach for the network by surprise.
+fn auto_download_allowed(no_download_flag: bool) -> bool {
+    let flagged = |name: &str| env::var_os(name).is_some_and(|value| !value.is_empty());
+    !(no_download_flag
+        || flagged("PI_WRAPPER_NO_DOWNLOAD")
+        || flagged("PI_NO_UPDATE_CHECK")
+        || flagged("CI"))
+}
+
+/// Set when `--wrapper-dry-run` was passed: resolution runs in full,
+/// but the winning invocation is printed instead of executed.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+fn dry_run_enabled() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Which resolution source won, recorded for the dry-run report.
+static RESOLUTION_SOURCE: OnceLock<String> = OnceLock::new();
+
+fn note_resolution_source(source: &str) {
+    let _ = RESOLUTION_SOURCE.set(source.to_string());
+}
+
+/// Runtime forced by `--runtime <name>`; validated in `main` so a typo
+/// fails before any resolution work, and checked ahead of
+/// `PI_JS_RUNTIME` and the `js_runtime` config key.
+static FORCED_RUNTIME: OnceLock<JsRuntime> = OnceLock::new();
+
+/// Deadline for the child CLI, from `--wrapper-timeout`; the
+/// `PI_WRAPPER_TIMEOUT` variable is the fallback, read in
+/// [`wrapper_timeout`].
+static WRAPPER_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// The effective child timeout: the flag wins over the environment,
+/// and an unparsable environment value warns once and is ignored
+/// rather than blocking the invocation.
+fn wrapper_timeout() -> Option<Duration> {
+    if let Some(limit) = WRAPPER_TIMEOUT.get() {
+        return Some(*limit);
+    }
+    static FROM_ENV: OnceLock<Option<Duration>> = OnceLock::new();
+    *FROM_ENV.get_or_init(|| {
+        let raw = env::var("PI_WRAPPER_TIMEOUT").ok()?;
+        let parsed = parse_wrapper_timeout(&raw);
+        if parsed.is_none() {
+            eprintln!(
+                "{}",
+                ui::Style::for_stderr().warn(&format!(
+                    "Ignoring invalid PI_WRAPPER_TIMEOUT value {:?} (expected e.g. 90s, 10m, 1h)",
+                    raw
+                ))
+            );
         }
+        parsed
+    })
+}
+
+/// Parses a timeout duration: a positive integer with an optional
+/// `s`/`m`/`h` suffix; a bare number means seconds.
+fn parse_wrapper_timeout(raw: &str) -> Option<Duration> {
+    let raw = raw.trim();
+    let (digits, multiplier) = if let Some(value) = raw.strip_suffix('s') {
+        (value, 1)
+    } else if let Some(value) = raw.strip_suffix('m') {
+        (value, 60)
+    } else if let Some(value) = raw.strip_suffix('h') {
+        (value, 3600)
     } else {
-        println!("Usage: pi [command] [options]");
-        println!("This is a Rust wrapper for the Package Installer CLI.");
-        println!("Use the binary name directly followed by your command to run the CLI.");
-        println!("Example: pi create my-app");
-        std::process::exit(1);
-    }
-}
-
-fn run_bundled_cli(cli_args: &[String]) -> Result<i32, Box<dyn std::error::Error>> {
-    // Try different bundled CLI locations in order of preference
-    
-    // 1. Check for local npm installation first (highest priority)
-    if let Ok(exit_code) = try_local_npm_installation(cli_args) {
-        return Ok(exit_code);
-    }
-    
-    // 2. Try bundled standalone pi executable relative to this binary
-    if let Ok(exit_code) = try_bundled_pi_executable(cli_args) {
-        return Ok(exit_code);
-    }
-    
-    // 3. Try bundled standalone pi executable in development location
-    if let Ok(exit_code) = try_bundled_pi_development(cli_args) {
-        return Ok(exit_code);
-    }
-    
-    Err("No CLI installation found".into())
-}
-
-fn try_local_npm_installation(cli_args: &[String]) -> Result<i32, Box<dyn std::error::Error>> {
-    let current_dir = env::current_dir()?;
-    
-    // Check for local npm installations
-    let local_paths = vec![
-        current_dir.join("node_modules").join("@0xshariq").join("package-installer").join("dist").join("index.js"),
-        current_dir.join("node_modules").join("package-installer-cli").join("dist").join("index.js"),
+        (raw, 1)
+    };
+    let seconds: u64 = digits.parse().ok()?;
+    (seconds > 0).then(|| Duration::from_secs(seconds * multiplier))
+}
+
+/// The install-channel token advertised to the child CLI via
+/// `PI_WRAPPER_RESOLUTION` (coarser than the dry-run source label).
+static RESOLUTION_CHANNEL: OnceLock<&'static str> = OnceLock::new();
+
+fn note_resolution_channel(channel: &'static str) {
+    let _ = RESOLUTION_CHANNEL.set(channel);
+}
+
+/// The forwarded argument vector, kept for the history entry written
+/// just before an `exec()` replaces the wrapper.
+static FORWARDED_ARGS: OnceLock<Vec<OsString>> = OnceLock::new();
+
+fn note_forwarded_args(cli_args: &[OsString]) {
+    let _ = FORWARDED_ARGS.set(cli_args.to_vec());
+}
+
+/// Writes the history entry for a command the wrapper is about to
+/// `exec()` into. The process is replaced, so this is the last chance
+/// to record anything — the exit code and duration go unrecorded.
+/// Called from [`runner::exec_or_run`].
+fn history_before_exec() {
+    let Some(cli_args) = FORWARDED_ARGS.get() else {
+        return;
+    };
+    let source = RESOLUTION_CHANNEL.get().copied().unwrap_or("unknown");
+    history::record_exec(cli_args, source);
+}
+
+/// Records the finished command in the history (best-effort) and exits
+/// with its code. Only reached on the spawn-and-wait paths — an
+/// `exec()`ed child never comes back here; its history entry is
+/// written by [`history_before_exec`] instead.
+fn finish(cli_args: &[OsString], started: Instant, exit_code: i32) -> ! {
+    let source = RESOLUTION_CHANNEL.get().copied().unwrap_or("unknown");
+    history::record(cli_args, source, exit_code, started.elapsed());
+    capture::report();
+    timing::report();
+    std::process::exit(exit_code);
+}
+
+/// Context variables for the child CLI, so it can tell it was launched
+/// through the wrapper (and over which channel) for its own update
+/// prompts and install telemetry. Values the user already exported are
+/// not overridden — see [`runner::exec_or_run`].
+fn wrapper_context() -> Vec<(&'static str, String)> {
+    let mut vars = vec![
+        ("PI_WRAPPER", "1".to_string()),
+        ("PI_WRAPPER_VERSION", env!("CARGO_PKG_VERSION").to_string()),
     ];
-    
-    for path in &local_paths {
+    if let Some(channel) = RESOLUTION_CHANNEL.get() {
+        vars.push(("PI_WRAPPER_RESOLUTION", channel.to_string()));
+    }
+    vars
+}
+
+/// Quotes `value` for copy-pasting into a shell; plain path-like values
+/// pass through unquoted.
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@+,".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Prints the dry-run report to stdout in its documented, stable
+/// format: a `source:` line naming the winning resolution source, a
+/// `program:` line, and one `arg:` line per argument, shell-quoted.
+fn print_dry_run_report(command: &Command) {
+    report::WrapperMessage::DryRun {
+        source: RESOLUTION_SOURCE
+            .get()
+            .map(String::as_str)
+            .unwrap_or("unknown")
+            .to_string(),
+        program: command.get_program().to_string_lossy().into_owned(),
+        args: command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+    }
+    .emit();
+}
+
+/// Flags that belong to the wrapper itself rather than the CLI.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct WrapperFlags {
+    quiet: bool,
+    verbose: bool,
+    no_cache: bool,
+    allow_npx: bool,
+    non_interactive: bool,
+    no_local: bool,
+    dry_run: bool,
+    /// `--wrapper-docker`: run the CLI inside a container.
+    docker: bool,
+    /// `--no-download`: never fetch the bundled CLI from GitHub
+    /// releases when resolution fails.
+    no_download: bool,
+    /// `--wrapper-json`: wrapper-originated output as JSON lines.
+    json: bool,
+    /// `--wrapper-timing`: per-stage timing breakdown on stderr.
+    timing: bool,
+    /// `--wrapper-capture[=<path>]`: tee child output into a log file.
+    /// Outer `Some` means capture was requested; the inner option is
+    /// the explicit path, when one was given.
+    capture: Option<Option<PathBuf>>,
+    /// Directory to run in (`-C` / `--cwd`), applied before resolution.
+    cwd: Option<PathBuf>,
+    /// Raw `--wrapper-timeout` value, validated in `main`.
+    timeout: Option<String>,
+    /// Raw `--runtime` value (node, bun or deno), validated in `main`.
+    runtime: Option<String>,
+    /// A wrapper flag was malformed (e.g. `-C` with no directory);
+    /// the message is ready to emit.
+    parse_error: Option<String>,
+}
+
+/// The clap definition of the wrapper's own argument prefix. Every
+/// wrapper flag is declared here; the single trailing positional
+/// captures the CLI's arguments verbatim. `allow_hyphen_values` makes
+/// anything clap does not recognize — including unknown `--flags` —
+/// start that capture instead of erroring, and once it starts nothing
+/// after the first CLI token is interpreted, so `pi create
+/// --wrapper-quiet` forwards the flag to the CLI untouched. A leading
+/// `--` ends wrapper parsing explicitly: `pi -- --wrapper-quiet`
+/// forwards the literal flag.
+fn wrapper_flag_parser() -> clap::Command {
+    use clap::{builder::ValueParser, Arg, ArgAction};
+    let flag = |name: &'static str| Arg::new(name).long(name).action(ArgAction::SetTrue);
+    clap::Command::new("pi")
+        // The binary name is already stripped by forwarded_cli_args
+        .no_binary_name(true)
+        // -h/--help and -V/--version belong to the CLI, not the wrapper
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .arg(flag("wrapper-quiet"))
+        .arg(flag("wrapper-verbose"))
+        .arg(flag("wrapper-no-cache"))
+        .arg(flag("wrapper-allow-npx"))
+        .arg(flag("wrapper-non-interactive"))
+        .arg(flag("wrapper-no-local"))
+        .arg(flag("wrapper-json"))
+        .arg(flag("wrapper-dry-run"))
+        .arg(flag("wrapper-docker"))
+        .arg(flag("no-download"))
+        .arg(flag("wrapper-timing"))
+        .arg(
+            Arg::new("wrapper-capture")
+                .long("wrapper-capture")
+                .action(ArgAction::Set)
+                // The path is optional and must be attached with `=`;
+                // a bare `--wrapper-capture` must not swallow the
+                // following CLI token
+                .num_args(0..=1)
+                .require_equals(true)
+                // The empty missing-value stands for "capture, default
+                // path"; os_string because the path_buf parser rejects
+                // an empty value outright
+                .default_missing_value("")
+                .value_parser(ValueParser::os_string()),
+        )
+        .arg(
+            Arg::new("wrapper-timeout")
+                .long("wrapper-timeout")
+                .action(ArgAction::Set)
+                // OsString, not String: a garbled value must reach the
+                // duration parser's error message, not die here
+                .value_parser(ValueParser::os_string()),
+        )
+        .arg(
+            Arg::new("runtime")
+                .long("runtime")
+                .action(ArgAction::Set)
+                // OsString for the same reason as --wrapper-timeout: a
+                // garbled name must reach the wrapper's own error text
+                .value_parser(ValueParser::os_string()),
+        )
+        .arg(
+            Arg::new("cwd")
+                .short('C')
+                .long("cwd")
+                .action(ArgAction::Set)
+                // The directory may itself be non-UTF8
+                .value_parser(ValueParser::path_buf()),
+        )
+        .arg(
+            Arg::new("cli")
+                .action(ArgAction::Append)
+                .num_args(0..)
+                .trailing_var_arg(true)
+                .allow_hyphen_values(true)
+                .value_parser(ValueParser::os_string()),
+        )
+}
+
+/// Maps a clap parse failure to the wrapper's own wording. Only the
+/// two value-taking flags can realistically fail; anything else falls
+/// back to clap's rendered message.
+fn wrapper_parse_error(error: &clap::Error) -> String {
+    use clap::error::{ContextKind, ContextValue};
+    let offending = match error.get(ContextKind::InvalidArg) {
+        Some(ContextValue::String(arg)) => arg.as_str(),
+        _ => "",
+    };
+    if error.kind() == clap::error::ErrorKind::InvalidValue {
+        if offending.starts_with("--wrapper-timeout") {
+            return "--wrapper-timeout requires a duration argument (e.g. 90s, 10m)".to_string();
+        }
+        if offending.starts_with("--cwd") || offending.starts_with("-C") {
+            return "-C/--cwd requires a directory argument".to_string();
+        }
+        if offending.starts_with("--runtime") {
+            return "--runtime requires a runtime name (node, bun or deno)".to_string();
+        }
+    }
+    error.to_string().lines().next().unwrap_or_default().to_string()
+}
+
+/// Prepends `default_flags` from the per-user config file, so flags on
+/// the real command line always win. Only the user file is consulted:
+/// project config is found relative to the directory `-C` may still
+/// change, and a broken file surfaces later through `wrapper_config`.
+fn with_default_flags(args: Vec<OsString>) -> Vec<OsString> {
+    let defaults = config::user_config_path()
+        .and_then(|path| WrapperConfig::load_file(&path).ok().flatten())
+        .and_then(|config| config.default_flags);
+    let Some(defaults) = defaults else {
+        return args;
+    };
+    let mut combined: Vec<OsString> = defaults.iter().map(OsString::from).collect();
+    combined.extend(args);
+    combined
+}
+
+/// Splits the forwarded arguments into wrapper-owned flags and the
+/// vector that goes to the CLI. Wrapper flags (`--wrapper-quiet`,
+/// `--wrapper-verbose`, ..., `--wrapper-timeout <dur>`, `-C <dir>` /
+/// `--cwd <dir>`) are recognized anywhere before the first CLI token;
+/// from that token on — or after a literal `--` — everything forwards
+/// verbatim, non-UTF8 bytes included.
+fn extract_wrapper_flags(args: Vec<OsString>) -> (Vec<OsString>, WrapperFlags) {
+    let mut flags = WrapperFlags::default();
+    let matches = match wrapper_flag_parser().try_get_matches_from(args) {
+        Ok(matches) => matches,
+        Err(e) => {
+            flags.parse_error = Some(wrapper_parse_error(&e));
+            return (Vec::new(), flags);
+        }
+    };
+    flags.quiet = matches.get_flag("wrapper-quiet");
+    flags.verbose = matches.get_flag("wrapper-verbose");
+    flags.no_cache = matches.get_flag("wrapper-no-cache");
+    flags.allow_npx = matches.get_flag("wrapper-allow-npx");
+    flags.non_interactive = matches.get_flag("wrapper-non-interactive");
+    flags.no_local = matches.get_flag("wrapper-no-local");
+    flags.json = matches.get_flag("wrapper-json");
+    flags.dry_run = matches.get_flag("wrapper-dry-run");
+    flags.docker = matches.get_flag("wrapper-docker");
+    flags.no_download = matches.get_flag("no-download");
+    flags.timing = matches.get_flag("wrapper-timing");
+    flags.capture = matches.get_one::<OsString>("wrapper-capture").map(|path| {
+        // The empty default stands for "capture, default path"
+        (!path.is_empty()).then(|| PathBuf::from(path))
+    });
+    flags.timeout = matches
+        .get_one::<OsString>("wrapper-timeout")
+        .map(|value| value.to_string_lossy().into_owned());
+    flags.runtime = matches
+        .get_one::<OsString>("runtime")
+        .map(|value| value.to_string_lossy().into_owned());
+    flags.cwd = matches.get_one::<PathBuf>("cwd").cloned();
+    let kept = matches
+        .get_many::<OsString>("cli")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    (kept, flags)
+}
+
+/// True when the invocation asks for the wrapper's own version report:
+/// `pi --wrapper-version` or `pi version --wrapper`. A bare
+/// `pi --version` keeps forwarding to the CLI as before.
+fn wrapper_version_requested(cli_args: &[OsString]) -> bool {
+    match cli_args.first().and_then(|arg| arg.to_str()) {
+        Some("--wrapper-version") => true,
+        Some("version") => cli_args.iter().any(|arg| arg.to_str() == Some("--wrapper")),
+        _ => false,
+    }
+}
+
+/// Detects `pi wrapper doctor [--json]`. The CLI has its own `doctor`
+/// command, so the wrapper-level diagnostic lives under the `wrapper`
+/// prefix and a plain `pi doctor` still forwards to the CLI. Returns
+/// whether `--json` was requested.
+fn doctor_requested(cli_args: &[OsString]) -> Option<bool> {
+    if cli_args.first().and_then(|arg| arg.to_str()) != Some("wrapper")
+        || cli_args.get(1).and_then(|arg| arg.to_str()) != Some("doctor")
+    {
+        return None;
+    }
+    Some(cli_args[2..].iter().any(|arg| arg.to_str() == Some("--json")))
+}
+
+/// Arguments to wrapper-owned subcommands (flags, shell names, version
+/// tags): all plain text, so lossy conversion is fine for them.
+fn lossy_args(args: &[OsString]) -> Vec<String> {
+    args.iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Prints the wrapper's crate version plus the path and version of the
+/// CLI the resolver would pick, without running it.
+fn print_wrapper_version() {
+    let cli_path = resolved_cli_path();
+    let cli_version = cli_path.as_deref().and_then(cli_version);
+    report::WrapperMessage::Version {
+        wrapper_version: env!("CARGO_PKG_VERSION"),
+        cli_path: cli_path.map(|path| path.display().to_string()),
+        cli_version,
+    }
+    .emit();
+}
+
+/// The entrypoint the resolver would choose, following the same
+/// precedence as `run_bundled_cli` but without executing anything.
+fn resolved_cli_path() -> Option<PathBuf> {
+    if let Some(override_path) = env::var_os("PI_CLI_PATH") {
+        let path = PathBuf::from(override_path);
+        let resolved = if path.is_absolute() {
+            path
+        } else {
+            env::current_dir().ok()?.join(path)
+        };
+        return resolved.exists().then_some(resolved);
+    }
+
+    let config = wrapper_config().ok()?;
+    for step in config.resolution_order() {
+        let found = match step {
+            ResolutionStep::Local => find_local_npm_installation(),
+            ResolutionStep::Global => find_global_npm_installation(),
+            ResolutionStep::Bundled => {
+                find_bundled_executable()
+                    .or_else(find_user_bundle)
+                    .or_else(find_bundled_development)
+            }
+        };
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+/// The `version` field of a package.json document, or `None` when the
+/// JSON is malformed or the field is missing.
+fn package_json_version(contents: &str) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct PackageJson {
+        version: Option<String>,
+    }
+    serde_json::from_str::<PackageJson>(contents).ok()?.version
+}
+
+/// Best-effort version of the resolved CLI: the `version` field of the
+/// package.json adjacent to a JS entrypoint (searched a few directories
+/// up from e.g. `dist/index.js`), or the first line of `--version`
+/// output for a standalone executable.
+fn cli_version(path: &Path) -> Option<String> {
+    if is_js_entrypoint(path) {
+        let mut dir = path.parent()?;
+        for _ in 0..3 {
+            let package_json = dir.join("package.json");
+            if let Ok(contents) = std::fs::read_to_string(&package_json) {
+                return package_json_version(&contents);
+            }
+            dir = dir.parent()?;
+        }
+        None
+    } else {
+        command_stdout(path.to_str()?, &["--version"])
+            .map(|output| output.lines().next().unwrap_or("").trim().to_string())
+    }
+}
+
+/// True when the executable's file stem identifies it as the CLI.
+///
+/// Only the stem of `args[0]` is considered (so `pi`, `pi.exe`,
+/// `package-installer` and symlinks with those names match, case
+/// insensitively), never substrings — a binary named `pip-helper` or a
+/// path like `/home/pills/tools/wrapper` must not enter CLI mode.
+fn binary_invokes_cli(binary_name: &OsStr) -> bool {
+    Path::new(binary_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| {
+            stem.eq_ignore_ascii_case("pi")
+                || stem.eq_ignore_ascii_case("package-installer")
+                || stem.eq_ignore_ascii_case("package-installer-cli")
+        })
+        .unwrap_or(false)
+}
+
+/// Decides whether this invocation should run the CLI and, if so, which
+/// arguments to forward. The amount to skip is derived from the same
+/// decision as the detection itself so we never drop or duplicate a user
+/// argument: one for the binary name, plus one more when the CLI is
+/// invoked as a `pi` subcommand of a differently-named wrapper.
+fn forwarded_cli_args(args: &[OsString]) -> Option<Vec<OsString>> {
+    let binary_name = args.first()?;
+    if binary_invokes_cli(binary_name) {
+        Some(args[1..].to_vec())
+    } else if args.len() > 1 && args[1].to_str() == Some("pi") {
+        Some(args[2..].to_vec())
+    } else {
+        None
+    }
+}
+
+/// Wrapper configuration, loaded once per process. Config file errors
+/// are preserved so every caller reports the same readable message.
+fn wrapper_config() -> Result<&'static WrapperConfig, String> {
+    static CONFIG: std::sync::OnceLock<Result<WrapperConfig, String>> = std::sync::OnceLock::new();
+    CONFIG
+        .get_or_init(WrapperConfig::load)
+        .as_ref()
+        .map_err(|e| e.clone())
+}
+
+/// True when wrapper status banners should be printed.
+///
+/// Banners go to stderr so stdout stays a pure passthrough of the
+/// child's output; they are suppressed by `--wrapper-quiet`,
+/// `PI_WRAPPER_QUIET=1`, the config `quiet` key, or automatically when
+/// stderr is not a terminal. `PI_WRAPPER_QUIET=0` forces them on, which
+/// also lets scripts capture them from a pipe.
+fn banners_enabled() -> bool {
+    if WRAPPER_QUIET_FLAG.load(Ordering::Relaxed) {
+        return false;
+    }
+    match env::var("PI_WRAPPER_QUIET").as_deref() {
+        Ok("1") => return false,
+        Ok("0") => return true,
+        _ => {}
+    }
+    let config_quiet = wrapper_config()
+        .ok()
+        .and_then(|config| config.quiet)
+        .unwrap_or(false);
+    if config_quiet {
+        return false;
+    }
+    std::io::stderr().is_terminal()
+}
+
+/// Prints a wrapper status line (with a success marker) to stderr
+/// unless banners are suppressed.
+fn status_message(message: &str) {
+    if banners_enabled() {
+        eprintln!("{}", ui::Style::for_stderr().ok(message));
+    }
+}
+
+/// Why one resolution source could not be used. Spawn and runtime
+/// failures are kept distinct from "not installed" so an existing
+/// install with a broken runtime is reported as exactly that, not as a
+/// missing CLI.
+#[derive(Debug)]
+enum ResolutionError {
+    /// Nothing found at any probed path for this source.
+    NotPresent { source: &'static str },
+    /// A cwd-relative source that could not be probed because the
+    /// working directory no longer exists.
+    SkippedNoCwd { source: &'static str },
+    /// `PI_CLI_PATH` named a file that does not exist.
+    OverrideMissing { path: PathBuf },
+    /// The entrypoint exists but no JS runtime could be selected.
+    RuntimeUnavailable { path: PathBuf, reason: String },
+    /// The entrypoint exists but launching it failed.
+    SpawnFailed { path: PathBuf, reason: String },
+    /// The executable exists but has no execute bit and the wrapper
+    /// could not restore it (e.g. a read-only filesystem).
+    NotExecutable { path: PathBuf, reason: String },
+    /// The bundled executable failed checksum verification.
+    Verification(String),
+    /// The resolved CLI's version is outside the supported range and
+    /// `PI_WRAPPER_STRICT_VERSION=1` is set.
+    IncompatibleVersion { version: String },
+    /// The wrapper configuration could not be loaded.
+    Config(String),
+}
+
+impl std::fmt::Display for ResolutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolutionError::NotPresent { source } => write!(f, "{}: not present", source),
+            ResolutionError::SkippedNoCwd { source } => write!(
+                f,
+                "{}: skipped (working directory unavailable)",
+                source
+            ),
+            ResolutionError::OverrideMissing { path } => write!(
+                f,
+                "PI_CLI_PATH points to {} which does not exist",
+                path.display()
+            ),
+            ResolutionError::RuntimeUnavailable { path, reason } => write!(
+                f,
+                "{} exists but no runtime can launch it: {}",
+                path.display(),
+                reason
+            ),
+            ResolutionError::SpawnFailed { path, reason } => {
+                write!(f, "{} exists but failed to launch: {}", path.display(), reason)
+            }
+            ResolutionError::NotExecutable { path, reason } => write!(
+                f,
+                "{} is not executable and the wrapper could not fix it ({}); run `chmod +x {}`",
+                path.display(),
+                reason,
+                path.display()
+            ),
+            ResolutionError::Verification(reason) => write!(f, "{}", reason),
+            ResolutionError::IncompatibleVersion { version } => write!(
+                f,
+                "CLI version {} is outside this wrapper's supported range {} (unset PI_WRAPPER_STRICT_VERSION to run anyway)",
+                version,
+                compat::SUPPORTED_CLI_RANGE
+            ),
+            ResolutionError::Config(reason) => write!(f, "configuration error: {}", reason),
+        }
+    }
+}
+
+/// Every attempt the resolver made, reported together when all of them
+/// fail so the user sees what was actually tried and why.
+#[derive(Debug)]
+struct ResolutionFailure {
+    attempts: Vec<ResolutionError>,
+}
+
+impl std::fmt::Display for ResolutionFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no CLI installation could be used; attempts:")?;
+        for attempt in &self.attempts {
+            write!(f, "\n  - {}", attempt)?;
+        }
+        Ok(())
+    }
+}
+
+impl ResolutionFailure {
+    /// The report form shared between the human and JSON renderings.
+    fn to_message(&self) -> report::WrapperMessage {
+        report::WrapperMessage::ResolutionError {
+            message: "no CLI installation could be used".to_string(),
+            attempts: self.attempts.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl From<ResolutionError> for ResolutionFailure {
+    fn from(error: ResolutionError) -> ResolutionFailure {
+        ResolutionFailure {
+            attempts: vec![error],
+        }
+    }
+}
+
+/// Records the winning resolution for future invocations (unless the
+/// cache is disabled for this run) and returns the CLI version it
+/// recorded, so the compatibility check never probes twice.
+fn remember_resolution(path: &Path, kind: cache::CliKind) -> Option<String> {
+    let version = cli_version(path);
+    if !CACHE_DISABLED.load(Ordering::Relaxed) && !bundle::is_extracted_path(path) {
+        if let Ok(cwd) = env::current_dir() {
+            cache::store(&cwd, path, kind, version.clone());
+        }
+    }
+    version
+}
+
+/// Applies the wrapper/CLI compatibility policy to the resolved CLI's
+/// version: out-of-range versions warn (or refuse, under
+/// `PI_WRAPPER_STRICT_VERSION=1`), and an undetermined version never
+/// blocks execution.
+fn ensure_supported_cli(version: Option<&str>) -> Result<(), ResolutionError> {
+    // Every execution path funnels its resolved version through here,
+    // which makes it the one spot for the update notice too
+    notifier::maybe_notify(version);
+    compat::enforce(version).map_err(|version| ResolutionError::IncompatibleVersion { version })
+}
+
+/// The working directory, or `None` when it has been deleted or made
+/// inaccessible from under the wrapper (a stale shell after `rm -rf`).
+/// Resolution then skips the cwd-relative sources and carries on with
+/// the executable-relative and override ones instead of bailing out.
+fn usable_current_dir() -> Option<PathBuf> {
+    match env::current_dir() {
+        Ok(cwd) => Some(cwd),
+        Err(e) => {
+            debug_log!("working directory unavailable: {} — skipping cwd-relative sources", e);
+            None
+        }
+    }
+}
+
+/// Checks whether a candidate path exists, logging the probe (path,
+/// result, duration) when debug logging is on.
+fn probe_exists(path: &Path) -> bool {
+    if !debug::enabled() {
+        return path.exists();
+    }
+    let start = std::time::Instant::now();
+    let exists = path.exists();
+    debug_log!(
+        "probe {} — {} ({:?})",
+        path.display(),
+        if exists { "exists" } else { "missing" },
+        start.elapsed()
+    );
+    exists
+}
+
+/// The resolution engine configured from the real process state:
+/// working directory, this binary's locations, the configured order
+/// and the env-derived knobs. Global candidates are passed as a
+/// function so package managers are only queried when the global step
+/// is actually reached.
+fn real_resolver() -> resolver::Resolver {
+    let mut resolver = resolver::Resolver::new(usable_current_dir());
+    resolver.exe_dirs = executable_dirs();
+    resolver.user_bundle_dir = update::user_bundle_dir();
+    resolver.global_candidates = global_candidate_paths;
+    resolver.bundle_fallback = bundle::extracted_candidates;
+    resolver.order = wrapper_config()
+        .map(|config| config.resolution_order())
+        .unwrap_or_else(|_| WrapperConfig::DEFAULT_ORDER.to_vec());
+    resolver.max_depth = max_walk_depth();
+    resolver.local_disabled = local_step_disabled();
+    resolver
+}
+
+/// The walk's real [`resolver::Runner`]: prints the per-source banner,
+/// verifies bundles, records the winner in the cache, applies the
+/// compatibility policy, then hands off to process execution.
+struct ProcessRunner;
+
+impl resolver::Runner for ProcessRunner {
+    type Error = ResolutionError;
+
+    fn run(
+        &mut self,
+        source: Source,
+        cli: &ResolvedCli,
+        args: &[OsString],
+    ) -> Result<i32, ResolutionError> {
+        note_resolution_channel(source.channel());
+        match (source, cli) {
+            (Source::LocalNpm, ResolvedCli::NodeScript(path)) => {
+                debug_log!("winner: {} (local)", path.display());
+                note_resolution_source(source.label());
+                let version = remember_resolution(path, cache::CliKind::Node);
+                ensure_supported_cli(version.as_deref())?;
+                status_message("Using locally installed CLI from node_modules");
+                run_node_cli(path, args)
+            }
+            (Source::LocalBinShim, ResolvedCli::Executable(shim)) => {
+                debug_log!("winner: {} (local .bin shim)", shim.display());
+                note_resolution_source(source.label());
+                let version = remember_resolution(shim, cache::CliKind::Executable);
+                ensure_supported_cli(version.as_deref())?;
+                status_message("Using the CLI's node_modules/.bin shim");
+                run_pi_executable(shim, args)
+            }
+            (Source::YarnPnp, ResolvedCli::PnpManifest(manifest)) => run_yarn_pnp(manifest, args),
+            (Source::GlobalNpm, ResolvedCli::NodeScript(entry)) => {
+                debug_log!("winner: {} (global)", entry.display());
+                note_resolution_source(source.label());
+                let version = remember_resolution(entry, cache::CliKind::Node);
+                ensure_supported_cli(version.as_deref())?;
+                status_message(&format!(
+                    "Using globally installed CLI from {}",
+                    entry.display()
+                ));
+                run_node_cli(entry, args)
+            }
+            (Source::BundledExecutable, ResolvedCli::Executable(path)) => {
+                debug_log!("winner: {} (bundled)", path.display());
+                note_resolution_source(source.label());
+                verify::verify_bundle(path).map_err(ResolutionError::Verification)?;
+                let version = remember_resolution(path, cache::CliKind::Executable);
+                ensure_supported_cli(version.as_deref())?;
+                status_message("Using bundled standalone pi executable");
+                run_pi_executable(path, args)
+            }
+            (Source::UserBundle, ResolvedCli::Executable(path)) => {
+                debug_log!("winner: {} (user bundle)", path.display());
+                note_resolution_source(source.label());
+                verify::verify_bundle(path).map_err(ResolutionError::Verification)?;
+                let version = remember_resolution(path, cache::CliKind::Executable);
+                ensure_supported_cli(version.as_deref())?;
+                status_message("Using downloaded standalone pi executable");
+                run_pi_executable(path, args)
+            }
+            (Source::DevBundle, ResolvedCli::Executable(path)) => {
+                debug_log!("winner: {} (bundled development)", path.display());
+                note_resolution_source(source.label());
+                verify::verify_bundle(path).map_err(ResolutionError::Verification)?;
+                let version = remember_resolution(path, cache::CliKind::Executable);
+                ensure_supported_cli(version.as_deref())?;
+                status_message("Using bundled standalone pi executable (development)");
+                run_pi_executable(path, args)
+            }
+            // Every source produces exactly one ResolvedCli variant;
+            // any other pairing is a resolver bug.
+            (source, cli) => unreachable!("source {:?} produced {:?}", source, cli),
+        }
+    }
+}
+
+fn run_bundled_cli(cli_args: &[OsString]) -> Result<i32, ResolutionFailure> {
+    // PI_CLI_PATH overrides resolution entirely: use it or fail, never
+    // fall back to probing
+    if let Some(override_path) = env::var_os("PI_CLI_PATH") {
+        let override_path = PathBuf::from(override_path);
+        debug_log!("PI_CLI_PATH override: {}", override_path.display());
+        note_resolution_source("PI_CLI_PATH override");
+        note_resolution_channel("env");
+        return run_overridden_cli(&override_path, cli_args).map_err(Into::into);
+    }
+
+    // A pinned version (a `.pi-version` file or `pi wrapper use`) beats
+    // both the cache and the probe chain; a pin for a version that is
+    // not installed falls through with a warning rather than leaving
+    // the user with no CLI at all
+    if let Some((version, source)) = versions::selection() {
+        debug_log!("pinned version: {} (from {})", version, source);
+        match versions::installed_executable(&version) {
+            Some(pinned_path) => {
+                if let Err(reason) = verify::verify_bundle(&pinned_path) {
+                    return Err(ResolutionError::Verification(reason).into());
+                }
+                ensure_supported_cli(Some(&version)).map_err(ResolutionFailure::from)?;
+                note_resolution_source(&format!("pinned version {}", version));
+                note_resolution_channel("bundled");
+                status_message(&format!("Using pinned CLI version {}", version));
+                return run_pi_executable(&pinned_path, cli_args).map_err(Into::into);
+            }
+            None => {
+                eprintln!(
+                    "{}",
+                    ui::Style::for_stderr().warn(&format!(
+                        "Pinned CLI version {} is not installed (run `pi wrapper update --version v{}`); falling back to normal resolution",
+                        version, version
+                    ))
+                );
+            }
+        }
+    }
+
+    // A still-valid cached resolution skips the probe walk entirely.
+    // With the local step disabled the cache is bypassed too — it may
+    // well hold a local resolution from an earlier run.
+    if !CACHE_DISABLED.load(Ordering::Relaxed) && !local_step_disabled() {
+        if let Ok(cwd) = env::current_dir() {
+            if let Some(hit) = cache::lookup(&cwd) {
+                debug_log!("cache hit: {} ({:?})", hit.path.display(), hit.kind);
+                note_resolution_source("cache");
+                note_resolution_channel(match hit.kind {
+                    cache::CliKind::Node => "local-npm",
+                    cache::CliKind::Executable => "bundled",
+                });
+                ensure_supported_cli(hit.version.as_deref()).map_err(ResolutionFailure::from)?;
+                let result = match hit.kind {
+                    cache::CliKind::Node => run_node_cli(&hit.path, cli_args),
+                    cache::CliKind::Executable => run_pi_executable(&hit.path, cli_args),
+                };
+                return result.map_err(Into::into);
+            }
+            debug_log!("cache miss for {}", cwd.display());
+        }
+    }
+
+    // Probe the configured locations in order (local npm install, global
+    // installs, then the bundled standalone executable by default),
+    // collecting every failed attempt so the final error explains what
+    // was tried instead of a generic "not found"
+    wrapper_config().map_err(ResolutionError::Config)?;
+    let resolver = real_resolver();
+    let cwd_missing = resolver.cwd.is_none();
+    let mut attempts = Vec::new();
+    match resolver.run(&probe_exists, cli_args, &mut ProcessRunner) {
+        Ok(exit_code) => return Ok(exit_code),
+        Err(walk) => attempts.extend(walk.into_iter().map(|attempt| match attempt {
+            resolver::Attempt::Missing(source) if cwd_missing && source.relies_on_cwd() => {
+                ResolutionError::SkippedNoCwd {
+                    source: source.missing_description(),
+                }
+            }
+            resolver::Attempt::Missing(source) => ResolutionError::NotPresent {
+                source: source.missing_description(),
+            },
+            resolver::Attempt::Failed(error) => error,
+        })),
+    }
+
+    // Last resort, opt-in only: hand the invocation to npx
+    let npx_allowed = NPX_FALLBACK_ALLOWED.load(Ordering::Relaxed)
+        || env::var("PI_WRAPPER_ALLOW_NPX").map(|v| v == "1").unwrap_or(false);
+    if should_fall_back_to_npx(npx_allowed, || {
+        command_stdout("npx", &["--version"]).is_some()
+    }) {
+        match run_npx_fallback(cli_args) {
+            Ok(exit_code) => return Ok(exit_code),
+            Err(error) => attempts.push(error),
+        }
+    }
+
+    debug_log!("no resolution step produced a CLI");
+    Err(ResolutionFailure { attempts })
+}
+
+/// Decides whether the npx fallback should run: it is opt-in, and only
+/// useful when npx is actually on PATH. The availability probe is
+/// injected so the decision is testable without npx installed.
+fn should_fall_back_to_npx(allowed: bool, npx_available: impl FnOnce() -> bool) -> bool {
+    allowed && npx_available()
+}
+
+/// Runs the CLI through `npx --yes @0xshariq/package-installer`,
+/// downloading it on first use. stdin/stdout/stderr are inherited, so
+/// interactive prompts from the CLI keep working.
+fn run_npx_fallback(cli_args: &[OsString]) -> Result<i32, ResolutionError> {
+    eprintln!(
+        "{}",
+        ui::Style::for_stderr()
+            .warn("No CLI installation found; falling back to npx (downloads the package on first use)")
+    );
+    debug_log!("winner: npx fallback");
+    note_resolution_source("npx fallback");
+    note_resolution_channel("global-npm");
+    let mut command = Command::new("npx");
+    command
+        .arg("--yes")
+        .arg("@0xshariq/package-installer")
+        .args(cli_args);
+    runner::exec_or_run(command).map_err(|e| ResolutionError::SpawnFailed {
+        path: PathBuf::from("npx"),
+        reason: e.to_string(),
+    })
+}
+
+/// True for entrypoints that must be run under a JS runtime rather than
+/// executed directly.
+fn is_js_entrypoint(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("js") || ext.eq_ignore_ascii_case("mjs"))
+        .unwrap_or(false)
+}
+
+/// Runs the exact entrypoint named by `PI_CLI_PATH`. Relative paths are
+/// resolved against the current working directory, and a missing file is
+/// a hard error naming the path we tried — no silent fallback to the
+/// probe chain.
+fn run_overridden_cli(path: &Path, cli_args: &[OsString]) -> Result<i32, ResolutionError> {
+    let resolved = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()
+            .map_err(|e| {
+                ResolutionError::Config(format!("cannot determine working directory: {}", e))
+            })?
+            .join(path)
+    };
+
+    if !resolved.exists() {
+        return Err(ResolutionError::OverrideMissing { path: resolved });
+    }
+
+    if is_js_entrypoint(&resolved) {
+        run_node_cli(&resolved, cli_args)
+    } else {
+        run_pi_executable(&resolved, cli_args)
+    }
+}
+
+fn max_walk_depth() -> usize {
+    env::var("PI_WRAPPER_MAX_DEPTH")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(resolver::DEFAULT_MAX_WALK_DEPTH)
+}
+
+/// Finds a local npm installation in the current directory or a parent.
+fn find_local_npm_installation() -> Option<PathBuf> {
+    real_resolver()
+        .candidate_paths(Source::LocalNpm)
+        .into_iter()
+        .find(|path| probe_exists(path))
+}
+
+/// Runs the CLI through a Yarn Plug'n'Play installation: no
+/// `node_modules` exists, so the entry is resolved with `yarn bin pi`
+/// and executed under `node --require <.pnp manifest>`. This path is
+/// never cached — it is not a plain file invocation.
+fn run_yarn_pnp(manifest: &Path, cli_args: &[OsString]) -> Result<i32, ResolutionError> {
+    let entry = command_stdout("yarn", &["bin", "pi"]).ok_or_else(|| {
+        ResolutionError::RuntimeUnavailable {
+            path: manifest.to_path_buf(),
+            reason: "found a PnP manifest but `yarn bin pi` could not resolve the CLI entry"
+                .to_string(),
+        }
+    })?;
+    debug_log!(
+        "winner: {} under --require {} (yarn pnp)",
+        entry,
+        manifest.display()
+    );
+    note_resolution_source(Source::YarnPnp.label());
+    status_message("Using Yarn PnP installation (runs under node --require .pnp.cjs)");
+    let mut command = Command::new(node_binary());
+    command
+        .arg("--require")
+        .arg(manifest)
+        .arg(entry.trim())
+        .args(cli_args);
+    runner::exec_or_run(command).map_err(|e| ResolutionError::SpawnFailed {
+        path: manifest.to_path_buf(),
+        reason: e.to_string(),
+    })
+}
+
+
+/// Runs `program` and returns its trimmed stdout, or `None` when the
+/// command is missing, fails, or prints nothing.
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Global `node_modules` roots to probe, in priority order: what npm
+/// itself reports, then well-known prefix locations so a global install
+/// is still found when `npm` isn't callable.
+fn global_npm_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+
+    if let Some(root) = command_stdout("npm", &["root", "-g"]) {
+        roots.push(PathBuf::from(root));
+    }
+    if let Some(prefix) = command_stdout("npm", &["config", "get", "prefix"]) {
+        let prefix = PathBuf::from(prefix);
+        if cfg!(windows) {
+            roots.push(prefix.join("node_modules"));
+        } else {
+            roots.push(prefix.join("lib").join("node_modules"));
+        }
+    }
+
+    // Common prefixes, probed directly so they work even when npm itself
+    // isn't on PATH
+    roots.push(PathBuf::from("/usr/local/lib/node_modules"));
+    roots.push(PathBuf::from("/opt/homebrew/lib/node_modules"));
+    if let Ok(appdata) = env::var("APPDATA") {
+        roots.push(PathBuf::from(appdata).join("npm").join("node_modules"));
+    }
+
+    roots.dedup();
+    roots
+}
+
+/// Default pnpm global `node_modules` location under a given pnpm home.
+fn pnpm_default_root(pnpm_home: &Path) -> PathBuf {
+    pnpm_home.join("global").join("5").join("node_modules")
+}
+
+/// Global roots used by pnpm: whatever `pnpm root -g` reports, plus the
+/// default `PNPM_HOME` layout so installs are found without shelling out.
+fn pnpm_global_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(root) = command_stdout("pnpm", &["root", "-g"]) {
+        roots.push(PathBuf::from(root));
+    }
+    if let Ok(pnpm_home) = env::var("PNPM_HOME") {
+        roots.push(pnpm_default_root(Path::new(&pnpm_home)));
+    } else if let Some(home) = env::home_dir() {
+        roots.push(pnpm_default_root(&home.join(".local").join("share").join("pnpm")));
+    }
+    roots
+}
+
+/// Global root used by yarn classic (`yarn global dir` + `node_modules`).
+fn yarn_global_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(dir) = command_stdout("yarn", &["global", "dir"]) {
+        roots.push(PathBuf::from(dir).join("node_modules"));
+    }
+    roots
+}
+
+/// Every global `node_modules` root worth probing, across npm, pnpm and
+/// yarn. Computed once per process — querying three package managers on
+/// every resolution attempt would be needlessly slow, and a missing
+/// manager simply contributes no roots.
+fn package_manager_roots() -> &'static [PathBuf] {
+    static ROOTS: std::sync::OnceLock<Vec<PathBuf>> = std::sync::OnceLock::new();
+    ROOTS.get_or_init(|| {
+        let mut roots = global_npm_roots();
+        roots.extend(pnpm_global_roots());
+        roots.extend(yarn_global_roots());
+        roots.dedup();
+        roots
+    })
+}
+
+/// Every global install path the resolver would probe, across npm,
+/// pnpm and yarn roots.
+fn global_candidate_paths() -> Vec<PathBuf> {
+    package_manager_roots()
+        .iter()
+        .map(|root| {
+            root.join("@0xshariq")
+                .join("package-installer")
+                .join("dist")
+                .join("index.js")
+        })
+        .collect()
+}
+
+/// Finds a global install under any known package manager root.
+fn find_global_npm_installation() -> Option<PathBuf> {
+    global_candidate_paths().into_iter().find(|entry| probe_exists(entry))
+}
+
+/// [`resolver::executable_dirs_for`] applied to this process's binary.
+fn executable_dirs() -> Vec<PathBuf> {
+    let Ok(exe_path) = env::current_exe() else {
+        return Vec::new();
+    };
+    resolver::executable_dirs_for(&exe_path, |path| std::fs::canonicalize(path).ok())
+}
+
+/// Finds a downloaded bundle in the per-user data directory.
+fn find_user_bundle() -> Option<PathBuf> {
+    find_bundled_pi(&update::user_bundle_dir()?)
+}
+
+/// Finds the bundled pi executable shipped alongside this binary,
+/// probing both the invoked and the symlink-resolved locations.
+fn find_bundled_executable() -> Option<PathBuf> {
+    executable_dirs()
+        .into_iter()
+        .find_map(|exe_dir| find_bundled_pi(&exe_dir.join("bundle-standalone")))
+}
+
+/// Finds a bundled pi executable in the current working directory (for
+/// development checkouts).
+fn find_bundled_development() -> Option<PathBuf> {
+    let current_dir = env::current_dir().ok()?;
+    find_bundled_pi(&current_dir.join("bundle-standalone"))
+}
+
+/// Every path the resolver would probe, in the configured priority
+/// order, paired with its resolution step. Used by `pi wrapper doctor`
+/// to explain resolution without executing anything.
+fn resolution_candidates() -> Vec<(ResolutionStep, PathBuf)> {
+    let resolver = real_resolver();
+    let mut candidates = Vec::new();
+    for step in resolver.order.clone() {
+        for source in Source::for_step(step) {
+            candidates.extend(
+                resolver
+                    .candidate_paths(*source)
+                    .into_iter()
+                    .map(|path| (step, path)),
+            );
+        }
+    }
+    candidates
+}
+
+/// The `--kind` label for a candidate: the bundled step covers both
+/// installed bundles and the development checkout in the working
+/// directory, told apart by location.
+fn which_kind_label(step: ResolutionStep, path: &Path) -> &'static str {
+    match step {
+        ResolutionStep::Local => "local-npm",
+        ResolutionStep::Global => "global-npm",
+        ResolutionStep::Bundled => {
+            let in_cwd = env::current_dir()
+                .map(|cwd| path.starts_with(&cwd))
+                .unwrap_or(false);
+            if in_cwd {
+                "dev-bundle"
+            } else {
+                "bundled"
+            }
+        }
+    }
+}
+
+/// Implements `pi wrapper which`: prints just the path the resolver
+/// would execute, for shell scripting (`ls -l "$(pi wrapper which)"`).
+/// `--kind` appends the resolution source and `--all` lists every
+/// existing candidate in priority order. Exits 1 with nothing on
+/// stdout when nothing resolves.
+fn run_which(show_kind: bool, all: bool) -> i32 {
+    let mut found: Vec<(&'static str, PathBuf)> = Vec::new();
+    if let Some(override_path) = env::var_os("PI_CLI_PATH") {
+        let path = PathBuf::from(override_path);
         if path.exists() {
-            println!("✅ Using locally installed CLI from node_modules");
-            return run_node_cli(path, cli_args);
-        }
-    }
-    
-    // Check parent directories (up to 5 levels) for local npm installations
-    let mut check_dir = current_dir.as_path();
-    for _ in 0..5 {
-        for local_path in &[
-            "node_modules/@0xshariq/package-installer/dist/index.js",
-            "node_modules/package-installer-cli/dist/index.js",
-        ] {
-            let full_path = check_dir.join(local_path);
-            if full_path.exists() {
-                println!("✅ Using locally installed CLI from node_modules");
-                return run_node_cli(&full_path, cli_args);
-            }
-        }
-        
-        if let Some(parent) = check_dir.parent() {
-            check_dir = parent;
+            found.push(("env-override", path));
+        }
+    } else {
+        for (step, path) in resolution_candidates() {
+            if step == ResolutionStep::Local && local_step_disabled() {
+                continue;
+            }
+            if path.exists() {
+                found.push((which_kind_label(step, &path), path));
+            }
+        }
+    }
+    if found.is_empty() {
+        return 1;
+    }
+    let shown = if all { &found[..] } else { &found[..1] };
+    for (kind, path) in shown {
+        if show_kind {
+            println!("{} {}", path.display(), kind);
         } else {
-            break;
-        }
-    }
-    
-    Err("No local npm installation found".into())
-}
-
-fn try_bundled_pi_executable(cli_args: &[String]) -> Result<i32, Box<dyn std::error::Error>> {
-    // Get the directory where this binary is located
-    let exe_path = env::current_exe()?;
-    let exe_dir = exe_path.parent().ok_or("Cannot determine executable directory")?;
-    
-    // Check for bundled pi executable relative to the binary
-    let bundled_pi_path = exe_dir.join("bundle-standalone").join("pi");
-    
-    if bundled_pi_path.exists() {
-        println!("✅ Using bundled standalone pi executable");
-        return run_pi_executable(&bundled_pi_path, cli_args);
-    }
-    
-    Err("Bundled pi executable not found relative to binary".into())
-}
-
-fn try_bundled_pi_development(cli_args: &[String]) -> Result<i32, Box<dyn std::error::Error>> {
-    // Check in the current working directory (for development)
-    let current_dir = env::current_dir()?;
-    let bundled_pi_dev_path = current_dir.join("bundle-standalone").join("pi");
-    
-    if bundled_pi_dev_path.exists() {
-        println!("✅ Using bundled standalone pi executable (development)");
-        return run_pi_executable(&bundled_pi_dev_path, cli_args);
-    }
-    
-    Err("Bundled pi executable not found in development location".into())
-}
-
-fn run_node_cli(cli_path: &Path, cli_args: &[String]) -> Result<i32, Box<dyn std::error::Error>> {
-    let status = Command::new("node")
-        .arg(cli_path)
-        .args(cli_args)
-        .status()
-        .map_err(|e| format!("Failed to run Node.js CLI. Make sure Node.js is installed: {}", e))?;
-    
-    Ok(status.code().unwrap_or(1))
-}
-
-fn run_pi_executable(pi_path: &Path, cli_args: &[String]) -> Result<i32, Box<dyn std::error::Error>> {
-    let status = Command::new(pi_path)
-        .args(cli_args)
-        .status()
-        .map_err(|e| format!("Failed to run pi executable: {}", e))?;
-    
-    Ok(status.code().unwrap_or(1))
-}
-
-fn print_usage_instructions() {
-    println!("\n📋 CLI NOT FOUND:");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("The Package Installer CLI was not found. Here are your options:");
-    println!("");
-    
-    println!("🌍 OPTION 1: Install locally via npm (Recommended)");
-    println!("   npm install @0xshariq/package-installer");
-    println!("   npx pi create my-app");
-    println!("");
-    
-    println!("🔧 OPTION 2: Use the bundled version");
-    println!("   Make sure the 'bundle-standalone/' directory is available alongside this executable");
-    println!("   The bundle should contain: bundle-standalone/pi (bundled executable)");
-    println!("");
-    
-    println!("💡 REQUIREMENTS:");
-    println!("   - For npm version: Install Node.js from https://nodejs.org");
-    println!("   - For bundled version: No additional requirements");
-    
-    println!("");
-    println!("🔗 More info: https://github.com/0xshariq/rust_package_installer_cli");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            println!("{}", path.display());
+        }
+    }
+    0
+}
+
+/// Implements `pi verify` (and `pi wrapper verify`): re-checks every
+/// cached bundle against its manifest without running anything. Exit
+/// code 1 covers both "nothing to verify" and any failed check.
+fn run_verify() -> i32 {
+    let candidates: Vec<(&'static str, PathBuf)> = [
+        ("user bundle", find_user_bundle()),
+        ("bundled", find_bundled_executable()),
+        ("dev bundle", find_bundled_development()),
+    ]
+    .into_iter()
+    .filter_map(|(kind, path)| Some((kind, path?)))
+    .collect();
+    if candidates.is_empty() {
+        report::WrapperMessage::Error {
+            message: "No cached bundle to verify (run `pi wrapper update` to download one)"
+                .to_string(),
+        }
+        .emit();
+        return 1;
+    }
+    let mut failed = false;
+    for (kind, path) in candidates {
+        match verify::recheck(&path) {
+            Ok(()) => println!("ok {} ({})", path.display(), kind),
+            Err(reason) => {
+                failed = true;
+                report::WrapperMessage::Error {
+                    message: format!("{} ({}): {}", path.display(), kind, reason),
+                }
+                .emit();
+            }
+        }
+    }
+    if failed {
+        1
+    } else {
+        0
+    }
+}
+
+/// First existing candidate for the bundled pi executable in `dir`.
+fn find_bundled_pi(dir: &Path) -> Option<PathBuf> {
+    resolver::pi_executable_candidates(dir, cfg!(windows))
+        .into_iter()
+        .find(|path| probe_exists(path))
+}
+
+/// Locate the Node.js binary. A `PI_NODE_PATH` override wins — used
+/// it-or-fail, like `PI_CLI_PATH`, so CI never silently falls back to
+/// the wrong interpreter — then a `node_binary` config value, then the
+/// discovery chain (`NODE`, `node`, `nodejs`, nvm installations); on
+/// Windows a plain `node` can miss PATHEXT-resolved installs, so fall
+/// back to asking `where` for `node.exe`.
+fn node_binary() -> PathBuf {
+    if let Some(override_path) = env::var_os("PI_NODE_PATH") {
+        let path = PathBuf::from(override_path);
+        if !path.exists() {
+            report::WrapperMessage::Error {
+                message: format!("PI_NODE_PATH points to {} which does not exist", path.display()),
+            }
+            .emit();
+            std::process::exit(1);
+        }
+        return path;
+    }
+    if let Ok(config) = wrapper_config() {
+        if let Some(node) = &config.node_binary {
+            return node.clone();
+        }
+    }
+    if let Some(discovered) = nodejs::discover() {
+        return discovered;
+    }
+    #[cfg(windows)]
+    {
+        if Command::new("node").arg("--version").output().is_err() {
+            if let Ok(output) = Command::new("where").arg("node.exe").output() {
+                if output.status.success() {
+                    if let Some(line) = String::from_utf8_lossy(&output.stdout).lines().next() {
+                        return PathBuf::from(line.trim());
+                    }
+                }
+            }
+        }
+    }
+    PathBuf::from("node")
+}
+
+/// Build the command that launches the resolved pi executable.
+///
+/// Spawning a `.cmd`/`.ps1` shim directly with `Command::new` fails on
+/// Windows (ERROR_BAD_EXE_FORMAT), so those are routed through `cmd /C`
+/// and `powershell -File` respectively; arguments are passed through
+/// `Command::arg` so quoting of names with spaces survives the hop.
+fn build_pi_command(pi_path: &Path, cli_args: &[OsString]) -> Command {
+    #[cfg(windows)]
+    {
+        match pi_path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("cmd") || ext.eq_ignore_ascii_case("bat") => {
+                let mut command = Command::new("cmd");
+                command.arg("/C").arg(pi_path).args(cli_args);
+                return command;
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("ps1") => {
+                let mut command = Command::new("powershell");
+                command
+                    .arg("-NoProfile")
+                    .arg("-ExecutionPolicy")
+                    .arg("Bypass")
+                    .arg("-File")
+                    .arg(pi_path)
+                    .args(cli_args);
+                return command;
+            }
+            _ => {}
+        }
+    }
+    let mut command = Command::new(pi_path);
+    command.args(cli_args);
+    command
+}
+
+/// JavaScript runtime capable of executing the bundled `dist/index.js`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsRuntime {
+    Node,
+    Bun,
+    Deno,
+}
+
+impl JsRuntime {
+    /// Preference order when nothing is forced via `PI_JS_RUNTIME`.
+    const PREFERENCE: [JsRuntime; 3] = [JsRuntime::Node, JsRuntime::Bun, JsRuntime::Deno];
+
+    fn from_name(name: &str) -> Option<JsRuntime> {
+        match name.to_ascii_lowercase().as_str() {
+            "node" => Some(JsRuntime::Node),
+            "bun" => Some(JsRuntime::Bun),
+            "deno" => Some(JsRuntime::Deno),
+            _ => None,
+        }
+    }
+
+    fn is_available(self) -> bool {
+        let program: &Path = match self {
+            JsRuntime::Node => return Command::new(node_binary()).arg("--version").output().is_ok(),
+            JsRuntime::Bun => Path::new("bun"),
+            JsRuntime::Deno => Path::new("deno"),
+        };
+        Command::new(program).arg("--version").output().is_ok()
+    }
+
+    /// Builds the command that runs `cli_path` under this runtime.
+    fn command(self, cli_path: &Path) -> Command {
+        match self {
+            JsRuntime::Node => {
+                let mut command = Command::new(node_binary());
+                // `PI_NODE_OPTIONS` goes between node and the script:
+                // unlike NODE_OPTIONS it affects only this top-level
+                // process, never node children the CLI spawns
+                command.args(pi_node_options());
+                command.arg(cli_path);
+                command
+            }
+            JsRuntime::Bun => {
+                let mut command = Command::new("bun");
+                command.arg("run").arg(cli_path);
+                command
+            }
+            JsRuntime::Deno => {
+                let mut command = Command::new("deno");
+                command.arg("run").arg("--allow-all").arg(cli_path);
+                command
+            }
+        }
+    }
+}
+
+/// Picks the runtime to use: the `--runtime` flag wins, then
+/// `PI_JS_RUNTIME=node|bun|deno` (an unknown value is an error, not a
+/// silent fallback), then a `js_runtime` config value; otherwise the
+/// first available runtime in preference order wins.
+fn select_js_runtime() -> Result<JsRuntime, String> {
+    if let Some(forced) = FORCED_RUNTIME.get() {
+        return Ok(*forced);
+    }
+    if let Ok(forced) = env::var("PI_JS_RUNTIME") {
+        return JsRuntime::from_name(&forced).ok_or_else(|| {
+            format!(
+                "Invalid PI_JS_RUNTIME value {:?} (expected node, bun or deno)",
+                forced
+            )
+        });
+    }
+
+    if let Ok(config) = wrapper_config() {
+        if let Some(configured) = &config.js_runtime {
+            return JsRuntime::from_name(configured).ok_or_else(|| {
+                format!(
+                    "Invalid js_runtime config value {:?} (expected node, bun or deno)",
+                    configured
+                )
+            });
+        }
+    }
+
+    JsRuntime::PREFERENCE
+        .into_iter()
+        .find(|runtime| runtime.is_available())
+        .ok_or_else(|| {
+            "No JavaScript runtime found. Install one of:\n\
+             \x20  - Node.js: https://nodejs.org (or `pi wrapper install-node`)\n\
+             \x20  - bun: https://bun.sh\n\
+             \x20  - deno: https://deno.com"
+                .to_string()
+        })
+}
+
+/// Minimum Node.js version the TypeScript CLI runs on; older runtimes
+/// die on modern syntax with a V8 `SyntaxError` instead of anything
+/// actionable, so the wrapper checks first.
+const MIN_NODE_VERSION: (u64, u64, u64) = (18, 0, 0);
+
+/// Exit code for "Node.js is too old", distinct from the generic
+/// resolution failure so scripts can tell the two apart.
+const NODE_TOO_OLD_EXIT_CODE: i32 = 3;
+
+/// Parses `node --version` output (`v20.11.1`, nightly builds like
+/// `v22.0.0-nightly2024...`) into a version triple. Garbage is `None`.
+fn parse_node_version(output: &str) -> Option<(u64, u64, u64)> {
+    let line = output.lines().next()?.trim();
+    let core = line.strip_prefix('v').unwrap_or(line);
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.');
+    let major: u64 = parts.next()?.parse().ok()?;
+    let minor: u64 = parts.next()?.parse().ok()?;
+    let patch: u64 = parts.next()?.parse().ok()?;
+    Some((major, minor, patch))
+}
+
+/// Decides whether the detected Node.js version is usable. Unknown or
+/// unparsable versions pass — the CLI itself will fail more precisely —
+/// and `skip` (from `PI_WRAPPER_SKIP_NODE_CHECK=1`) bypasses the check.
+fn node_version_check(version_output: Option<&str>, skip: bool) -> Result<(), String> {
+    if skip {
+        return Ok(());
+    }
+    let Some(detected) = version_output.and_then(parse_node_version) else {
+        return Ok(());
+    };
+    if detected >= MIN_NODE_VERSION {
+        return Ok(());
+    }
+    let (major, minor, patch) = MIN_NODE_VERSION;
+    Err(format!(
+        "Node.js {}.{}.{} is too old for the Package Installer CLI (needs at least {}.{}.{}).\n\
+         Upgrade via https://nodejs.org or your version manager (e.g. `nvm install --lts`),\n\
+         run `pi wrapper install-node` for a private portable runtime,\n\
+         or set PI_WRAPPER_SKIP_NODE_CHECK=1 to run anyway.",
+        detected.0, detected.1, detected.2, major, minor, patch
+    ))
+}
+
+/// `node --version` output, probed once per process.
+fn detected_node_version() -> Option<&'static str> {
+    static NODE_VERSION: OnceLock<Option<String>> = OnceLock::new();
+    NODE_VERSION
+        .get_or_init(|| command_stdout(&node_binary().display().to_string(), &["--version"]))
+        .as_deref()
+}
+
+/// The wrapper's private portable runtime, for when the system cannot
+/// provide a usable node: either already installed under
+/// `$PI_HOME/node/`, or — with `PI_WRAPPER_AUTO_NODE=1` — downloaded
+/// on the spot by [`node_install`].
+fn private_node_rescue() -> Option<PathBuf> {
+    if let Some(node) = node_install::installed_runtime() {
+        return Some(node);
+    }
+    if node_install::auto_install_enabled() {
+        match node_install::install() {
+            Ok(node) => return Some(node),
+            Err(message) => eprintln!("{}", ui::Style::for_stderr().error(&message)),
+        }
+    }
+    None
+}
+
+fn run_node_cli(cli_path: &Path, cli_args: &[OsString]) -> Result<i32, ResolutionError> {
+    // The private portable runtime steps in when the system has no
+    // runtime at all or its node is too old for the CLI
+    let mut private_node: Option<PathBuf> = None;
+    let runtime = match select_js_runtime() {
+        Ok(runtime) => runtime,
+        Err(reason) => match private_node_rescue() {
+            Some(node) => {
+                private_node = Some(node);
+                JsRuntime::Node
+            }
+            None => {
+                return Err(ResolutionError::RuntimeUnavailable {
+                    path: cli_path.to_path_buf(),
+                    reason,
+                })
+            }
+        },
+    };
+    if runtime == JsRuntime::Node && private_node.is_none() {
+        let skip = env::var("PI_WRAPPER_SKIP_NODE_CHECK")
+            .map(|v| v == "1")
+            .unwrap_or(false);
+        if let Err(message) = node_version_check(detected_node_version(), skip) {
+            match private_node_rescue() {
+                Some(node) => private_node = Some(node),
+                None => {
+                    eprintln!("{}", ui::Style::for_stderr().error(&message));
+                    std::process::exit(NODE_TOO_OLD_EXIT_CODE);
+                }
+            }
+        }
+    }
+    let mut command = match &private_node {
+        Some(node) => {
+            status_message("Using the wrapper's portable Node.js runtime");
+            debug_log!("portable node runtime: {}", node.display());
+            let mut command = Command::new(node);
+            command.args(pi_node_options());
+            command.arg(cli_path);
+            command
+        }
+        None => runtime.command(cli_path),
+    };
+    command.args(cli_args);
+    runner::exec_or_run(command).map_err(|e| ResolutionError::SpawnFailed {
+        path: cli_path.to_path_buf(),
+        reason: format!("{:?} runtime: {}", runtime, e),
+    })
+}
+
+/// The tokenized `PI_NODE_OPTIONS` flags, or empty when unset.
+fn pi_node_options() -> Vec<String> {
+    env::var("PI_NODE_OPTIONS")
+        .map(|raw| split_node_options(&raw))
+        .unwrap_or_default()
+}
+
+/// Splits a `PI_NODE_OPTIONS` value into node arguments: tokens are
+/// whitespace-separated, order is preserved, and a single- or
+/// double-quoted span keeps its spaces (`--title='my cli'` is one
+/// argument). No escapes or variable expansion — this is not a shell.
+fn split_node_options(raw: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+    for ch in raw.chars() {
+        match quote {
+            Some(q) if ch == q => quote = None,
+            Some(_) => current.push(ch),
+            None if ch == '\'' || ch == '"' => {
+                quote = Some(ch);
+                in_token = true;
+            }
+            None if ch.is_whitespace() => {
+                if in_token {
+                    args.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            None => {
+                current.push(ch);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        args.push(current);
+    }
+    args
+}
+
+/// Makes sure `path` carries an execute bit before it is spawned:
+/// unzipping a release on Linux routinely drops the bit, and the bare
+/// "Permission denied (os error 13)" from the spawn sends people down
+/// the wrong debugging path. The bit is restored in place when
+/// possible; otherwise the error names the exact chmod to run. A
+/// no-op on Windows, where execute bits do not exist.
+#[cfg(unix)]
+fn ensure_executable(path: &Path) -> Result<(), ResolutionError> {
+    use std::os::unix::fs::PermissionsExt;
+    // Spawn reports missing files itself, with a better error
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Ok(());
+    };
+    let mode = metadata.permissions().mode();
+    if mode & 0o111 != 0 {
+        return Ok(());
+    }
+    let mut permissions = metadata.permissions();
+    permissions.set_mode(mode | 0o111);
+    match std::fs::set_permissions(path, permissions) {
+        Ok(()) => {
+            debug_log!("restored the execute bit on {}", path.display());
+            Ok(())
+        }
+        Err(e) => Err(ResolutionError::NotExecutable {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        }),
+    }
+}
+
+#[cfg(not(unix))]
+fn ensure_executable(_path: &Path) -> Result<(), ResolutionError> {
+    Ok(())
+}
+
+fn run_pi_executable(pi_path: &Path, cli_args: &[OsString]) -> Result<i32, ResolutionError> {
+    ensure_executable(pi_path)?;
+    // Runtime flags cannot apply to the standalone build; say so
+    // instead of silently dropping them
+    if env::var_os("PI_NODE_OPTIONS").is_some_and(|value| !value.is_empty()) {
+        eprintln!(
+            "{}",
+            ui::Style::for_stderr().warn(
+                "PI_NODE_OPTIONS is ignored: the resolved CLI is a standalone executable, not a node script"
+            )
+        );
+    }
+    runner::exec_or_run(build_pi_command(pi_path, cli_args)).map_err(|e| {
+        ResolutionError::SpawnFailed {
+            path: pi_path.to_path_buf(),
+            reason: e.to_string(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<OsString> {
+        list.iter().map(OsString::from).collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_missing_execute_bit_is_restored_in_place() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("pi-wrapper-execbit-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pi = dir.join("pi");
+        std::fs::write(&pi, "#!/bin/sh\nexit 0\n").unwrap();
+        std::fs::set_permissions(&pi, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        ensure_executable(&pi).unwrap();
+        let mode = std::fs::metadata(&pi).unwrap().permissions().mode();
+        assert_ne!(mode & 0o111, 0, "execute bit must be restored");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn the_unfixable_execute_bit_error_names_the_chmod_command() {
+        let error = ResolutionError::NotExecutable {
+            path: PathBuf::from("/opt/pi/bundle-standalone/pi"),
+            reason: "Read-only file system (os error 30)".to_string(),
+        };
+        let message = error.to_string();
+        assert!(message.contains("chmod +x /opt/pi/bundle-standalone/pi"), "got: {message}");
+        assert!(message.contains("Read-only file system"));
+    }
+
+    #[test]
+    fn wrapper_timeout_parses_suffixed_durations() {
+        assert_eq!(parse_wrapper_timeout("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_wrapper_timeout("10m"), Some(Duration::from_secs(600)));
+        assert_eq!(parse_wrapper_timeout("1h"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_wrapper_timeout("45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_wrapper_timeout(" 5s "), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn wrapper_timeout_rejects_zero_and_garbage() {
+        assert_eq!(parse_wrapper_timeout("0"), None);
+        assert_eq!(parse_wrapper_timeout("0s"), None);
+        assert_eq!(parse_wrapper_timeout(""), None);
+        assert_eq!(parse_wrapper_timeout("10x"), None);
+        assert_eq!(parse_wrapper_timeout("ten"), None);
+        assert_eq!(parse_wrapper_timeout("-5s"), None);
+    }
+
+    #[test]
+    fn wrapper_timeout_flag_captures_its_value_and_is_stripped() {
+        let (kept, flags) = extract_wrapper_flags(args(&["--wrapper-timeout", "90s", "analyze"]));
+        assert_eq!(flags.timeout.as_deref(), Some("90s"));
+        assert!(flags.parse_error.is_none());
+        assert_eq!(kept, args(&["analyze"]));
+
+        // With no value to capture, the error names the expected shape
+        let (_, flags) = extract_wrapper_flags(args(&["--wrapper-timeout"]));
+        let message = flags.parse_error.expect("missing duration must be an error");
+        assert!(message.contains("--wrapper-timeout requires a duration"), "got: {message}");
+    }
+
+    #[test]
+    fn runtime_flag_captures_its_value_and_is_stripped() {
+        let (kept, flags) = extract_wrapper_flags(args(&["--runtime", "deno", "analyze"]));
+        assert_eq!(flags.runtime.as_deref(), Some("deno"));
+        assert!(flags.parse_error.is_none());
+        assert_eq!(kept, args(&["analyze"]));
+
+        let (_, flags) = extract_wrapper_flags(args(&["--runtime"]));
+        let message = flags.parse_error.expect("missing runtime must be an error");
+        assert!(message.contains("--runtime requires a runtime name"), "got: {message}");
+    }
+
+    #[test]
+    fn node_options_split_on_whitespace_in_order() {
+        assert_eq!(
+            split_node_options("--inspect-brk  --max-old-space-size=512\t--trace-warnings"),
+            ["--inspect-brk", "--max-old-space-size=512", "--trace-warnings"]
+        );
+        assert!(split_node_options("").is_empty());
+        assert!(split_node_options("   ").is_empty());
+    }
+
+    #[test]
+    fn node_options_quotes_keep_spaces_and_join_with_bare_text() {
+        assert_eq!(
+            split_node_options("--title='my cli' --eval=\"a b\""),
+            ["--title=my cli", "--eval=a b"]
+        );
+        // An unterminated quote still yields the token read so far
+        assert_eq!(split_node_options("--title='open"), ["--title=open"]);
+    }
+
+    #[test]
+    fn recognizes_cli_binary_names() {
+        assert!(binary_invokes_cli(OsStr::new("pi")));
+        assert!(binary_invokes_cli(OsStr::new("PI")));
+        assert!(binary_invokes_cli(OsStr::new("package-installer")));
+        assert!(binary_invokes_cli(OsStr::new("/usr/local/bin/pi")));
+        assert!(binary_invokes_cli(OsStr::new("./pi.exe")));
+        assert!(binary_invokes_cli(OsStr::new("target/release/package-installer.exe")));
+        // The crate's own binary name must keep working
+        assert!(binary_invokes_cli(OsStr::new("target/release/package-installer-cli")));
+    }
+
+    #[test]
+    fn rejects_names_that_merely_contain_pi() {
+        assert!(!binary_invokes_cli(OsStr::new("pip-helper")));
+        assert!(!binary_invokes_cli(OsStr::new("spider")));
+        assert!(!binary_invokes_cli(OsStr::new("/usr/bin/pip")));
+        // "pi" only in a parent directory must not count
+        assert!(!binary_invokes_cli(OsStr::new("/home/pills/tools/wrapper")));
+        assert!(!binary_invokes_cli(OsStr::new("/opt/pi/wrapper")));
+    }
+
+    #[test]
+    fn symlinked_cli_name_is_detected_by_stem() {
+        // Invocation through a symlink shows the symlink's own name in
+        // args[0]; only that name matters.
+        assert!(binary_invokes_cli(OsStr::new("/home/user/.local/bin/pi")));
+        assert!(!binary_invokes_cli(OsStr::new("/home/user/.local/bin/pi-wrapper-backup")));
+    }
+
+    #[test]
+    fn forwards_arguments_after_the_binary_name() {
+        let forwarded = forwarded_cli_args(&args(&["pi", "create", "my-app"]));
+        assert_eq!(forwarded, Some(args(&["create", "my-app"])));
+    }
+
+    #[test]
+    fn pi_and_package_installer_forward_identical_arguments() {
+        let via_pi = forwarded_cli_args(&args(&["pi", "create", "my-app"]));
+        let via_full = forwarded_cli_args(&args(&["package-installer", "create", "my-app"]));
+        assert_eq!(via_pi, via_full);
+    }
+
+    #[test]
+    fn pi_subcommand_of_foreign_wrapper_skips_both_names() {
+        let forwarded = forwarded_cli_args(&args(&["some-wrapper", "pi", "create", "my-app"]));
+        assert_eq!(forwarded, Some(args(&["create", "my-app"])));
+    }
+
+    #[test]
+    fn foreign_binary_without_pi_subcommand_is_rejected() {
+        assert_eq!(forwarded_cli_args(&args(&["pip-helper", "create"])), None);
+        assert_eq!(forwarded_cli_args(&args(&["/home/pills/tools/wrapper", "create"])), None);
+    }
+
+    #[test]
+    fn wrapper_version_is_intercepted_but_plain_version_is_forwarded() {
+        assert!(wrapper_version_requested(&args(&["--wrapper-version"])));
+        assert!(wrapper_version_requested(&args(&["version", "--wrapper"])));
+        assert!(!wrapper_version_requested(&args(&["--version"])));
+        assert!(!wrapper_version_requested(&args(&["version"])));
+        assert!(!wrapper_version_requested(&args(&["create", "--wrapper-version"])));
+    }
+
+    #[test]
+    fn aggregated_failure_lists_every_attempted_source() {
+        let failure = ResolutionFailure {
+            attempts: vec![
+                ResolutionError::NotPresent {
+                    source: "local node_modules installation",
+                },
+                ResolutionError::NotPresent {
+                    source: "global package manager installation",
+                },
+                ResolutionError::NotPresent {
+                    source: "bundled standalone executable (next to the binary)",
+                },
+                ResolutionError::NotPresent {
+                    source: "bundled standalone executable (development location)",
+                },
+            ],
+        };
+        let message = failure.to_string();
+        assert!(message.contains("local node_modules installation"));
+        assert!(message.contains("global package manager installation"));
+        assert!(message.contains("next to the binary"));
+        assert!(message.contains("development location"));
+    }
+
+    #[test]
+    fn broken_runtime_is_not_reported_as_not_installed() {
+        let error = ResolutionError::RuntimeUnavailable {
+            path: PathBuf::from("/project/node_modules/@0xshariq/package-installer/dist/index.js"),
+            reason: "No JavaScript runtime found".to_string(),
+        };
+        let message = error.to_string();
+        assert!(message.contains("exists but"));
+        assert!(message.contains("dist/index.js"));
+        assert!(!message.contains("not present"));
+
+        let spawn = ResolutionError::SpawnFailed {
+            path: PathBuf::from("/opt/pi/bundle-standalone/pi"),
+            reason: "Permission denied (os error 13)".to_string(),
+        };
+        assert!(spawn.to_string().contains("failed to launch"));
+        assert!(spawn.to_string().contains("Permission denied"));
+    }
+
+    #[test]
+    fn doctor_lives_under_the_wrapper_prefix() {
+        assert_eq!(doctor_requested(&args(&["wrapper", "doctor"])), Some(false));
+        assert_eq!(
+            doctor_requested(&args(&["wrapper", "doctor", "--json"])),
+            Some(true)
+        );
+        // The CLI's own doctor command keeps forwarding
+        assert_eq!(doctor_requested(&args(&["doctor"])), None);
+        assert_eq!(doctor_requested(&args(&["create", "wrapper"])), None);
+    }
+
+    #[test]
+    fn resolution_candidates_follow_the_configured_order() {
+        let candidates = resolution_candidates();
+        assert!(!candidates.is_empty());
+        // Default order: every local candidate precedes every global
+        // one, which precede every bundled one
+        let step_indices: Vec<_> = candidates
+            .iter()
+            .map(|(step, _)| match step {
+                ResolutionStep::Local => 0,
+                ResolutionStep::Global => 1,
+                ResolutionStep::Bundled => 2,
+            })
+            .collect();
+        let mut sorted = step_indices.clone();
+        sorted.sort_unstable();
+        assert_eq!(step_indices, sorted);
+    }
+
+    #[test]
+    fn package_json_version_field_is_extracted() {
+        assert_eq!(
+            package_json_version(r#"{"name": "pi", "version": "3.1.0"}"#),
+            Some("3.1.0".to_string())
+        );
+        // Missing field and malformed JSON both degrade to None
+        assert_eq!(package_json_version(r#"{"name": "pi"}"#), None);
+        assert_eq!(package_json_version(r#"{"version": "3.1.0""#), None);
+        assert_eq!(package_json_version("not json at all"), None);
+    }
+
+    #[test]
+    fn wrapper_flags_parse_only_before_the_first_cli_token() {
+        let (kept, flags) =
+            extract_wrapper_flags(args(&["--wrapper-verbose", "create", "my-app"]));
+        assert!(flags.verbose && !flags.quiet);
+        assert_eq!(kept, args(&["create", "my-app"]));
+
+        let (kept, flags) = extract_wrapper_flags(args(&[
+            "--wrapper-no-cache",
+            "--wrapper-allow-npx",
+            "create",
+        ]));
+        assert!(flags.no_cache && flags.allow_npx);
+        assert_eq!(kept, args(&["create"]));
+
+        // Past the first CLI token the same spelling is the CLI's
+        // problem, not the wrapper's
+        let (kept, flags) = extract_wrapper_flags(args(&["create", "--wrapper-quiet", "my-app"]));
+        assert!(!flags.quiet);
+        assert_eq!(kept, args(&["create", "--wrapper-quiet", "my-app"]));
+    }
+
+    /// One row per invocation shape: the input as typed after `pi`, and
+    /// the exact vector the CLI must receive.
+    #[test]
+    fn forwarded_vectors_for_every_invocation_shape() {
+        let table: &[(&[&str], &[&str])] = &[
+            // Plain commands pass through whole
+            (&["create", "my-app"], &["create", "my-app"]),
+            (&[], &[]),
+            // Wrapper flags before the command are stripped
+            (&["--wrapper-quiet", "create", "my-app"], &["create", "my-app"]),
+            (&["--wrapper-quiet", "--wrapper-verbose", "analyze"], &["analyze"]),
+            (&["--wrapper-timeout", "90s", "analyze"], &["analyze"]),
+            (&["-C", "apps/web", "update"], &["update"]),
+            (&["--cwd", "/tmp/x", "update"], &["update"]),
+            (&["--wrapper-json", "wrapper", "doctor"], &["wrapper", "doctor"]),
+            // After the first CLI token, wrapper spellings forward verbatim
+            (&["create", "--wrapper-quiet", "my-app"], &["create", "--wrapper-quiet", "my-app"]),
+            (&["update", "--cwd", "/tmp/x"], &["update", "--cwd", "/tmp/x"]),
+            (&["analyze", "--wrapper-timeout"], &["analyze", "--wrapper-timeout"]),
+            // `--` ends wrapper parsing: the CLI gets the literal flag
+            (&["--", "--wrapper-quiet"], &["--wrapper-quiet"]),
+            (&["--wrapper-verbose", "--", "--wrapper-quiet", "x"], &["--wrapper-quiet", "x"]),
+            // A later `--` belongs to the CLI and survives untouched
+            (&["create", "--", "extra"], &["create", "--", "extra"]),
+            // Unknown flags are not the wrapper's to reject
+            (&["--verbose", "create"], &["--verbose", "create"]),
+        ];
+        for (input, expected) in table {
+            let (kept, flags) = extract_wrapper_flags(args(input));
+            assert_eq!(flags.parse_error, None, "input {input:?}");
+            assert_eq!(kept, args(expected), "input {input:?}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_arguments_forward_byte_for_byte() {
+        use std::os::unix::ffi::OsStringExt;
+        let garbled = OsString::from_vec(vec![b'f', 0xff, b'o']);
+        let (kept, flags) = extract_wrapper_flags(vec![
+            OsString::from("--wrapper-quiet"),
+            OsString::from("create"),
+            garbled.clone(),
+        ]);
+        assert!(flags.quiet);
+        assert_eq!(kept, vec![OsString::from("create"), garbled]);
+    }
+
+    #[test]
+    fn npx_fallback_requires_opt_in_and_an_available_npx() {
+        // Disabled: npx availability must not even be probed
+        assert!(!should_fall_back_to_npx(false, || panic!("must not probe npx")));
+        // Enabled but npx missing
+        assert!(!should_fall_back_to_npx(true, || false));
+        // Enabled and available
+        assert!(should_fall_back_to_npx(true, || true));
+    }
+
+    #[test]
+    fn global_roots_include_well_known_prefixes() {
+        let roots = global_npm_roots();
+        assert!(roots.contains(&PathBuf::from("/usr/local/lib/node_modules")));
+        assert!(roots.contains(&PathBuf::from("/opt/homebrew/lib/node_modules")));
+    }
+
+    #[test]
+    fn js_entrypoints_are_detected_by_extension() {
+        assert!(is_js_entrypoint(Path::new("dist/index.js")));
+        assert!(is_js_entrypoint(Path::new("dist/index.MJS")));
+        assert!(!is_js_entrypoint(Path::new("bundle-standalone/pi")));
+        assert!(!is_js_entrypoint(Path::new("pi.exe")));
+    }
+
+    #[test]
+    fn missing_override_path_is_a_hard_error_naming_the_path() {
+        let err = run_overridden_cli(Path::new("/does/not/exist/index.js"), &[])
+            .expect_err("missing PI_CLI_PATH target must fail");
+        let message = err.to_string();
+        assert!(message.contains("/does/not/exist/index.js"));
+        assert!(message.contains("does not exist"));
+    }
+
+    #[test]
+    fn js_runtime_names_parse_case_insensitively() {
+        assert_eq!(JsRuntime::from_name("node"), Some(JsRuntime::Node));
+        assert_eq!(JsRuntime::from_name("BUN"), Some(JsRuntime::Bun));
+        assert_eq!(JsRuntime::from_name("Deno"), Some(JsRuntime::Deno));
+        assert_eq!(JsRuntime::from_name("quickjs"), None);
+    }
+
+    #[test]
+    fn bun_and_deno_commands_use_run_invocations() {
+        let cli = Path::new("dist/index.js");
+
+        let bun = JsRuntime::Bun.command(cli);
+        let bun_args: Vec<_> = bun.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(bun.get_program(), "bun");
+        assert_eq!(bun_args, ["run", "dist/index.js"]);
+
+        let deno = JsRuntime::Deno.command(cli);
+        let deno_args: Vec<_> = deno.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(deno.get_program(), "deno");
+        assert_eq!(deno_args, ["run", "--allow-all", "dist/index.js"]);
+    }
+
+    #[test]
+    fn pnpm_default_root_matches_documented_layout() {
+        let root = pnpm_default_root(Path::new("/home/user/.local/share/pnpm"));
+        assert_eq!(
+            root,
+            Path::new("/home/user/.local/share/pnpm/global/5/node_modules")
+        );
+    }
+
+    #[test]
+    fn cwd_flag_captures_its_directory_and_is_stripped() {
+        let (kept, flags) = extract_wrapper_flags(args(&["-C", "apps/web", "update"]));
+        assert_eq!(flags.cwd, Some(PathBuf::from("apps/web")));
+        assert!(flags.parse_error.is_none());
+        assert_eq!(kept, args(&["update"]));
+
+        let (kept, flags) = extract_wrapper_flags(args(&["--cwd", "/tmp/x", "update"]));
+        assert_eq!(flags.cwd, Some(PathBuf::from("/tmp/x")));
+        assert_eq!(kept, args(&["update"]));
+
+        let (_, flags) = extract_wrapper_flags(args(&["-C"]));
+        let message = flags.parse_error.expect("missing directory must be an error");
+        assert!(message.contains("-C/--cwd requires a directory"), "got: {message}");
+    }
+
+    #[test]
+    fn capture_flag_takes_an_optional_inline_path_and_is_stripped() {
+        // Bare: capture requested, default log name
+        let (kept, flags) = extract_wrapper_flags(args(&["--wrapper-capture", "create", "my-app"]));
+        assert_eq!(flags.capture, Some(None));
+        assert_eq!(kept, args(&["create", "my-app"]));
+
+        // Attached: capture into the named file
+        let (kept, flags) =
+            extract_wrapper_flags(args(&["--wrapper-capture=/tmp/run.log", "create"]));
+        assert_eq!(flags.capture, Some(Some(PathBuf::from("/tmp/run.log"))));
+        assert_eq!(kept, args(&["create"]));
+
+        // Absent: no capture
+        let (_, flags) = extract_wrapper_flags(args(&["create"]));
+        assert_eq!(flags.capture, None);
+    }
+
+    #[test]
+    fn node_versions_parse_including_nightlies() {
+        assert_eq!(parse_node_version("v20.11.1"), Some((20, 11, 1)));
+        assert_eq!(parse_node_version("18.0.0"), Some((18, 0, 0)));
+        assert_eq!(
+            parse_node_version("v22.0.0-nightly20240101abcdef"),
+            Some((22, 0, 0))
+        );
+        assert_eq!(parse_node_version("v20.11.1\nextra noise"), Some((20, 11, 1)));
+        for garbage in ["", "node: command not found", "v20.11", "vX.Y.Z"] {
+            assert_eq!(parse_node_version(garbage), None, "{garbage:?}");
+        }
+    }
+
+    #[test]
+    fn old_node_fails_the_check_with_both_versions_named() {
+        let message = node_version_check(Some("v14.21.3"), false).unwrap_err();
+        assert!(message.contains("14.21.3"));
+        assert!(message.contains("18.0.0"));
+        assert!(message.contains("PI_WRAPPER_SKIP_NODE_CHECK"));
+    }
+
+    #[test]
+    fn new_unknown_or_skipped_node_passes_the_check() {
+        assert_eq!(node_version_check(Some("v20.11.1"), false), Ok(()));
+        assert_eq!(node_version_check(Some("garbage"), false), Ok(()));
+        assert_eq!(node_version_check(None, false), Ok(()));
+        // Skip overrides even a known-old version
+        assert_eq!(node_version_check(Some("v14.21.3"), true), Ok(()));
+    }
 }
@@ -0,0 +1,399 @@
+//! `pi wrapper install-node`: a pinned portable Node.js runtime.
+//!
+//! A "bundled" CLI that is really the JS dist still needs a runtime,
+//! and not every machine has a usable node. This module downloads the
+//! official Node.js build for the current OS and architecture from
+//! nodejs.org, verifies it against the published `SHASUMS256.txt`
+//! before anything is unpacked, and extracts it under
+//! `$PI_HOME/node/<version>/` via a scratch directory and a final
+//! rename, so an interrupted install never looks complete. The
+//! version is pinned in [`PINNED_NODE_VERSION`] and overridable with
+//! the `node_version` config key; `run_node_cli` falls back to this
+//! private runtime when the system node is missing or too old, and
+//! `PI_WRAPPER_AUTO_NODE=1` downloads it on the spot in that case.
+
+use std::env;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sha2::{Digest, Sha256};
+
+/// The LTS release installed when nothing overrides it.
+pub const PINNED_NODE_VERSION: &str = "v22.12.0";
+
+/// Official dist tree; `PI_WRAPPER_NODE_BASE` points it at a mirror or
+/// a test server.
+const DEFAULT_DIST_BASE: &str = "https://nodejs.org/dist";
+
+fn dist_base() -> String {
+    env::var("PI_WRAPPER_NODE_BASE").unwrap_or_else(|_| DEFAULT_DIST_BASE.to_string())
+}
+
+/// The version to install: the `node_version` config key (normalized
+/// to a leading `v`) beats the pinned constant.
+pub fn selected_version() -> String {
+    let version = crate::wrapper_config()
+        .ok()
+        .and_then(|config| config.node_version.clone())
+        .unwrap_or_else(|| PINNED_NODE_VERSION.to_string());
+    if version.starts_with('v') {
+        version
+    } else {
+        format!("v{}", version)
+    }
+}
+
+/// The official archive name for a version on an OS/arch pair, or
+/// `None` when nodejs.org publishes no build for it.
+fn archive_name(version: &str, os: &str, arch: &str) -> Option<String> {
+    let platform = match (os, arch) {
+        ("linux", "x86_64") => "linux-x64",
+        ("linux", "aarch64") => "linux-arm64",
+        ("macos", "x86_64") => "darwin-x64",
+        ("macos", "aarch64") => "darwin-arm64",
+        ("windows", "x86_64") => "win-x64",
+        ("windows", "aarch64") => "win-arm64",
+        _ => return None,
+    };
+    let extension = if os == "windows" { "zip" } else { "tar.gz" };
+    Some(format!("node-{}-{}.{}", version, platform, extension))
+}
+
+/// Root for portable runtimes: `$PI_HOME/node/`.
+fn runtimes_dir() -> Option<PathBuf> {
+    Some(crate::versions::pi_home()?.join("node"))
+}
+
+/// The node binary inside one extracted runtime directory.
+fn node_within(dir: &Path) -> Option<PathBuf> {
+    let node = if cfg!(windows) {
+        dir.join("node.exe")
+    } else {
+        dir.join("bin").join("node")
+    };
+    node.exists().then_some(node)
+}
+
+/// The selected version's private runtime, when already installed.
+pub fn installed_runtime() -> Option<PathBuf> {
+    node_within(&runtimes_dir()?.join(selected_version()))
+}
+
+/// The published checksum for `file`, from `SHASUMS256.txt` contents
+/// (lines of `<hex>  <filename>`).
+fn checksum_for(shasums: &str, file: &str) -> Option<String> {
+    shasums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?;
+        (name == file).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+/// Streams a file through SHA-256 and returns the lowercase hex digest.
+fn sha256_hex(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Fetches a small text resource (the checksum file).
+fn fetch_text(url: &str) -> Result<String, String> {
+    crate::http::agent_for(url)
+        .get(url)
+        .set("User-Agent", "package-installer-cli-wrapper")
+        .call()
+        .map_err(|e| {
+            format!(
+                "Cannot reach {}: {} (check your network connection or proxy settings)",
+                url, e
+            )
+        })?
+        .into_string()
+        .map_err(|e| format!("Cannot read the response from {}: {}", url, e))
+}
+
+/// Streams `url` to `dest` with coarse progress on stderr, using the
+/// Content-Length header for the totals when the server sends one.
+fn download(url: &str, dest: &Path) -> Result<(), String> {
+    let response = crate::http::agent_for(url)
+        .get(url)
+        .set("User-Agent", "package-installer-cli-wrapper")
+        .call()
+        .map_err(|e| {
+            format!(
+                "Download of {} failed: {} (check your network connection and retry)",
+                url, e
+            )
+        })?;
+    let total: u64 = response
+        .header("Content-Length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+
+    let mut file = std::fs::File::create(dest)
+        .map_err(|e| format!("Cannot write to {}: {}", dest.display(), e))?;
+    let mut reader = response.into_reader();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut downloaded: u64 = 0;
+    let mut last_reported_percent = 0;
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("Download interrupted: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])
+            .map_err(|e| format!("Cannot write to {}: {}", dest.display(), e))?;
+        downloaded += read as u64;
+        if let Some(percent) = (downloaded * 100).checked_div(total) {
+            let percent = percent as u32;
+            if percent >= last_reported_percent + 10 {
+                last_reported_percent = percent - percent % 10;
+                eprintln!("  {}% ({} / {} bytes)", last_reported_percent, downloaded, total);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Verifies `archive` against `expected_sha` and unpacks it so the
+/// archive's single top-level directory becomes `target`: extraction
+/// goes into a scratch directory next to it and the result is renamed
+/// into place, so `target` either doesn't exist or is complete.
+fn extract_verified(archive: &Path, expected_sha: &str, target: &Path) -> Result<(), String> {
+    let actual = sha256_hex(archive)?;
+    if actual != expected_sha.to_ascii_lowercase() {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {} — the download may be corrupted or tampered with",
+            archive.display(),
+            expected_sha,
+            actual
+        ));
+    }
+
+    let parent = target
+        .parent()
+        .ok_or_else(|| format!("{} has no parent directory", target.display()))?;
+    let scratch = parent.join(format!(".pi.extract-{}", std::process::id()));
+    std::fs::create_dir_all(&scratch)
+        .map_err(|e| format!("Cannot create {}: {}", scratch.display(), e))?;
+    // GNU tar and bsdtar both auto-detect the compression, and bsdtar
+    // (the tar.exe Windows ships) reads the .zip builds too
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(&scratch)
+        .status()
+        .map_err(|e| format!("cannot run tar: {}", e));
+    let extracted = status.and_then(|status| {
+        if status.success() {
+            // The official archives hold one node-<version>-<platform>/
+            let mut entries = std::fs::read_dir(&scratch)
+                .map_err(|e| format!("Cannot read {}: {}", scratch.display(), e))?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir());
+            entries
+                .next()
+                .ok_or_else(|| format!("{} contained no directory", archive.display()))
+        } else {
+            Err(format!("tar exited with {}", status))
+        }
+    });
+    let result = extracted.and_then(|inner| {
+        std::fs::rename(&inner, target)
+            .map_err(|e| format!("Cannot install to {}: {}", target.display(), e))
+    });
+    std::fs::remove_dir_all(&scratch).ok();
+    result
+}
+
+/// Downloads, verifies and installs the selected version, returning
+/// the node binary's path. Already-installed versions return
+/// immediately, so auto mode costs one stat once the runtime is there.
+pub fn install() -> Result<PathBuf, String> {
+    let version = selected_version();
+    let dir = runtimes_dir().ok_or("Cannot determine the user data directory")?;
+    let target = dir.join(&version);
+    if let Some(node) = node_within(&target) {
+        return Ok(node);
+    }
+    let file = archive_name(&version, env::consts::OS, env::consts::ARCH).ok_or_else(|| {
+        format!(
+            "nodejs.org publishes no {} build for {} {}",
+            version,
+            env::consts::OS,
+            env::consts::ARCH
+        )
+    })?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    // One installer at a time; the loser of the race finds the runtime
+    // already in place on the re-check
+    let _lock = crate::lock::for_write(&dir)
+        .ok_or("Another wrapper process is installing Node.js; try again shortly")?;
+    if let Some(node) = node_within(&target) {
+        return Ok(node);
+    }
+
+    let base = dist_base();
+    eprintln!("Downloading Node.js {} ({})...", version, file);
+    let shasums = fetch_text(&format!("{}/{}/SHASUMS256.txt", base, version))?;
+    let expected = checksum_for(&shasums, &file)
+        .ok_or_else(|| format!("SHASUMS256.txt for {} has no entry for {}", version, file))?;
+    let archive = dir.join(format!(".pi.download-{}", std::process::id()));
+    let installed = download(&format!("{}/{}/{}", base, version, file), &archive)
+        .and_then(|()| extract_verified(&archive, &expected, &target));
+    std::fs::remove_file(&archive).ok();
+    installed?;
+
+    let node = node_within(&target).ok_or_else(|| {
+        format!(
+            "The extracted archive holds no node binary under {}",
+            target.display()
+        )
+    })?;
+    eprintln!("Installed Node.js {} to {}", version, target.display());
+    Ok(node)
+}
+
+/// True when `PI_WRAPPER_AUTO_NODE=1` allows downloading the runtime
+/// without an explicit `pi wrapper install-node`.
+pub fn auto_install_enabled() -> bool {
+    env::var("PI_WRAPPER_AUTO_NODE").map(|v| v == "1").unwrap_or(false)
+}
+
+/// Implements `pi wrapper install-node`; returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    if let Some(unknown) = args.first() {
+        eprintln!(
+            "{}",
+            crate::ui::Style::for_stderr()
+                .error(&format!("Unknown option {} (install-node takes none; pin a version with the node_version config key)", unknown))
+        );
+        return 1;
+    }
+    match install() {
+        Ok(node) => {
+            eprintln!("Runtime ready: {}", node.display());
+            0
+        }
+        Err(message) => {
+            eprintln!("{}", crate::ui::Style::for_stderr().error(&message));
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_names_cover_the_published_platforms() {
+        assert_eq!(
+            archive_name("v22.12.0", "linux", "x86_64").as_deref(),
+            Some("node-v22.12.0-linux-x64.tar.gz")
+        );
+        assert_eq!(
+            archive_name("v22.12.0", "macos", "aarch64").as_deref(),
+            Some("node-v22.12.0-darwin-arm64.tar.gz")
+        );
+        assert_eq!(
+            archive_name("v22.12.0", "windows", "x86_64").as_deref(),
+            Some("node-v22.12.0-win-x64.zip")
+        );
+        assert_eq!(archive_name("v22.12.0", "linux", "riscv64"), None);
+    }
+
+    #[test]
+    fn checksums_are_looked_up_by_file_name() {
+        let shasums = "abc123  node-v22.12.0-linux-x64.tar.gz\n\
+                       DEF456  node-v22.12.0-win-x64.zip\n";
+        assert_eq!(
+            checksum_for(shasums, "node-v22.12.0-linux-x64.tar.gz").as_deref(),
+            Some("abc123")
+        );
+        // Hashes compare case-insensitively, names exactly
+        assert_eq!(
+            checksum_for(shasums, "node-v22.12.0-win-x64.zip").as_deref(),
+            Some("def456")
+        );
+        assert_eq!(checksum_for(shasums, "node-v22.12.0-darwin-x64.tar.gz"), None);
+    }
+
+    #[cfg(unix)]
+    mod extraction {
+        use super::*;
+
+        /// A miniature official-style archive: one top-level
+        /// `node-<version>-<platform>/bin/node`, plus the matching
+        /// SHASUMS256.txt line.
+        fn fixture(tag: &str) -> (PathBuf, PathBuf, String) {
+            let root = env::temp_dir().join(format!(
+                "pi-wrapper-nodeinstall-test-{}-{}",
+                tag,
+                std::process::id()
+            ));
+            let stage = root.join("stage").join("node-v1.2.3-linux-x64").join("bin");
+            std::fs::create_dir_all(&stage).unwrap();
+            std::fs::write(stage.join("node"), "#!/bin/sh\necho v1.2.3\n").unwrap();
+            let archive = root.join("node-v1.2.3-linux-x64.tar.gz");
+            let status = Command::new("tar")
+                .arg("-czf")
+                .arg(&archive)
+                .arg("-C")
+                .arg(root.join("stage"))
+                .arg("node-v1.2.3-linux-x64")
+                .status()
+                .unwrap();
+            assert!(status.success());
+            let checksum = sha256_hex(&archive).unwrap();
+            (root, archive, checksum)
+        }
+
+        #[test]
+        fn a_matching_checksum_extracts_into_the_version_directory() {
+            let (root, archive, checksum) = fixture("ok");
+            let shasums = format!("{}  node-v1.2.3-linux-x64.tar.gz\n", checksum);
+            let expected =
+                checksum_for(&shasums, "node-v1.2.3-linux-x64.tar.gz").unwrap();
+
+            let target = root.join("node").join("v1.2.3");
+            std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+            extract_verified(&archive, &expected, &target).unwrap();
+            assert_eq!(node_within(&target), Some(target.join("bin").join("node")));
+            // The scratch directory is gone
+            assert!(!target.parent().unwrap().join(format!(".pi.extract-{}", std::process::id())).exists());
+
+            std::fs::remove_dir_all(&root).ok();
+        }
+
+        #[test]
+        fn a_checksum_mismatch_refuses_to_extract_anything() {
+            let (root, archive, _) = fixture("bad");
+            let target = root.join("node").join("v1.2.3");
+            std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+
+            let err = extract_verified(&archive, &"0".repeat(64), &target).unwrap_err();
+            assert!(err.contains("Checksum mismatch"), "got: {err}");
+            assert!(!target.exists(), "a rejected archive must install nothing");
+
+            std::fs::remove_dir_all(&root).ok();
+        }
+    }
+}
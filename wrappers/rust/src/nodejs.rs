@@ -0,0 +1,230 @@
+//! Node.js interpreter discovery.
+//!
+//! A plain `node` on PATH is not a given: Debian ships the binary as
+//! `nodejs`, and nvm-managed installations are often invisible to
+//! non-interactive shells. Discovery order: the `NODE` environment
+//! variable (honored as-is), `node`, `nodejs`, and finally the
+//! versions installed under `~/.nvm/versions/node/` — preferring the
+//! version named by a `.nvmrc` found while walking up from the working
+//! directory, otherwise the highest installed version. An nvm-derived
+//! path is cached next to the resolution cache so the directory scan
+//! runs once, not on every invocation.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::cache;
+use crate::debug::debug_log;
+
+/// Finds a usable Node.js interpreter, or `None` when there is none.
+/// The decision is made once per process.
+pub fn discover() -> Option<PathBuf> {
+    static CHOSEN: std::sync::OnceLock<Option<PathBuf>> = std::sync::OnceLock::new();
+    CHOSEN
+        .get_or_init(|| {
+            let chosen = choose(
+                env::var("NODE").ok().as_deref(),
+                |name| Command::new(name).arg("--version").output().is_ok(),
+                nvm_versions_dir().as_deref(),
+                nvmrc_version().as_deref(),
+            )?;
+            debug_log!("node interpreter: {}", chosen.display());
+            Some(chosen)
+        })
+        .clone()
+}
+
+/// The discovery decision, with the PATH probe and directory locations
+/// injected so the ordering is testable against fake trees.
+fn choose(
+    env_node: Option<&str>,
+    path_has: impl Fn(&str) -> bool,
+    nvm_dir: Option<&Path>,
+    nvmrc: Option<&str>,
+) -> Option<PathBuf> {
+    if let Some(forced) = env_node {
+        if !forced.is_empty() {
+            return Some(PathBuf::from(forced));
+        }
+    }
+    for name in ["node", "nodejs"] {
+        if path_has(name) {
+            return Some(PathBuf::from(name));
+        }
+    }
+    let nvm_dir = nvm_dir?;
+    if let Some(cached) = load_cached_nvm_node() {
+        return Some(cached);
+    }
+    let found = pick_nvm_node(nvm_dir, nvmrc)?;
+    store_cached_nvm_node(&found);
+    Some(found)
+}
+
+/// `~/.nvm/versions/node`, where nvm keeps its installations.
+fn nvm_versions_dir() -> Option<PathBuf> {
+    let nvm_home = env::var("NVM_DIR")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| env::home_dir().map(|home| home.join(".nvm")))?;
+    Some(nvm_home.join("versions").join("node"))
+}
+
+/// The version named by a `.nvmrc` found from the working directory up
+/// through 5 parents, matching the other project-file walks.
+fn nvmrc_version() -> Option<String> {
+    let cwd = env::current_dir().ok()?;
+    let mut check_dir = Some(cwd.as_path());
+    for _ in 0..=5 {
+        let dir = check_dir?;
+        if let Ok(contents) = std::fs::read_to_string(dir.join(".nvmrc")) {
+            let version = contents.trim();
+            if !version.is_empty() {
+                return Some(version.to_string());
+            }
+        }
+        check_dir = dir.parent();
+    }
+    None
+}
+
+/// Parses an nvm directory name like `v20.11.1` into a version triple.
+fn parse_nvm_version(name: &str) -> Option<(u64, u64, u64)> {
+    let core = name.strip_prefix('v').unwrap_or(name);
+    let mut parts = core.split('.');
+    let major: u64 = parts.next()?.parse().ok()?;
+    let minor: u64 = parts.next()?.parse().ok()?;
+    let patch: u64 = parts.next()?.parse().ok()?;
+    Some((major, minor, patch))
+}
+
+/// True when an installed version satisfies an `.nvmrc` request, which
+/// may name a bare major (`18`), a partial (`18.17`) or a full version.
+fn satisfies_nvmrc(installed: (u64, u64, u64), requested: &str) -> bool {
+    let requested = requested.trim().trim_start_matches('v');
+    let mut parts = requested.split('.');
+    let wanted = [parts.next(), parts.next(), parts.next()];
+    let installed = [installed.0, installed.1, installed.2];
+    wanted.iter().zip(installed).all(|(wanted, actual)| match wanted {
+        Some(wanted) => wanted.parse::<u64>().map(|w| w == actual).unwrap_or(false),
+        None => true,
+    })
+}
+
+/// Picks the node binary from an nvm versions directory: the `.nvmrc`
+/// match when one is requested and installed, else the highest version.
+fn pick_nvm_node(versions_dir: &Path, nvmrc: Option<&str>) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(versions_dir).ok()?;
+    let mut installed: Vec<(u64, u64, u64)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_nvm_version(&entry.file_name().to_string_lossy()))
+        .collect();
+    installed.sort();
+
+    let version = match nvmrc {
+        Some(requested) => installed
+            .iter()
+            .rev()
+            .find(|candidate| satisfies_nvmrc(**candidate, requested))
+            .copied()
+            .or_else(|| installed.last().copied())?,
+        None => installed.last().copied()?,
+    };
+    let node = versions_dir
+        .join(format!("v{}.{}.{}", version.0, version.1, version.2))
+        .join("bin")
+        .join("node");
+    if node.exists() {
+        Some(node)
+    } else {
+        None
+    }
+}
+
+/// Sidecar file next to the resolution cache holding the nvm-derived
+/// interpreter path.
+fn cached_nvm_node_file() -> Option<PathBuf> {
+    Some(cache::cache_file_path()?.with_file_name("node-binary"))
+}
+
+/// A previously discovered nvm interpreter, if it still exists.
+fn load_cached_nvm_node() -> Option<PathBuf> {
+    let cached = PathBuf::from(
+        std::fs::read_to_string(cached_nvm_node_file()?)
+            .ok()?
+            .trim(),
+    );
+    if cached.exists() {
+        Some(cached)
+    } else {
+        None
+    }
+}
+
+/// Records the discovered interpreter; best-effort like the cache.
+fn store_cached_nvm_node(path: &Path) {
+    let Some(file) = cached_nvm_node_file() else {
+        return;
+    };
+    if let Some(parent) = file.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(file, format!("{}\n", path.display()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_nvm_tree(tag: &str, versions: &[&str]) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "pi-wrapper-nodejs-test-{}-{}",
+            tag,
+            std::process::id()
+        ));
+        for version in versions {
+            let bin = dir.join(version).join("bin");
+            std::fs::create_dir_all(&bin).unwrap();
+            std::fs::write(bin.join("node"), "#!/bin/sh\n").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn the_node_env_variable_beats_every_probe() {
+        let chosen = choose(Some("/custom/node"), |_| true, None, None);
+        assert_eq!(chosen, Some(PathBuf::from("/custom/node")));
+    }
+
+    #[test]
+    fn node_is_preferred_over_nodejs_when_both_exist() {
+        let chosen = choose(None, |_| true, None, None);
+        assert_eq!(chosen, Some(PathBuf::from("node")));
+    }
+
+    #[test]
+    fn debian_nodejs_is_found_when_node_is_missing() {
+        let chosen = choose(None, |name| name == "nodejs", None, None);
+        assert_eq!(chosen, Some(PathBuf::from("nodejs")));
+    }
+
+    #[test]
+    fn nvm_picks_the_highest_installed_version_by_default() {
+        let dir = fake_nvm_tree("highest", &["v18.17.0", "v20.11.1", "v9.0.0"]);
+        let chosen = pick_nvm_node(&dir, None).unwrap();
+        assert_eq!(chosen, dir.join("v20.11.1").join("bin").join("node"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn nvmrc_selects_a_matching_major_version() {
+        let dir = fake_nvm_tree("nvmrc", &["v18.17.0", "v20.11.1"]);
+        let chosen = pick_nvm_node(&dir, Some("18")).unwrap();
+        assert_eq!(chosen, dir.join("v18.17.0").join("bin").join("node"));
+        // An uninstalled or unparsable request falls back to the highest
+        let fallback = pick_nvm_node(&dir, Some("lts/hydrogen")).unwrap();
+        assert_eq!(fallback, dir.join("v20.11.1").join("bin").join("node"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
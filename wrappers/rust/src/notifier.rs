@@ -0,0 +1,274 @@
+//! Once-a-day update notice.
+//!
+//! Nothing ever told users a newer CLI exists, so they stay on ancient
+//! versions. The notifier keeps a tiny state file
+//! (`~/.cache/package-installer/update-check.json`) with the last
+//! check time and the latest published version. Each run prints a
+//! single-line notice from that state when it is behind; when the
+//! state is older than a day, a detached background process of this
+//! binary refreshes it from the npm registry. The foreground command
+//! is never delayed: the refresh runs after we have already handed
+//! off, network errors are swallowed, and `PI_NO_UPDATE_CHECK=1` or a
+//! set `CI` variable disables the whole feature.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use package_installer_cli::debug::debug_log;
+
+use crate::{cache, lock, ui};
+
+/// How often the registry is asked for the latest version.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Network budget for the background refresh.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The `<registry>/<package>/latest` metadata URL for a registry base;
+/// a trailing slash on the base is tolerated.
+fn latest_url(base: &str) -> String {
+    format!(
+        "{}/@0xshariq/package-installer/latest",
+        base.trim_end_matches('/')
+    )
+}
+
+/// Process-internal marker: a child spawned only to refresh the state.
+pub const REFRESH_ENV: &str = "PI_WRAPPER_INTERNAL_UPDATE_CHECK";
+
+/// Persisted notifier state.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CheckState {
+    /// Unix seconds of the last registry query (attempted or not).
+    checked_at: u64,
+    /// Latest published version, when the last query succeeded.
+    latest: Option<String>,
+}
+
+/// State file location, next to the resolution cache.
+fn state_path() -> Option<PathBuf> {
+    Some(cache::cache_dir()?.join("update-check.json"))
+}
+
+fn load(path: &std::path::Path) -> CheckState {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &std::path::Path, state: &CheckState) {
+    let Ok(rendered) = serde_json::to_string_pretty(state) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, rendered);
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// True when the notifier must stay completely silent and offline.
+fn disabled() -> bool {
+    let flagged = |name: &str| std::env::var_os(name).is_some_and(|value| !value.is_empty());
+    flagged("PI_NO_UPDATE_CHECK") || flagged("CI")
+}
+
+/// Whether the last check is old enough to warrant a new one.
+fn due(checked_at: u64, now: u64) -> bool {
+    now.saturating_sub(checked_at) >= CHECK_INTERVAL.as_secs()
+}
+
+/// Parses `x.y.z` (ignoring any pre-release suffix) for comparison.
+fn parse_triple(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.');
+    Some((
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+    ))
+}
+
+/// True when `latest` is a strictly newer release than `current`.
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_triple(latest), parse_triple(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// The one-line stderr notice.
+fn notice_line(latest: &str, current: &str) -> String {
+    format!(
+        "A new version ({}) of package-installer is available (you have {}) — run `pi wrapper update`",
+        latest, current
+    )
+}
+
+/// Called once per run with the resolved CLI's version: prints the
+/// notice when the persisted state says we are behind, and kicks off a
+/// detached refresh when the state has gone stale. Never blocks.
+pub fn maybe_notify(current_version: Option<&str>) {
+    if disabled() {
+        return;
+    }
+    let Some(path) = state_path() else {
+        return;
+    };
+    let state = load(&path);
+    if let (Some(current), Some(latest)) = (current_version, state.latest.as_deref()) {
+        if is_newer(latest, current) {
+            eprintln!("{}", ui::Style::for_stderr().warn(&notice_line(latest, current)));
+        }
+    }
+    if due(state.checked_at, unix_now()) {
+        spawn_refresh();
+    }
+}
+
+/// Starts a detached copy of this binary whose only job is to refresh
+/// the state file; its exit is never waited for.
+fn spawn_refresh() {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let spawned = std::process::Command::new(exe)
+        .env(REFRESH_ENV, "1")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+    match spawned {
+        Ok(_) => debug_log!("spawned background update check"),
+        Err(e) => debug_log!("cannot spawn background update check: {}", e),
+    }
+}
+
+/// `registry/<package>/latest` response subset.
+#[derive(Debug, Deserialize)]
+struct LatestResponse {
+    version: String,
+}
+
+/// The background refresh: records the check time first (so even a
+/// hanging registry cannot cause back-to-back checks), then queries
+/// the registry and stores the latest version. All failures are
+/// silent — the worst case is no notice.
+pub fn refresh() -> i32 {
+    let Some(path) = state_path() else {
+        return 0;
+    };
+    let Some(_lock) = lock::for_write(&path) else {
+        return 0;
+    };
+    let mut state = load(&path);
+    state.checked_at = unix_now();
+    save(&path, &state);
+
+    // PI_WRAPPER_REGISTRY_BASE is the test hook; PI_REGISTRY / the
+    // `registry` config key are the user-facing mirror configuration
+    let base = std::env::var("PI_WRAPPER_REGISTRY_BASE")
+        .unwrap_or_else(|_| crate::registry::metadata_base().to_string());
+    let url = latest_url(&base);
+    let agent = crate::http::builder_for(&url)
+        .timeout_connect(FETCH_TIMEOUT)
+        .timeout(FETCH_TIMEOUT)
+        .build();
+    let Ok(response) = agent
+        .get(&url)
+        .set("User-Agent", "package-installer-cli-wrapper")
+        .call()
+    else {
+        return 0;
+    };
+    let Ok(body) = response.into_string() else {
+        return 0;
+    };
+    let Ok(parsed) = serde_json::from_str::<LatestResponse>(&body) else {
+        return 0;
+    };
+    state.latest = Some(parsed.version);
+    save(&path, &state);
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_check_is_due_once_a_day_on_a_mocked_clock() {
+        let day = CHECK_INTERVAL.as_secs();
+        assert!(due(0, day), "a fresh state file has never checked");
+        assert!(due(1_000, 1_000 + day));
+        assert!(!due(1_000, 1_000 + day - 1));
+        // A clock that jumped backwards does not panic or re-check
+        assert!(!due(2_000, 1_000));
+    }
+
+    #[test]
+    fn only_strictly_newer_releases_are_announced() {
+        assert!(is_newer("3.4.0", "3.1.2"));
+        assert!(is_newer("v4.0.0", "3.9.9"));
+        assert!(!is_newer("3.1.2", "3.1.2"));
+        assert!(!is_newer("3.0.0", "3.1.2"));
+        assert!(!is_newer("garbage", "3.1.2"));
+        assert!(!is_newer("3.4.0", "unknown"));
+    }
+
+    #[test]
+    fn the_notice_is_a_single_line_naming_both_versions() {
+        let line = notice_line("3.4.0", "3.1.2");
+        assert!(!line.contains('\n'));
+        assert!(line.contains("(3.4.0)"));
+        assert!(line.contains("you have 3.1.2"));
+        assert!(line.contains("pi wrapper update"));
+    }
+
+    #[test]
+    fn metadata_urls_follow_the_registry_base() {
+        assert_eq!(
+            latest_url(crate::registry::DEFAULT_BASE),
+            "https://registry.npmjs.org/@0xshariq/package-installer/latest"
+        );
+        assert_eq!(
+            latest_url("https://artifactory.corp.example/api/npm/npm-remote/"),
+            "https://artifactory.corp.example/api/npm/npm-remote/@0xshariq/package-installer/latest"
+        );
+    }
+
+    #[test]
+    fn state_files_round_trip_and_tolerate_corruption() {
+        let dir = std::env::temp_dir().join(format!("pi-wrapper-notifier-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("update-check.json");
+
+        save(
+            &path,
+            &CheckState {
+                checked_at: 42,
+                latest: Some("3.4.0".to_string()),
+            },
+        );
+        let state = load(&path);
+        assert_eq!(state.checked_at, 42);
+        assert_eq!(state.latest.as_deref(), Some("3.4.0"));
+
+        std::fs::write(&path, "not json").unwrap();
+        let state = load(&path);
+        assert_eq!(state.checked_at, 0);
+        assert_eq!(state.latest, None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,88 @@
+//! Custom npm registry support for locked-down networks.
+//!
+//! Shops that mirror npm through Artifactory or Verdaccio often block
+//! registry.npmjs.org outright, so every npm command the wrapper
+//! constructs (the auto-install prompt, the suggested commands in the
+//! failure text) and the update notifier's metadata queries must
+//! target the mirror instead. `PI_REGISTRY` wins over the `registry`
+//! config key; a value that is not an http(s) URL is warned about once
+//! and the defaults are used.
+
+use std::env;
+use std::sync::OnceLock;
+
+use crate::ui;
+
+/// The registry everything defaults to.
+pub const DEFAULT_BASE: &str = "https://registry.npmjs.org";
+
+/// Sanity check, not full URL parsing: an http(s) scheme, something
+/// after it, and no whitespace. Anything subtler is left to the
+/// package manager's own error reporting.
+fn valid(url: &str) -> bool {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    matches!(rest, Some(host) if !host.is_empty() && !url.chars().any(char::is_whitespace))
+}
+
+/// The raw override, environment first, then config.
+fn raw_override() -> Option<String> {
+    if let Ok(url) = env::var("PI_REGISTRY") {
+        if !url.is_empty() {
+            return Some(url);
+        }
+    }
+    crate::wrapper_config()
+        .ok()
+        .and_then(|config| config.registry.clone())
+}
+
+/// The validated registry override, decided once per process. A broken
+/// value warns and behaves as if nothing were configured.
+pub fn overridden() -> Option<&'static str> {
+    static OVERRIDE: OnceLock<Option<String>> = OnceLock::new();
+    OVERRIDE
+        .get_or_init(|| {
+            let url = raw_override()?.trim_end_matches('/').to_string();
+            if valid(&url) {
+                Some(url)
+            } else {
+                eprintln!(
+                    "{}",
+                    ui::Style::for_stderr().warn(&format!(
+                        "Ignoring invalid registry {:?} (expected an http(s) URL); using {}",
+                        url, DEFAULT_BASE
+                    ))
+                );
+                None
+            }
+        })
+        .as_deref()
+}
+
+/// Base URL for version metadata queries.
+pub fn metadata_base() -> &'static str {
+    overridden().unwrap_or(DEFAULT_BASE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plausible_http_urls_pass_validation() {
+        assert!(valid("https://registry.npmjs.org"));
+        assert!(valid("https://artifactory.corp.example/api/npm/npm-remote"));
+        assert!(valid("http://localhost:4873"));
+        for broken in [
+            "",
+            "artifactory.corp.example",
+            "ftp://mirror.example",
+            "https://",
+            "https://has spaces.example",
+        ] {
+            assert!(!valid(broken), "{broken:?} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Machine-readable wrapper output.
+//!
+//! `--wrapper-json` (or `PI_WRAPPER_OUTPUT=json`) switches everything
+//! the *wrapper* says — resolution failures, dry-run reports, version
+//! info, its own errors — to single-line JSON objects on stderr, while
+//! the child CLI's streams stay untouched, so tooling that wraps `pi`
+//! can parse outcomes instead of scraping styled text.
+//!
+//! Schema: each line is one object tagged by `"type"`:
+//!
+//! ```json
+//! {"type":"resolution_error","message":"...","attempts":["..."]}
+//! {"type":"dry_run","source":"...","program":"...","args":["..."]}
+//! {"type":"version","wrapper_version":"...","cli_path":null,"cli_version":null}
+//! {"type":"error","message":"..."}
+//! ```
+//!
+//! The same [`WrapperMessage`] values also drive the human-readable
+//! rendering, so the two formats cannot drift apart.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+
+use package_installer_cli::debug::debug_log;
+
+use crate::ui;
+
+/// Set when `--wrapper-json` was passed.
+static JSON_FLAG: AtomicBool = AtomicBool::new(false);
+
+pub fn force_json() {
+    JSON_FLAG.store(true, Ordering::Relaxed);
+}
+
+/// True when wrapper output must be JSON, by flag or
+/// `PI_WRAPPER_OUTPUT=json`.
+pub fn json_enabled() -> bool {
+    JSON_FLAG.load(Ordering::Relaxed)
+        || std::env::var("PI_WRAPPER_OUTPUT")
+            .map(|value| value == "json")
+            .unwrap_or(false)
+}
+
+/// One wrapper-originated message, in either output format.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WrapperMessage {
+    /// Every resolution attempt failed; `attempts` holds one line per
+    /// probed source, in probe order.
+    ResolutionError {
+        message: String,
+        attempts: Vec<String>,
+    },
+    /// `--wrapper-dry-run`: what would have run.
+    DryRun {
+        source: String,
+        program: String,
+        args: Vec<String>,
+    },
+    /// `--wrapper-version`: the wrapper itself plus the CLI it would
+    /// pick.
+    Version {
+        wrapper_version: &'static str,
+        cli_path: Option<String>,
+        cli_version: Option<String>,
+    },
+    /// A fatal wrapper-level error (bad flags, unusable directories).
+    Error { message: String },
+}
+
+impl WrapperMessage {
+    /// The human rendering, exactly as the wrapper always printed it.
+    fn render_text(&self) -> String {
+        match self {
+            WrapperMessage::ResolutionError { message, attempts } => {
+                let style = ui::Style::for_stderr();
+                let mut text =
+                    style.error(&format!("Failed to execute the CLI: {}; attempts:", message));
+                for attempt in attempts {
+                    text.push_str(&format!("\n  - {}", attempt));
+                }
+                text.push('\n');
+                text.push_str(&ui::usage_instructions(style, crate::registry::overridden()));
+                text
+            }
+            WrapperMessage::DryRun {
+                source,
+                program,
+                args,
+            } => {
+                let mut text = format!("source: {}\n", source);
+                text.push_str(&format!("program: {}\n", crate::shell_quote(program)));
+                for arg in args {
+                    text.push_str(&format!("arg: {}\n", crate::shell_quote(arg)));
+                }
+                text.pop();
+                text
+            }
+            WrapperMessage::Version {
+                wrapper_version,
+                cli_path,
+                cli_version,
+            } => {
+                let mut text = format!("package-installer-cli wrapper {}\n", wrapper_version);
+                match (cli_path, cli_version) {
+                    (Some(path), Some(version)) => {
+                        text.push_str(&format!("CLI: {} (version {})", path, version))
+                    }
+                    (Some(path), None) => {
+                        text.push_str(&format!("CLI: {} (version unknown)", path))
+                    }
+                    (None, _) => text.push_str("CLI: not found"),
+                }
+                text
+            }
+            WrapperMessage::Error { message } => ui::Style::for_stderr().error(message),
+        }
+    }
+
+    /// Whether the human rendering belongs on stderr (JSON always
+    /// does).
+    fn text_on_stderr(&self) -> bool {
+        matches!(
+            self,
+            WrapperMessage::ResolutionError { .. } | WrapperMessage::Error { .. }
+        )
+    }
+
+    /// Prints the message in the active format. The JSON form also
+    /// goes through [`debug_log!`], so the persistent log file sees
+    /// every wrapper message regardless of the output mode.
+    pub fn emit(&self) {
+        let line = serde_json::to_string(self).ok();
+        if let Some(line) = &line {
+            debug_log!("{}", line);
+        }
+        if json_enabled() {
+            if let Some(line) = line {
+                eprintln!("{}", line);
+            }
+            return;
+        }
+        if self.text_on_stderr() {
+            eprintln!("{}", self.render_text());
+        } else {
+            println!("{}", self.render_text());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Exact-output snapshots: the schema is a contract for external
+    // tooling, so any change here must be deliberate.
+
+    #[test]
+    fn resolution_error_schema() {
+        let message = WrapperMessage::ResolutionError {
+            message: "no CLI installation could be used".to_string(),
+            attempts: vec![
+                "local node_modules CLI: not present".to_string(),
+                "bundled executable: not present".to_string(),
+            ],
+        };
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"resolution_error","message":"no CLI installation could be used","attempts":["local node_modules CLI: not present","bundled executable: not present"]}"#
+        );
+    }
+
+    #[test]
+    fn dry_run_schema() {
+        let message = WrapperMessage::DryRun {
+            source: "local node_modules CLI".to_string(),
+            program: "/usr/bin/node".to_string(),
+            args: vec!["/p/dist/index.js".to_string(), "create".to_string()],
+        };
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"dry_run","source":"local node_modules CLI","program":"/usr/bin/node","args":["/p/dist/index.js","create"]}"#
+        );
+    }
+
+    #[test]
+    fn version_schema() {
+        let message = WrapperMessage::Version {
+            wrapper_version: "2.5.0",
+            cli_path: None,
+            cli_version: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"version","wrapper_version":"2.5.0","cli_path":null,"cli_version":null}"#
+        );
+    }
+
+    #[test]
+    fn error_schema() {
+        let message = WrapperMessage::Error {
+            message: "-C/--cwd requires a directory argument".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"error","message":"-C/--cwd requires a directory argument"}"#
+        );
+    }
+
+    #[test]
+    fn human_renderings_match_the_established_formats() {
+        let dry_run = WrapperMessage::DryRun {
+            source: "bundled executable".to_string(),
+            program: "/opt/pi".to_string(),
+            args: vec!["create".to_string(), "my app".to_string()],
+        };
+        assert_eq!(
+            dry_run.render_text(),
+            "source: bundled executable\nprogram: /opt/pi\narg: create\narg: 'my app'"
+        );
+
+        let version = WrapperMessage::Version {
+            wrapper_version: "2.5.0",
+            cli_path: Some("/opt/pi".to_string()),
+            cli_version: Some("3.1.2".to_string()),
+        };
+        assert_eq!(
+            version.render_text(),
+            "package-installer-cli wrapper 2.5.0\nCLI: /opt/pi (version 3.1.2)"
+        );
+    }
+}
@@ -0,0 +1,972 @@
+//! The CLI resolution engine.
+//!
+//! [`Resolver`] owns the walk that decides which CLI installation runs.
+//! It is configured with the working directory, the wrapper binary's
+//! own locations and the probe order — all plain data supplied by the
+//! caller, so the engine itself never reads process state. Probing goes
+//! through the injected [`Fs`] trait and execution through the
+//! [`Runner`] trait, which keeps the ordering, fall-through behavior
+//! and stat budget testable without a filesystem layout or a spawned
+//! process; the binary installs the real implementations in `main.rs`.
+//! Candidates are generated lazily and probes memoized across the
+//! walk, so a hit in the first location short-circuits everything
+//! else — including the on-demand package-manager and archive probes.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::config::{ResolutionStep, WrapperConfig};
+use crate::debug::debug_log;
+
+/// Upper bound on the parent-directory walk when no project boundary
+/// is found, overridable via `PI_WRAPPER_MAX_DEPTH`.
+pub const DEFAULT_MAX_WALK_DEPTH: usize = 64;
+
+/// Relative entrypoint paths a local `node_modules` may hold.
+pub const LOCAL_ENTRY_PATHS: [&str; 2] = [
+    "node_modules/@0xshariq/package-installer/dist/index.js",
+    "node_modules/package-installer-cli/dist/index.js",
+];
+
+/// Files/directories that mark the root of a project.
+const PROJECT_BOUNDARY_MARKERS: [&str; 3] = ["package.json", "pnpm-workspace.yaml", ".git"];
+
+/// True for directories that mark the root of a project; the walk
+/// checks such a directory but never goes above it, so a stale CLI in
+/// an unrelated sibling checkout higher in the tree is never picked up.
+pub fn is_project_boundary(dir: &Path) -> bool {
+    PROJECT_BOUNDARY_MARKERS
+        .iter()
+        .any(|marker| dir.join(marker).exists())
+}
+
+/// The filesystem view the walk probes through. A plain closure
+/// implements it, so tests keep describing layouts as path lists; the
+/// binary passes its logging `probe_exists`.
+pub trait Fs {
+    fn exists(&self, path: &Path) -> bool;
+}
+
+impl<F: Fn(&Path) -> bool> Fs for F {
+    fn exists(&self, path: &Path) -> bool {
+        self(path)
+    }
+}
+
+/// Memoizes probes for the duration of one walk, so no path is ever
+/// statted twice — the boundary markers shared by the three local
+/// sources above all.
+struct ProbeCache<'a> {
+    fs: &'a dyn Fs,
+    seen: std::collections::HashMap<PathBuf, bool>,
+}
+
+impl<'a> ProbeCache<'a> {
+    fn new(fs: &'a dyn Fs) -> ProbeCache<'a> {
+        ProbeCache {
+            fs,
+            seen: std::collections::HashMap::new(),
+        }
+    }
+
+    fn exists(&mut self, path: &Path) -> bool {
+        if let Some(&known) = self.seen.get(path) {
+            return known;
+        }
+        let exists = self.fs.exists(path);
+        self.seen.insert(path.to_path_buf(), exists);
+        exists
+    }
+
+    /// Boundary check through the cache, mirroring
+    /// [`is_project_boundary`].
+    fn is_boundary(&mut self, dir: &Path) -> bool {
+        PROJECT_BOUNDARY_MARKERS
+            .iter()
+            .any(|marker| self.exists(&dir.join(marker)))
+    }
+}
+
+/// Directories whose `node_modules` the local probes check: from
+/// `start` up to and including the first project boundary, or to the
+/// filesystem root, capped at `max_depth` levels as a safety valve.
+pub fn local_search_dirs(start: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut check_dir = Some(start);
+    for _ in 0..max_depth {
+        let Some(dir) = check_dir else { break };
+        dirs.push(dir.to_path_buf());
+        if is_project_boundary(dir) {
+            break;
+        }
+        check_dir = dir.parent();
+    }
+    dirs
+}
+
+/// Candidate file names for the bundled pi executable in `dir`.
+///
+/// On Windows the bundle ships `pi.exe`, but npm-style installations may
+/// leave `pi.cmd` or `pi.ps1` shims instead, so all three are probed in
+/// that order. Platform is a parameter so both lists stay testable from
+/// either host.
+pub fn pi_executable_candidates(dir: &Path, windows: bool) -> Vec<PathBuf> {
+    let names: &[&str] = if windows {
+        &["pi.exe", "pi.cmd", "pi.ps1"]
+    } else {
+        &["pi"]
+    };
+    names.iter().map(|name| dir.join(name)).collect()
+}
+
+/// Directories that may hold `bundle-standalone/` for this binary: the
+/// invoked location's directory and, when the binary is reached through
+/// a symlink, the canonical target's directory too — plus the keg
+/// prefix when the real binary lives under a Homebrew-style `libexec/`.
+/// The canonicalization step is injected so symlinked layouts are
+/// testable; a failing canonicalization degrades to the invoked
+/// directory alone.
+pub fn executable_dirs_for(
+    exe_path: &Path,
+    canonicalize: impl Fn(&Path) -> Option<PathBuf>,
+) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = exe_path.parent() {
+        dirs.push(dir.to_path_buf());
+    }
+    if let Some(resolved) = canonicalize(exe_path) {
+        if let Some(dir) = resolved.parent() {
+            if !dirs.contains(&dir.to_path_buf()) {
+                dirs.push(dir.to_path_buf());
+            }
+            if dir.file_name().map(|name| name == "libexec").unwrap_or(false) {
+                if let Some(prefix) = dir.parent() {
+                    dirs.push(prefix.to_path_buf());
+                }
+            }
+        }
+    }
+    dirs
+}
+
+/// What resolution produced: an entrypoint plus how it must be run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedCli {
+    /// A JS entrypoint (`dist/index.js`) to run under a JS runtime.
+    NodeScript(PathBuf),
+    /// A standalone executable or shim to launch directly.
+    Executable(PathBuf),
+    /// A Yarn PnP manifest: the CLI runs under `node --require
+    /// <manifest>` with the entry resolved at launch time.
+    PnpManifest(PathBuf),
+}
+
+impl ResolvedCli {
+    /// The on-disk path the resolution is anchored to.
+    pub fn path(&self) -> &Path {
+        match self {
+            ResolvedCli::NodeScript(path)
+            | ResolvedCli::Executable(path)
+            | ResolvedCli::PnpManifest(path) => path,
+        }
+    }
+}
+
+/// One place the resolver knows how to find the CLI. Sources are probed
+/// grouped by their [`ResolutionStep`], in the order listed here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    LocalNpm,
+    LocalBinShim,
+    YarnPnp,
+    GlobalNpm,
+    BundledExecutable,
+    UserBundle,
+    DevBundle,
+}
+
+impl Source {
+    /// The sources making up one configured resolution step.
+    pub fn for_step(step: ResolutionStep) -> &'static [Source] {
+        match step {
+            ResolutionStep::Local => &[Source::LocalNpm, Source::LocalBinShim, Source::YarnPnp],
+            ResolutionStep::Global => &[Source::GlobalNpm],
+            ResolutionStep::Bundled => &[
+                Source::BundledExecutable,
+                Source::UserBundle,
+                Source::DevBundle,
+            ],
+        }
+    }
+
+    /// Short name for a winning source, as shown in the dry-run
+    /// report's `source:` line.
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::LocalNpm => "local node_modules",
+            Source::LocalBinShim => "node_modules/.bin shim",
+            Source::YarnPnp => "yarn pnp",
+            Source::GlobalNpm => "global installation",
+            Source::BundledExecutable => "bundled standalone",
+            Source::UserBundle => "downloaded standalone",
+            Source::DevBundle => "bundled standalone (development)",
+        }
+    }
+
+    /// How this source is described in the aggregated failure list when
+    /// it contributed no candidate.
+    /// True for sources probed relative to the working directory. When
+    /// the working directory itself is gone these are skipped rather
+    /// than treated as fatal.
+    pub fn relies_on_cwd(self) -> bool {
+        matches!(
+            self,
+            Source::LocalNpm | Source::LocalBinShim | Source::YarnPnp | Source::DevBundle
+        )
+    }
+
+    /// The coarse install-channel token this source maps to, exported
+    /// to the child CLI as `PI_WRAPPER_RESOLUTION`.
+    pub fn channel(self) -> &'static str {
+        match self {
+            Source::LocalNpm | Source::LocalBinShim | Source::YarnPnp => "local-npm",
+            Source::GlobalNpm => "global-npm",
+            Source::BundledExecutable | Source::UserBundle => "bundled",
+            Source::DevBundle => "dev",
+        }
+    }
+
+    pub fn missing_description(self) -> &'static str {
+        match self {
+            Source::LocalNpm => "local node_modules installation",
+            Source::LocalBinShim => "node_modules/.bin shim",
+            Source::YarnPnp => "Yarn PnP installation (.pnp.cjs manifest)",
+            Source::GlobalNpm => "global package manager installation",
+            Source::BundledExecutable => "bundled standalone executable (next to the binary)",
+            Source::UserBundle => "downloaded standalone executable (user data directory)",
+            Source::DevBundle => "bundled standalone executable (development location)",
+        }
+    }
+}
+
+/// One source's contribution to a failed walk.
+#[derive(Debug, PartialEq)]
+pub enum Attempt<E> {
+    /// No candidate path for this source exists.
+    Missing(Source),
+    /// A candidate was found but running it failed; the walk went on.
+    Failed(E),
+}
+
+/// Executes a resolved CLI. The binary implements this over real
+/// processes (banners, caching, verification and all — which is why
+/// the winning [`Source`] is passed along); tests implement it in
+/// memory, so the walk's ordering and fall-through behavior can be
+/// asserted without spawning anything.
+pub trait Runner {
+    type Error;
+    fn run(
+        &mut self,
+        source: Source,
+        cli: &ResolvedCli,
+        args: &[OsString],
+    ) -> Result<i32, Self::Error>;
+}
+
+/// Default for [`Resolver::global_candidates`]: no globals.
+fn no_global_candidates() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// Default for [`Resolver::bundle_fallback`]: no extra bundles.
+fn no_bundle_fallback() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// The resolution walk's inputs, all plain data: the binary fills this
+/// from the real process state (`real_resolver` in `main.rs`), tests
+/// construct it directly.
+pub struct Resolver {
+    /// Directory the local probes walk up from; `None` (an unreadable
+    /// working directory) simply yields no local or dev candidates.
+    pub cwd: Option<PathBuf>,
+    /// Directories that may hold `bundle-standalone/` for this binary.
+    pub exe_dirs: Vec<PathBuf>,
+    /// Per-user bundle directory maintained by `pi wrapper update`.
+    pub user_bundle_dir: Option<PathBuf>,
+    /// Produces global install candidates on demand — a function so the
+    /// package managers are only queried when the global step is
+    /// actually reached.
+    pub global_candidates: fn() -> Vec<PathBuf>,
+    /// Produces additional bundled-executable candidates on demand,
+    /// after the loose `bundle-standalone/` directories came up empty —
+    /// the binary hooks lazy archive extraction in here.
+    pub bundle_fallback: fn() -> Vec<PathBuf>,
+    /// Step order from the wrapper config.
+    pub order: Vec<ResolutionStep>,
+    /// Cap on the parent-directory walk.
+    pub max_depth: usize,
+    /// `--wrapper-no-local` / `PI_WRAPPER_NO_LOCAL=1`: the whole local
+    /// step is skipped.
+    pub local_disabled: bool,
+    /// Probe Windows executable names (`pi.exe` and shims) too.
+    pub windows: bool,
+}
+
+impl Resolver {
+    /// A resolver with everything but the working directory defaulted:
+    /// no binary locations, no globals, the default order and depth.
+    pub fn new(cwd: Option<PathBuf>) -> Resolver {
+        Resolver {
+            cwd,
+            exe_dirs: Vec::new(),
+            user_bundle_dir: None,
+            global_candidates: no_global_candidates,
+            bundle_fallback: no_bundle_fallback,
+            order: WrapperConfig::DEFAULT_ORDER.to_vec(),
+            max_depth: DEFAULT_MAX_WALK_DEPTH,
+            local_disabled: false,
+            windows: cfg!(windows),
+        }
+    }
+
+    /// Directories the local sources check, per [`local_search_dirs`].
+    fn search_dirs(&self) -> Vec<PathBuf> {
+        match &self.cwd {
+            Some(cwd) => local_search_dirs(cwd, self.max_depth),
+            None => Vec::new(),
+        }
+    }
+
+    /// Every path `source` would probe, most specific first.
+    pub fn candidate_paths(&self, source: Source) -> Vec<PathBuf> {
+        match source {
+            Source::LocalNpm => {
+                let mut paths = Vec::new();
+                for dir in self.search_dirs() {
+                    for local_path in &LOCAL_ENTRY_PATHS {
+                        paths.push(dir.join(local_path));
+                    }
+                }
+                paths
+            }
+            Source::LocalBinShim => {
+                let mut paths = Vec::new();
+                for dir in self.search_dirs() {
+                    let bin = dir.join("node_modules").join(".bin");
+                    paths.push(bin.join("pi"));
+                    if self.windows {
+                        paths.push(bin.join("pi.cmd"));
+                        paths.push(bin.join("pi.ps1"));
+                    }
+                }
+                paths
+            }
+            Source::YarnPnp => {
+                let mut paths = Vec::new();
+                for dir in self.search_dirs() {
+                    paths.push(dir.join(".pnp.cjs"));
+                    paths.push(dir.join(".pnp.js"));
+                }
+                paths
+            }
+            Source::GlobalNpm => (self.global_candidates)(),
+            Source::BundledExecutable => {
+                let mut paths: Vec<PathBuf> = self
+                    .exe_dirs
+                    .iter()
+                    .flat_map(|dir| {
+                        pi_executable_candidates(&dir.join("bundle-standalone"), self.windows)
+                    })
+                    .collect();
+                // Loose directories first, so an extracted archive can
+                // never shadow an unpacked bundle sitting right there
+                paths.extend((self.bundle_fallback)());
+                paths
+            }
+            Source::UserBundle => match &self.user_bundle_dir {
+                Some(dir) => pi_executable_candidates(dir, self.windows),
+                None => Vec::new(),
+            },
+            Source::DevBundle => match &self.cwd {
+                Some(cwd) => {
+                    pi_executable_candidates(&cwd.join("bundle-standalone"), self.windows)
+                }
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Walks up from the working directory, probing each directory's
+    /// candidates before even statting the next level's boundary
+    /// markers — a hit in the very first directory costs exactly one
+    /// stat, not a full parent walk.
+    fn first_in_walk(
+        &self,
+        cache: &mut ProbeCache,
+        candidates_for: impl Fn(&Path) -> Vec<PathBuf>,
+    ) -> Option<PathBuf> {
+        let mut next = self.cwd.as_deref();
+        for _ in 0..self.max_depth {
+            let dir = next?;
+            for path in candidates_for(dir) {
+                if cache.exists(&path) {
+                    return Some(path);
+                }
+            }
+            if cache.is_boundary(dir) {
+                return None;
+            }
+            next = dir.parent();
+        }
+        None
+    }
+
+    /// The first existing path for `source`, probing lazily:
+    /// candidates are generated one directory at a time and the
+    /// on-demand producers (package-manager queries, archive
+    /// extraction) only run when every cheaper candidate has missed.
+    fn first_existing(&self, source: Source, cache: &mut ProbeCache) -> Option<PathBuf> {
+        match source {
+            Source::LocalNpm => self.first_in_walk(cache, |dir| {
+                LOCAL_ENTRY_PATHS
+                    .iter()
+                    .map(|local_path| dir.join(local_path))
+                    .collect()
+            }),
+            Source::LocalBinShim => self.first_in_walk(cache, |dir| {
+                let bin = dir.join("node_modules").join(".bin");
+                let mut paths = vec![bin.join("pi")];
+                if self.windows {
+                    paths.push(bin.join("pi.cmd"));
+                    paths.push(bin.join("pi.ps1"));
+                }
+                paths
+            }),
+            Source::YarnPnp => {
+                self.first_in_walk(cache, |dir| vec![dir.join(".pnp.cjs"), dir.join(".pnp.js")])
+            }
+            Source::GlobalNpm => (self.global_candidates)()
+                .into_iter()
+                .find(|path| cache.exists(path)),
+            Source::BundledExecutable => {
+                let loose = self
+                    .exe_dirs
+                    .iter()
+                    .flat_map(|dir| {
+                        pi_executable_candidates(&dir.join("bundle-standalone"), self.windows)
+                    })
+                    .find(|path| cache.exists(path));
+                // The fallback may extract archives — only reached
+                // when every loose candidate missed
+                loose.or_else(|| {
+                    (self.bundle_fallback)()
+                        .into_iter()
+                        .find(|path| cache.exists(path))
+                })
+            }
+            Source::UserBundle => match &self.user_bundle_dir {
+                Some(dir) => pi_executable_candidates(dir, self.windows)
+                    .into_iter()
+                    .find(|path| cache.exists(path)),
+                None => None,
+            },
+            Source::DevBundle => match &self.cwd {
+                Some(cwd) => {
+                    pi_executable_candidates(&cwd.join("bundle-standalone"), self.windows)
+                        .into_iter()
+                        .find(|path| cache.exists(path))
+                }
+                None => None,
+            },
+        }
+    }
+
+    /// The first existing candidate for `source`, tagged with how it
+    /// must be run.
+    pub fn find(&self, source: Source, fs: &dyn Fs) -> Option<ResolvedCli> {
+        self.find_cached(source, &mut ProbeCache::new(fs))
+    }
+
+    fn find_cached(&self, source: Source, cache: &mut ProbeCache) -> Option<ResolvedCli> {
+        let path = self.first_existing(source, cache)?;
+        Some(match source {
+            Source::LocalNpm | Source::GlobalNpm => ResolvedCli::NodeScript(path),
+            Source::YarnPnp => ResolvedCli::PnpManifest(path),
+            Source::LocalBinShim
+            | Source::BundledExecutable
+            | Source::UserBundle
+            | Source::DevBundle => ResolvedCli::Executable(path),
+        })
+    }
+
+    /// Walks the configured steps in order and hands each source's
+    /// first existing candidate to `runner`. A runner failure is
+    /// recorded and the walk continues — an install with a broken
+    /// runtime must not shadow a usable bundle further down the chain.
+    /// When nothing runs, every attempt is returned so the caller can
+    /// report what was actually tried. Probes are memoized across the
+    /// whole walk, so sources sharing paths never stat them twice.
+    pub fn run<R: Runner>(
+        &self,
+        fs: &dyn Fs,
+        args: &[OsString],
+        runner: &mut R,
+    ) -> Result<i32, Vec<Attempt<R::Error>>> {
+        let mut cache = ProbeCache::new(fs);
+        let mut attempts = Vec::new();
+        for step in &self.order {
+            if *step == ResolutionStep::Local && self.local_disabled {
+                debug_log!("resolution step: local — skipped (disabled)");
+                continue;
+            }
+            debug_log!("resolution step: {}", step.name());
+            for source in Source::for_step(*step) {
+                match self.find_cached(*source, &mut cache) {
+                    Some(cli) => match runner.run(*source, &cli, args) {
+                        Ok(exit_code) => return Ok(exit_code),
+                        Err(error) => attempts.push(Attempt::Failed(error)),
+                    },
+                    None => attempts.push(Attempt::Missing(*source)),
+                }
+            }
+        }
+        Err(attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walk_tree(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pi-wrapper-walk-test-{}-{}",
+            tag,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn the_walk_stops_at_a_project_boundary_but_checks_it() {
+        let root = walk_tree("boundary");
+        let project = root.join("checkout").join("project");
+        let nested = project.join("src").join("deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(project.join("package.json"), "{}").unwrap();
+
+        let dirs = local_search_dirs(&nested, DEFAULT_MAX_WALK_DEPTH);
+        assert_eq!(
+            dirs,
+            vec![nested.clone(), project.join("src"), project.clone()]
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn the_walk_reaches_the_filesystem_root_without_a_boundary() {
+        let root = walk_tree("rootstop");
+        let nested = root.join("no").join("markers").join("here");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let dirs = local_search_dirs(&nested, DEFAULT_MAX_WALK_DEPTH);
+        let last = dirs.last().unwrap();
+        assert!(
+            last.parent().is_none() || is_project_boundary(last),
+            "walk ended at {} without reaching the root or a boundary",
+            last.display()
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn deeply_nested_monorepo_packages_still_reach_the_repo_root() {
+        let root = walk_tree("monorepo");
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        let nested = root.join("a/b/c/d/e/f/g");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let dirs = local_search_dirs(&nested, DEFAULT_MAX_WALK_DEPTH);
+        assert_eq!(dirs.last(), Some(&root));
+        assert_eq!(dirs.len(), 8);
+        // The old fixed 5-level walk would have stopped short
+        assert!(local_search_dirs(&nested, 5).last() != Some(&root));
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_installs_probe_the_real_binary_directory_too() {
+        let root = walk_tree("symlink");
+        let release = root.join("release");
+        std::fs::create_dir_all(release.join("bundle-standalone")).unwrap();
+        std::fs::write(release.join("pi-wrapper"), "binary").unwrap();
+        std::fs::write(release.join("bundle-standalone").join("pi"), "#!/bin/sh\n").unwrap();
+        let bin = root.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let link = bin.join("pi");
+        std::os::unix::fs::symlink(release.join("pi-wrapper"), &link).unwrap();
+
+        let dirs = executable_dirs_for(&link, |path| std::fs::canonicalize(path).ok());
+        assert_eq!(dirs.first(), Some(&bin));
+        assert!(dirs.contains(&std::fs::canonicalize(&release).unwrap()));
+        // The bundle next to the real binary is reachable through one
+        // of the probed directories
+        assert!(dirs
+            .iter()
+            .any(|dir| dir.join("bundle-standalone").join("pi").exists()));
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn homebrew_libexec_layouts_probe_the_keg_prefix() {
+        let exe = Path::new("/opt/homebrew/bin/pi");
+        let dirs = executable_dirs_for(exe, |_| {
+            Some(PathBuf::from("/opt/homebrew/Cellar/pi/2.5.0/libexec/pi"))
+        });
+        assert_eq!(
+            dirs,
+            vec![
+                PathBuf::from("/opt/homebrew/bin"),
+                PathBuf::from("/opt/homebrew/Cellar/pi/2.5.0/libexec"),
+                PathBuf::from("/opt/homebrew/Cellar/pi/2.5.0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_canonicalization_degrades_to_the_invoked_directory() {
+        let dirs = executable_dirs_for(Path::new("/home/user/bin/pi"), |_| None);
+        assert_eq!(dirs, vec![PathBuf::from("/home/user/bin")]);
+    }
+
+    #[test]
+    fn windows_candidates_cover_exe_cmd_and_ps1_shims() {
+        let candidates = pi_executable_candidates(Path::new("bundle-standalone"), true);
+        let names: Vec<_> = candidates
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, ["pi.exe", "pi.cmd", "pi.ps1"]);
+    }
+
+    #[test]
+    fn unix_candidates_are_the_bare_binary() {
+        let candidates = pi_executable_candidates(Path::new("bundle-standalone"), false);
+        assert_eq!(candidates, [Path::new("bundle-standalone").join("pi")]);
+    }
+
+    // -- Resolver walk tests: all in memory, the "filesystem" is a
+    // list of present paths and the runner records what it was asked
+    // to run.
+
+    fn global_fixture() -> Vec<PathBuf> {
+        vec![PathBuf::from(
+            "/usr/local/lib/node_modules/@0xshariq/package-installer/dist/index.js",
+        )]
+    }
+
+    /// A resolver over a synthetic layout; `/repo/pkg` has no on-disk
+    /// boundary markers, so the walk runs `/repo/pkg`, `/repo`, `/`.
+    fn test_resolver() -> Resolver {
+        let mut resolver = Resolver::new(Some(PathBuf::from("/repo/pkg")));
+        resolver.exe_dirs = vec![PathBuf::from("/opt/pi")];
+        resolver.user_bundle_dir = Some(PathBuf::from("/home/user/.local/share/pi-bundle"));
+        resolver.global_candidates = global_fixture;
+        resolver.windows = false;
+        resolver
+    }
+
+    struct FakeRunner {
+        /// Paths the fake refuses to run, exercising fall-through.
+        refuse: Vec<PathBuf>,
+        ran: Vec<(Source, ResolvedCli)>,
+    }
+
+    impl FakeRunner {
+        fn new() -> FakeRunner {
+            FakeRunner {
+                refuse: Vec::new(),
+                ran: Vec::new(),
+            }
+        }
+    }
+
+    impl Runner for FakeRunner {
+        type Error = String;
+
+        fn run(
+            &mut self,
+            source: Source,
+            cli: &ResolvedCli,
+            _args: &[OsString],
+        ) -> Result<i32, String> {
+            if self.refuse.iter().any(|path| path == cli.path()) {
+                return Err(format!("refused {}", cli.path().display()));
+            }
+            self.ran.push((source, cli.clone()));
+            Ok(0)
+        }
+    }
+
+    fn present(paths: &[PathBuf]) -> impl Fn(&Path) -> bool + '_ {
+        move |path| paths.iter().any(|candidate| candidate == path)
+    }
+
+    #[test]
+    fn each_source_resolves_to_its_own_invocation_kind() {
+        let resolver = test_resolver();
+        let cases = [
+            (
+                Source::LocalNpm,
+                PathBuf::from("/repo/node_modules/@0xshariq/package-installer/dist/index.js"),
+                ResolvedCli::NodeScript(PathBuf::from(
+                    "/repo/node_modules/@0xshariq/package-installer/dist/index.js",
+                )),
+            ),
+            (
+                Source::LocalBinShim,
+                PathBuf::from("/repo/pkg/node_modules/.bin/pi"),
+                ResolvedCli::Executable(PathBuf::from("/repo/pkg/node_modules/.bin/pi")),
+            ),
+            (
+                Source::YarnPnp,
+                PathBuf::from("/repo/.pnp.cjs"),
+                ResolvedCli::PnpManifest(PathBuf::from("/repo/.pnp.cjs")),
+            ),
+            (
+                Source::GlobalNpm,
+                global_fixture().remove(0),
+                ResolvedCli::NodeScript(global_fixture().remove(0)),
+            ),
+            (
+                Source::BundledExecutable,
+                PathBuf::from("/opt/pi/bundle-standalone/pi"),
+                ResolvedCli::Executable(PathBuf::from("/opt/pi/bundle-standalone/pi")),
+            ),
+            (
+                Source::UserBundle,
+                PathBuf::from("/home/user/.local/share/pi-bundle/pi"),
+                ResolvedCli::Executable(PathBuf::from("/home/user/.local/share/pi-bundle/pi")),
+            ),
+            (
+                Source::DevBundle,
+                PathBuf::from("/repo/pkg/bundle-standalone/pi"),
+                ResolvedCli::Executable(PathBuf::from("/repo/pkg/bundle-standalone/pi")),
+            ),
+        ];
+        for (source, on_disk, expected) in cases {
+            let layout = vec![on_disk];
+            assert_eq!(
+                resolver.find(source, &present(&layout)),
+                Some(expected),
+                "{source:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn default_order_prefers_local_over_global_over_bundled() {
+        let resolver = test_resolver();
+        let layout = vec![
+            PathBuf::from("/repo/pkg/node_modules/@0xshariq/package-installer/dist/index.js"),
+            global_fixture().remove(0),
+            PathBuf::from("/opt/pi/bundle-standalone/pi"),
+        ];
+        let mut runner = FakeRunner::new();
+        assert_eq!(resolver.run(&present(&layout), &[], &mut runner), Ok(0));
+        assert_eq!(
+            runner.ran,
+            vec![(
+                Source::LocalNpm,
+                ResolvedCli::NodeScript(layout[0].clone())
+            )]
+        );
+    }
+
+    #[test]
+    fn configured_order_is_respected() {
+        let mut resolver = test_resolver();
+        resolver.order = vec![ResolutionStep::Bundled, ResolutionStep::Local];
+        let layout = vec![
+            PathBuf::from("/repo/pkg/node_modules/@0xshariq/package-installer/dist/index.js"),
+            PathBuf::from("/opt/pi/bundle-standalone/pi"),
+        ];
+        let mut runner = FakeRunner::new();
+        assert_eq!(resolver.run(&present(&layout), &[], &mut runner), Ok(0));
+        assert_eq!(runner.ran[0].0, Source::BundledExecutable);
+    }
+
+    #[test]
+    fn within_the_local_step_the_npm_entry_beats_shim_and_pnp() {
+        let resolver = test_resolver();
+        let layout = vec![
+            PathBuf::from("/repo/pkg/.pnp.cjs"),
+            PathBuf::from("/repo/pkg/node_modules/.bin/pi"),
+            PathBuf::from("/repo/pkg/node_modules/@0xshariq/package-installer/dist/index.js"),
+        ];
+        let mut runner = FakeRunner::new();
+        assert_eq!(resolver.run(&present(&layout), &[], &mut runner), Ok(0));
+        assert_eq!(runner.ran[0].0, Source::LocalNpm);
+
+        // Without the entry, the shim wins over the PnP manifest
+        let layout = vec![
+            PathBuf::from("/repo/pkg/.pnp.cjs"),
+            PathBuf::from("/repo/pkg/node_modules/.bin/pi"),
+        ];
+        let mut runner = FakeRunner::new();
+        assert_eq!(resolver.run(&present(&layout), &[], &mut runner), Ok(0));
+        assert_eq!(runner.ran[0].0, Source::LocalBinShim);
+    }
+
+    #[test]
+    fn a_failing_candidate_falls_through_to_the_next_source() {
+        let resolver = test_resolver();
+        let local = PathBuf::from(
+            "/repo/pkg/node_modules/@0xshariq/package-installer/dist/index.js",
+        );
+        let bundled = PathBuf::from("/opt/pi/bundle-standalone/pi");
+        let layout = vec![local.clone(), bundled.clone()];
+        let mut runner = FakeRunner::new();
+        runner.refuse.push(local);
+        assert_eq!(resolver.run(&present(&layout), &[], &mut runner), Ok(0));
+        assert_eq!(
+            runner.ran,
+            vec![(
+                Source::BundledExecutable,
+                ResolvedCli::Executable(bundled)
+            )]
+        );
+    }
+
+    #[test]
+    fn disabled_local_step_skips_every_local_source() {
+        let mut resolver = test_resolver();
+        resolver.local_disabled = true;
+        let layout = vec![
+            PathBuf::from("/repo/pkg/node_modules/@0xshariq/package-installer/dist/index.js"),
+            PathBuf::from("/repo/pkg/node_modules/.bin/pi"),
+            PathBuf::from("/repo/pkg/.pnp.cjs"),
+        ];
+        let mut runner = FakeRunner::new();
+        let attempts = resolver
+            .run(&present(&layout), &[], &mut runner)
+            .unwrap_err();
+        assert!(runner.ran.is_empty());
+        // The local sources do not even appear as attempts
+        assert_eq!(
+            attempts,
+            vec![
+                Attempt::Missing(Source::GlobalNpm),
+                Attempt::Missing(Source::BundledExecutable),
+                Attempt::Missing(Source::UserBundle),
+                Attempt::Missing(Source::DevBundle),
+            ]
+        );
+    }
+
+    /// An [`Fs`] that counts every probe, for stat-budget assertions.
+    struct CountingFs {
+        present: Vec<PathBuf>,
+        probed: std::cell::RefCell<Vec<PathBuf>>,
+    }
+
+    impl CountingFs {
+        fn with(present: Vec<PathBuf>) -> CountingFs {
+            CountingFs {
+                present,
+                probed: std::cell::RefCell::new(Vec::new()),
+            }
+        }
+
+        fn stats(&self) -> usize {
+            self.probed.borrow().len()
+        }
+    }
+
+    impl Fs for CountingFs {
+        fn exists(&self, path: &Path) -> bool {
+            self.probed.borrow_mut().push(path.to_path_buf());
+            self.present.iter().any(|candidate| candidate == path)
+        }
+    }
+
+    #[test]
+    fn a_first_candidate_hit_stays_within_the_stat_budget() {
+        // A deep synthetic tree: 40 directories below the would-be
+        // project root, none with boundary markers on disk
+        let mut cwd = PathBuf::from("/repo");
+        for level in 0..40 {
+            cwd.push(format!("d{level}"));
+        }
+        let mut resolver = test_resolver();
+        resolver.cwd = Some(cwd.clone());
+        let fs = CountingFs::with(vec![cwd.join(LOCAL_ENTRY_PATHS[0])]);
+        let mut runner = FakeRunner::new();
+        assert_eq!(resolver.run(&fs, &[], &mut runner), Ok(0));
+        // The very first probe hits; neither the rest of the walk nor
+        // the boundary markers may be statted at all
+        assert!(fs.stats() <= 2, "{} stats for a first-candidate hit", fs.stats());
+    }
+
+    #[test]
+    fn a_full_miss_never_stats_the_same_path_twice() {
+        let resolver = test_resolver();
+        let fs = CountingFs::with(Vec::new());
+        let mut runner = FakeRunner::new();
+        resolver.run(&fs, &[], &mut runner).unwrap_err();
+        let probed = fs.probed.borrow();
+        let mut unique: Vec<_> = probed.clone();
+        unique.sort();
+        unique.dedup();
+        // The boundary markers are shared by all three local walks;
+        // memoization must collapse them to one stat each
+        assert_eq!(probed.len(), unique.len(), "duplicate stats in {probed:?}");
+    }
+
+    #[test]
+    fn on_demand_producers_are_skipped_when_cheaper_candidates_hit() {
+        fn must_not_run() -> Vec<PathBuf> {
+            panic!("on-demand candidates must not be produced");
+        }
+        // A loose bundle hit never triggers the extraction fallback
+        let mut resolver = test_resolver();
+        resolver.bundle_fallback = must_not_run;
+        let layout = vec![PathBuf::from("/opt/pi/bundle-standalone/pi")];
+        assert_eq!(
+            resolver.find(Source::BundledExecutable, &present(&layout)),
+            Some(ResolvedCli::Executable(layout[0].clone()))
+        );
+        // A local hit never queries the package managers
+        let mut resolver = test_resolver();
+        resolver.global_candidates = must_not_run;
+        let layout = vec![PathBuf::from(
+            "/repo/pkg/node_modules/@0xshariq/package-installer/dist/index.js",
+        )];
+        let mut runner = FakeRunner::new();
+        assert_eq!(resolver.run(&present(&layout), &[], &mut runner), Ok(0));
+    }
+
+    #[test]
+    fn failed_walk_reports_every_source_in_order() {
+        let resolver = test_resolver();
+        let mut runner = FakeRunner::new();
+        let attempts = resolver
+            .run(&present(&[]), &[], &mut runner)
+            .unwrap_err();
+        assert_eq!(
+            attempts,
+            vec![
+                Attempt::Missing(Source::LocalNpm),
+                Attempt::Missing(Source::LocalBinShim),
+                Attempt::Missing(Source::YarnPnp),
+                Attempt::Missing(Source::GlobalNpm),
+                Attempt::Missing(Source::BundledExecutable),
+                Attempt::Missing(Source::UserBundle),
+                Attempt::Missing(Source::DevBundle),
+            ]
+        );
+    }
+}
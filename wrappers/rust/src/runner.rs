@@ -0,0 +1,463 @@
+//! Child process execution with signal forwarding.
+//!
+//! The wrapper used to call `Command::status` and collapse everything to
+//! `status.code().unwrap_or(1)`, which let a Ctrl-C kill the wrapper
+//! while the node child kept running. Here the child is spawned
+//! explicitly, terminal signals are forwarded to it, and a child killed
+//! by a signal exits the wrapper with `128 + signal` per shell
+//! convention.
+
+use std::io;
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus};
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+mod signals {
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    /// PID of the currently running child, or 0 when there is none.
+    /// Written before the handlers are installed and cleared after the
+    /// child is reaped.
+    static CHILD_PID: AtomicI32 = AtomicI32::new(0);
+
+    /// Signal handler: forwards the received signal to the child's whole
+    /// process group (the child leads its own — see [`super::tree`]),
+    /// so package-manager grandchildren die with it. Only
+    /// async-signal-safe calls (`kill`) are made here.
+    extern "C" fn forward_signal(signal: libc::c_int) {
+        let pid = CHILD_PID.load(Ordering::SeqCst);
+        if pid > 0 {
+            unsafe {
+                libc::kill(-pid, signal);
+            }
+        }
+    }
+
+    /// Registers forwarding for the terminal signals and records the
+    /// child to forward them to.
+    pub fn forward_to(pid: u32) {
+        CHILD_PID.store(pid as i32, Ordering::SeqCst);
+        unsafe {
+            for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+                libc::signal(signal, forward_signal as *const () as libc::sighandler_t);
+            }
+        }
+    }
+
+    /// Clears the forwarding target once the child has been reaped.
+    pub fn clear() {
+        CHILD_PID.store(0, Ordering::SeqCst);
+    }
+}
+
+/// Child process-tree lifetime management (Unix): the child is started
+/// in its own process group so the wrapper can signal the entire tree
+/// at once, and on Linux the direct child additionally dies with the
+/// wrapper via `PR_SET_PDEATHSIG` — a CI harness SIGKILLing the
+/// wrapper no longer leaves node holding workspace locks. Every spawn
+/// in this module goes through [`tree::adopt`], which covers both the
+/// node and the standalone-executable execution paths.
+#[cfg(unix)]
+mod tree {
+    use std::process::Command;
+
+    /// Configures `command` to start in its own process group and, on
+    /// Linux, to be killed when the wrapper dies.
+    pub fn adopt(command: &mut Command) {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+        #[cfg(target_os = "linux")]
+        unsafe {
+            command.pre_exec(|| {
+                // Best-effort: an old kernel refusing this only loses
+                // the die-with-parent guarantee, not the spawn
+                libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL as libc::c_ulong);
+                Ok(())
+            });
+        }
+    }
+
+    /// Makes the child's process group the terminal's foreground group
+    /// for its lifetime, so interactive prompts keep working from the
+    /// separate group. Inert without a TTY. The guard hands the
+    /// terminal back on drop.
+    pub struct TerminalGuard {
+        owned: bool,
+    }
+
+    pub fn give_terminal_to(pid: u32) -> TerminalGuard {
+        unsafe {
+            if libc::isatty(libc::STDIN_FILENO) == 1 {
+                // Poking the foreground group from the background
+                // raises SIGTTOU; ignore it for the handover
+                libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+                if libc::tcsetpgrp(libc::STDIN_FILENO, pid as libc::pid_t) == 0 {
+                    return TerminalGuard { owned: true };
+                }
+            }
+        }
+        TerminalGuard { owned: false }
+    }
+
+    impl Drop for TerminalGuard {
+        fn drop(&mut self) {
+            if self.owned {
+                unsafe {
+                    libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp());
+                }
+            }
+        }
+    }
+}
+
+/// Child process-tree lifetime management (Windows): the child is
+/// assigned to a Job Object with `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE`,
+/// so the whole tree dies when the wrapper's handle closes — including
+/// when the wrapper itself is killed.
+#[cfg(windows)]
+mod tree {
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    /// Owns the job handle; the OS closes it (killing the tree) when
+    /// the wrapper exits for any reason.
+    pub struct Job(windows_sys::Win32::Foundation::HANDLE);
+
+    // The handle is only ever closed by process teardown.
+    unsafe impl Send for Job {}
+
+    /// Puts `child` in a kill-on-close job. `None` (best-effort) when
+    /// any step fails.
+    pub fn adopt(child: &Child) -> Option<Job> {
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return None;
+            }
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const core::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            AssignProcessToJobObject(job, child.as_raw_handle() as _);
+            Some(Job(job))
+        }
+    }
+}
+
+/// Maps an exit status to the wrapper's own exit code: the child's code
+/// when it exited normally, `128 + signal` when it was killed by a
+/// signal (Unix), and 1 only as a last resort.
+fn exit_code_from_status(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+/// Hands the process over to `command`.
+///
+/// On Unix this replaces the wrapper via `exec(2)` so signals, job
+/// control, TTY ownership and exit codes behave exactly as if the CLI
+/// had been invoked directly — no second process, no double-Ctrl-C.
+/// `PI_WRAPPER_NO_EXEC=1` forces the spawn-and-wait path instead, which
+/// also remains the behavior on Windows and for any mode that needs to
+/// do post-processing after the child exits — a `--wrapper-timeout`
+/// watchdog, output capture, or the timing report. History recording
+/// (on by default) does not hold exec back: its entry is written just
+/// before the replacement, without an exit code.
+pub fn exec_or_run(mut command: Command) -> io::Result<i32> {
+    // `--wrapper-dry-run`: resolution already happened; report what
+    // would have run instead of running it
+    if crate::dry_run_enabled() {
+        crate::print_dry_run_report(&command);
+        return Ok(0);
+    }
+    fallback_working_directory(&mut command);
+    apply_wrapper_context(&mut command);
+    // Everything up to here — probing, caching, version checks — is
+    // the resolution stage
+    crate::timing::resolution_finished();
+    // A timeout needs the wrapper to stay alive as the watchdog, so it
+    // forces the spawn-and-wait path over exec()
+    if let Some(limit) = crate::wrapper_timeout() {
+        return run_with_timeout(command, limit);
+    }
+    // Capture needs piped output streams and copy threads, so it has
+    // its own spawn path
+    if let Some(log) = crate::capture::target() {
+        return run_captured(command, log);
+    }
+    // The timing report is printed only after the child is done, so it
+    // needs the wrapper to outlive the child instead of exec()ing over
+    // itself
+    if crate::timing::enabled() {
+        return run_command(command);
+    }
+    #[cfg(unix)]
+    {
+        let no_exec = std::env::var_os("PI_WRAPPER_NO_EXEC")
+            .map(|value| value == "1")
+            .unwrap_or(false);
+        if !no_exec {
+            use std::os::unix::process::CommandExt;
+            // Last chance to record the run: nothing survives the
+            // replacement to write the entry afterwards
+            crate::history_before_exec();
+            // exec only returns on failure
+            return Err(command.exec());
+        }
+    }
+    run_command(command)
+}
+
+/// Advertises the wrapper context (`PI_WRAPPER`, `PI_WRAPPER_VERSION`,
+/// `PI_WRAPPER_RESOLUTION`) to the child. A value the user already
+/// exported wins over ours.
+fn apply_wrapper_context(command: &mut Command) {
+    for (key, value) in crate::wrapper_context() {
+        if std::env::var_os(key).is_none() {
+            command.env(key, value);
+        }
+    }
+}
+
+/// When the wrapper's own working directory has been deleted from
+/// under it (a stale shell after `rm -rf`), start the child from a
+/// directory that still exists — the home directory, or the temp
+/// directory as a last resort — instead of letting the spawn or the
+/// child's own cwd lookup fail. A usable working directory is always
+/// inherited untouched.
+fn fallback_working_directory(command: &mut Command) {
+    if std::env::current_dir().is_ok() {
+        return;
+    }
+    let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    let fallback = std::env::var_os(home)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(std::env::temp_dir);
+    command.current_dir(fallback);
+}
+
+/// Exit code when the child was killed because it outlived
+/// `--wrapper-timeout`, matching coreutils `timeout(1)`.
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// How long a timed-out child gets to react to SIGTERM before SIGKILL.
+const TIMEOUT_KILL_GRACE: Duration = Duration::from_secs(5);
+
+/// Poll interval for the timeout watchdog.
+const TIMEOUT_POLL: Duration = Duration::from_millis(50);
+
+/// Like [`run_command`], but kills the child if it runs past `limit`:
+/// SIGTERM first, SIGKILL after a grace period, then exits with
+/// [`TIMEOUT_EXIT_CODE`] and a message naming what was killed.
+fn run_with_timeout(mut command: Command, limit: Duration) -> io::Result<i32> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    #[cfg(unix)]
+    tree::adopt(&mut command);
+    let mut child = command.spawn()?;
+    crate::timing::spawn_finished();
+    #[cfg(windows)]
+    let _job = tree::adopt(&child);
+    #[cfg(unix)]
+    let _terminal = tree::give_terminal_to(child.id());
+
+    #[cfg(unix)]
+    signals::forward_to(child.id());
+
+    let verdict = wait_with_timeout(&mut child, limit);
+    crate::timing::child_finished();
+
+    #[cfg(unix)]
+    signals::clear();
+
+    match verdict? {
+        Some(status) => Ok(exit_code_from_status(status)),
+        None => {
+            eprintln!(
+                "{}",
+                crate::ui::Style::for_stderr().error(&format!(
+                    "Killed {} after the {}s wrapper timeout",
+                    program,
+                    limit.as_secs()
+                ))
+            );
+            Ok(TIMEOUT_EXIT_CODE)
+        }
+    }
+}
+
+/// Waits up to `limit` for the child; on expiry terminates it (with
+/// escalation) and returns `None`.
+fn wait_with_timeout(child: &mut Child, limit: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + limit;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        std::thread::sleep(TIMEOUT_POLL);
+    }
+
+    terminate(child);
+    let grace_deadline = Instant::now() + TIMEOUT_KILL_GRACE;
+    while Instant::now() < grace_deadline {
+        if child.try_wait()?.is_some() {
+            return Ok(None);
+        }
+        std::thread::sleep(TIMEOUT_POLL);
+    }
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as i32), libc::SIGKILL);
+    }
+    child.kill().ok();
+    child.wait().ok();
+    Ok(None)
+}
+
+/// Asks the child's tree to exit: SIGTERM to the process group on Unix
+/// (so everything can clean up), a hard kill elsewhere.
+fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as i32), libc::SIGTERM);
+    }
+    #[cfg(not(unix))]
+    child.kill().ok();
+}
+
+/// Like [`run_command`], but with the child's stdout and stderr piped
+/// through [`crate::capture::tee`]: live output keeps flowing to the
+/// terminal while a labeled copy lands in `log`. stdin stays inherited,
+/// so interactive prompts (which read the TTY directly) keep working.
+fn run_captured(mut command: Command, log: &std::path::Path) -> io::Result<i32> {
+    use std::process::Stdio;
+    let log_file = std::fs::File::create(log).map_err(|e| {
+        io::Error::new(e.kind(), format!("cannot create capture log {}: {}", log.display(), e))
+    })?;
+    let log_file = std::sync::Arc::new(std::sync::Mutex::new(log_file));
+
+    #[cfg(unix)]
+    tree::adopt(&mut command);
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+    crate::timing::spawn_finished();
+    #[cfg(windows)]
+    let _job = tree::adopt(&child);
+    #[cfg(unix)]
+    let _terminal = tree::give_terminal_to(child.id());
+
+    #[cfg(unix)]
+    signals::forward_to(child.id());
+
+    let started = Instant::now();
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+    let stdout_log = std::sync::Arc::clone(&log_file);
+    let stdout_thread = std::thread::spawn(move || {
+        crate::capture::tee(stdout, io::stdout(), "stdout", started, &stdout_log);
+    });
+    let stderr_log = std::sync::Arc::clone(&log_file);
+    let stderr_thread = std::thread::spawn(move || {
+        crate::capture::tee(stderr, io::stderr(), "stderr", started, &stderr_log);
+    });
+
+    let status = child.wait()?;
+    // The pipes close when the child's tree lets go of them; the copy
+    // threads drain whatever is still buffered before returning
+    let _ = stdout_thread.join();
+    let _ = stderr_thread.join();
+    crate::timing::child_finished();
+
+    #[cfg(unix)]
+    signals::clear();
+
+    Ok(exit_code_from_status(status))
+}
+
+/// Spawns `command`, forwards SIGINT/SIGTERM/SIGHUP to the child while it
+/// runs (Unix; on Windows Ctrl-C events already reach the whole console
+/// process group), waits for it to actually exit, and returns the exit
+/// code to propagate.
+pub fn run_command(mut command: Command) -> io::Result<i32> {
+    #[cfg(unix)]
+    tree::adopt(&mut command);
+    let mut child = command.spawn()?;
+    crate::timing::spawn_finished();
+    #[cfg(windows)]
+    let _job = tree::adopt(&child);
+    #[cfg(unix)]
+    let _terminal = tree::give_terminal_to(child.id());
+
+    #[cfg(unix)]
+    signals::forward_to(child.id());
+
+    let status = child.wait()?;
+    crate::timing::child_finished();
+
+    #[cfg(unix)]
+    signals::clear();
+
+    Ok(exit_code_from_status(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_exit_codes_pass_through() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("exit 42");
+        assert_eq!(run_command(command).unwrap(), 42);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_wedged_child_is_killed_at_the_deadline_with_exit_124() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("sleep 30");
+        let start = Instant::now();
+        let code = run_with_timeout(command, Duration::from_millis(200)).unwrap();
+        assert_eq!(code, TIMEOUT_EXIT_CODE);
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "the watchdog must not wait for the full sleep"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_child_finishing_inside_the_deadline_keeps_its_exit_code() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("exit 9");
+        assert_eq!(run_with_timeout(command, Duration::from_secs(30)).unwrap(), 9);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signal_death_maps_to_128_plus_signal() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("kill -TERM $$");
+        assert_eq!(run_command(command).unwrap(), 128 + libc::SIGTERM);
+    }
+}
@@ -0,0 +1,179 @@
+//! Native `pi list-templates`.
+//!
+//! The repository's full template catalog is embedded as
+//! `templates.json` (validated against the template trees by
+//! `build.rs`), so browsing what `pi create` offers needs no CLI
+//! install at all. The wrapper serves the listing itself when
+//! resolution has failed, and `--native` forces the embedded catalog
+//! even with a CLI present, so the two can be compared.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{report, ui};
+
+/// The checked-in catalog, embedded at compile time.
+const MANIFEST: &str = include_str!("../templates.json");
+
+/// One template in the catalog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateEntry {
+    /// The template tree it lives under (`rust`, `python`, ...).
+    pub language: String,
+    pub framework: String,
+    /// Repository path, relative to `core-cli/`.
+    pub path: String,
+    pub description: String,
+}
+
+/// The parsed catalog. The manifest is validated at build time, so a
+/// parse failure here is a packaging bug worth crashing on.
+fn catalog() -> &'static [TemplateEntry] {
+    static PARSED: OnceLock<Vec<TemplateEntry>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        serde_json::from_str(MANIFEST).expect("embedded templates.json is valid JSON")
+    })
+}
+
+/// The entries to show, optionally narrowed to one language group.
+fn filtered(entries: &[TemplateEntry], language: Option<&str>) -> Vec<TemplateEntry> {
+    entries
+        .iter()
+        .filter(|entry| language.is_none_or(|language| entry.language == language))
+        .cloned()
+        .collect()
+}
+
+/// `pi list-templates [--language <group>] [--json]` (also reached as
+/// `pi templates`).
+pub fn run(args: &[String]) -> i32 {
+    let mut language: Option<&str> = None;
+    let mut json = false;
+    let mut index = 0;
+    while index < args.len() {
+        match args[index].as_str() {
+            "--json" => json = true,
+            "--native" => {} // already here; accepted so it can be passed uniformly
+            "--language" => {
+                index += 1;
+                match args.get(index) {
+                    Some(value) => language = Some(value),
+                    None => {
+                        report::WrapperMessage::Error {
+                            message: "--language requires a value (e.g. --language rust)"
+                                .to_string(),
+                        }
+                        .emit();
+                        return 1;
+                    }
+                }
+            }
+            other => {
+                report::WrapperMessage::Error {
+                    message: format!(
+                        "Unknown option {} (expected --language <group>, --json, --native)",
+                        other
+                    ),
+                }
+                .emit();
+                return 1;
+            }
+        }
+        index += 1;
+    }
+
+    let entries = filtered(catalog(), language);
+    if json || report::json_enabled() {
+        match serde_json::to_string(&entries) {
+            Ok(rendered) => println!("{}", rendered),
+            Err(e) => {
+                eprintln!("{}", ui::Style::for_stderr().error(&format!("{}", e)));
+                return 1;
+            }
+        }
+        return 0;
+    }
+    if entries.is_empty() {
+        println!(
+            "No templates for language {:?} (groups: {})",
+            language.unwrap_or(""),
+            language_groups(catalog()).join(", ")
+        );
+        return 0;
+    }
+
+    // Grouped, column-aligned: one block per language, frameworks
+    // padded to the widest name in the block
+    let mut current_language = "";
+    let width = entries
+        .iter()
+        .map(|entry| entry.framework.len())
+        .max()
+        .unwrap_or(0);
+    for entry in &entries {
+        if entry.language != current_language {
+            if !current_language.is_empty() {
+                println!();
+            }
+            println!("{}:", entry.language);
+            current_language = &entry.language;
+        }
+        println!("  {:<width$}  {}", entry.framework, entry.description);
+    }
+    0
+}
+
+/// Every distinct language group, in catalog order.
+fn language_groups(entries: &[TemplateEntry]) -> Vec<&str> {
+    let mut groups: Vec<&str> = Vec::new();
+    for entry in entries {
+        if groups.last() != Some(&entry.language.as_str()) {
+            groups.push(&entry.language);
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_catalog_parses_and_covers_the_known_groups() {
+        let entries = catalog();
+        assert!(entries.len() > 100, "got {} entries", entries.len());
+        for group in ["rust", "python", "javascript", "go", "web3"] {
+            assert!(
+                entries.iter().any(|entry| entry.language == group),
+                "no {group} templates"
+            );
+        }
+        assert!(entries
+            .iter()
+            .any(|entry| entry.framework == "axum" && entry.path == "templates/rust/axum"));
+    }
+
+    #[test]
+    fn the_language_filter_narrows_to_one_group() {
+        let rust = filtered(catalog(), Some("rust"));
+        assert!(!rust.is_empty());
+        assert!(rust.iter().all(|entry| entry.language == "rust"));
+        assert!(filtered(catalog(), Some("cobol")).is_empty());
+        assert_eq!(filtered(catalog(), None).len(), catalog().len());
+    }
+
+    #[test]
+    fn the_json_shape_is_stable() {
+        let entry = TemplateEntry {
+            language: "rust".to_string(),
+            framework: "axum".to_string(),
+            path: "templates/rust/axum".to_string(),
+            description: "Ergonomic web framework built on tokio.".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            r#"{"language":"rust","framework":"axum","path":"templates/rust/axum","description":"Ergonomic web framework built on tokio."}"#
+        );
+    }
+}
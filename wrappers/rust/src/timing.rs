@@ -0,0 +1,106 @@
+//! `--wrapper-timing`: a per-stage wall-time breakdown on stderr.
+//!
+//! Stages are marked from wherever the work actually happens —
+//! `runner::exec_or_run` ends the resolution stage, the spawn and wait
+//! calls end theirs — and the report is printed once, when the wrapper
+//! exits. Timing forces the spawn-and-wait path (like history
+//! recording does), since an exec()ed wrapper is gone before it could
+//! report anything.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Elapsed-since-start marks for each completed stage. Cumulative, so
+/// the report derives per-stage durations by subtraction.
+struct Marks {
+    start: Instant,
+    resolution: Option<Duration>,
+    spawn: Option<Duration>,
+    child: Option<Duration>,
+}
+
+static MARKS: OnceLock<Mutex<Marks>> = OnceLock::new();
+
+/// Turns timing on, anchored at the wrapper's own start instant.
+pub fn enable(start: Instant) {
+    let _ = MARKS.set(Mutex::new(Marks {
+        start,
+        resolution: None,
+        spawn: None,
+        child: None,
+    }));
+}
+
+pub fn enabled() -> bool {
+    MARKS.get().is_some()
+}
+
+fn mark(stage: fn(&mut Marks) -> &mut Option<Duration>) {
+    if let Some(marks) = MARKS.get() {
+        if let Ok(mut marks) = marks.lock() {
+            let elapsed = marks.start.elapsed();
+            // Last mark wins: an auto-install retry re-runs the stages
+            // and the report should describe the run that counted
+            *stage(&mut marks) = Some(elapsed);
+        }
+    }
+}
+
+/// Resolution is over; the command is about to be spawned.
+pub fn resolution_finished() {
+    mark(|marks| &mut marks.resolution);
+}
+
+/// The child process exists.
+pub fn spawn_finished() {
+    mark(|marks| &mut marks.spawn);
+}
+
+/// The child has been waited on.
+pub fn child_finished() {
+    mark(|marks| &mut marks.child);
+}
+
+/// Prints the breakdown to stderr. Stages that never completed (a
+/// resolution failure, a dry run) are simply omitted.
+pub fn report() {
+    let Some(marks) = MARKS.get() else { return };
+    let Ok(marks) = marks.lock() else { return };
+    let mut lines: Vec<(&str, Duration)> = Vec::new();
+    let mut previous = Duration::ZERO;
+    for (name, mark) in [
+        ("resolution", marks.resolution),
+        ("spawn", marks.spawn),
+        ("child", marks.child),
+    ] {
+        if let Some(elapsed) = mark {
+            lines.push((name, elapsed.saturating_sub(previous)));
+            previous = elapsed;
+        }
+    }
+    lines.push(("total", marks.start.elapsed()));
+    eprintln!("wrapper timing:");
+    for (name, duration) in lines {
+        eprintln!("  {:<12}{:>9.1}ms", name, duration.as_secs_f64() * 1000.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_report_in_order_once_enabled() {
+        assert!(!enabled());
+        enable(Instant::now());
+        assert!(enabled());
+        resolution_finished();
+        spawn_finished();
+        child_finished();
+        let marks = MARKS.get().unwrap().lock().unwrap();
+        let resolution = marks.resolution.unwrap();
+        let spawn = marks.spawn.unwrap();
+        let child = marks.child.unwrap();
+        assert!(resolution <= spawn && spawn <= child);
+    }
+}
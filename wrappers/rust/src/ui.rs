@@ -0,0 +1,161 @@
+//! Rendering helpers for the wrapper's own terminal output.
+//!
+//! The child CLI's output always passes through untouched; everything
+//! the wrapper prints itself is routed through here so `NO_COLOR`,
+//! `PI_NO_EMOJI=1` and non-TTY streams consistently fall back to plain
+//! ASCII markers (`[ok]`, `[error]`) instead of emoji that turn into
+//! mojibake in CI log viewers.
+
+use std::env;
+use std::io::IsTerminal;
+
+/// Whether wrapper output may use emoji and other decoration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Decorated,
+    Plain,
+}
+
+impl Style {
+    /// Style for wrapper messages on stderr: plain when `NO_COLOR` is
+    /// set to any value (per the no-color.org convention), when
+    /// `PI_NO_EMOJI=1`, or when stderr is not a terminal.
+    pub fn for_stderr() -> Style {
+        if env::var_os("NO_COLOR").is_some() {
+            return Style::Plain;
+        }
+        let no_emoji = env::var_os("PI_NO_EMOJI")
+            .map(|value| value == "1")
+            .unwrap_or(false);
+        if no_emoji || !std::io::stderr().is_terminal() {
+            Style::Plain
+        } else {
+            Style::Decorated
+        }
+    }
+
+    /// Success marker in front of `message`: `✅` or `[ok]`.
+    pub fn ok(self, message: &str) -> String {
+        match self {
+            Style::Decorated => format!("✅ {}", message),
+            Style::Plain => format!("[ok] {}", message),
+        }
+    }
+
+    /// Warning marker in front of `message`: `⚠️` or `[warn]`.
+    pub fn warn(self, message: &str) -> String {
+        match self {
+            Style::Decorated => format!("⚠️  {}", message),
+            Style::Plain => format!("[warn] {}", message),
+        }
+    }
+
+    /// Failure marker in front of `message`: `❌` or `[error]`.
+    pub fn error(self, message: &str) -> String {
+        match self {
+            Style::Decorated => format!("❌ {}", message),
+            Style::Plain => format!("[error] {}", message),
+        }
+    }
+
+    /// Section heading: keeps the emoji in decorated mode, drops it in
+    /// plain mode.
+    fn heading(self, emoji: &str, text: &str) -> String {
+        match self {
+            Style::Decorated => format!("{} {}", emoji, text),
+            Style::Plain => text.to_string(),
+        }
+    }
+
+    /// Horizontal rule separating the usage screen from other output.
+    fn rule(self) -> &'static str {
+        match self {
+            Style::Decorated => "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
+            Style::Plain => "-------------------------------------------------------",
+        }
+    }
+}
+
+/// The "CLI not found" help screen, rendered for `style`. The suggested
+/// npm command targets `registry` when a mirror is configured.
+pub fn usage_instructions(style: Style, registry: Option<&str>) -> String {
+    let mut lines = vec![
+        String::new(),
+        style.heading("📋", "CLI NOT FOUND:"),
+        style.rule().to_string(),
+        "The Package Installer CLI was not found. Here are your options:".to_string(),
+        String::new(),
+    ];
+
+    lines.push(style.heading("🌍", "OPTION 1: Install locally via npm (Recommended)"));
+    let registry_suffix = registry
+        .map(|url| format!(" --registry {}", url))
+        .unwrap_or_default();
+    lines.push(format!(
+        "   npm install @0xshariq/package-installer{}",
+        registry_suffix
+    ));
+    lines.push("   npx pi create my-app".to_string());
+    lines.push(String::new());
+
+    lines.push(style.heading("🔧", "OPTION 2: Use the bundled version"));
+    lines.push(
+        "   Make sure the 'bundle-standalone/' directory is available alongside this executable"
+            .to_string(),
+    );
+    lines.push("   The bundle should contain: bundle-standalone/pi (bundled executable)".to_string());
+    lines.push(String::new());
+
+    lines.push(style.heading("💡", "REQUIREMENTS:"));
+    lines.push("   - For npm version: Install Node.js from https://nodejs.org".to_string());
+    lines.push("   - For bundled version: No additional requirements".to_string());
+
+    lines.push(String::new());
+    lines.push(style.heading(
+        "🔗",
+        "More info: https://github.com/0xshariq/rust_package_installer_cli",
+    ));
+    lines.push(style.rule().to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_and_decorated_banners_differ() {
+        let message = "Using locally installed CLI from node_modules";
+        assert_eq!(Style::Decorated.ok(message), format!("✅ {}", message));
+        assert_eq!(Style::Plain.ok(message), format!("[ok] {}", message));
+        assert_ne!(Style::Decorated.ok(message), Style::Plain.ok(message));
+
+        assert_eq!(Style::Plain.error("boom"), "[error] boom");
+        assert_eq!(Style::Decorated.error("boom"), "❌ boom");
+        assert_eq!(Style::Plain.warn("careful"), "[warn] careful");
+        assert!(Style::Decorated.warn("careful").contains("careful"));
+    }
+
+    #[test]
+    fn plain_usage_screen_is_pure_ascii() {
+        let screen = usage_instructions(Style::Plain, None);
+        assert!(screen.is_ascii(), "plain usage screen must be ASCII-only");
+        assert!(screen.contains("CLI NOT FOUND:"));
+    }
+
+    #[test]
+    fn decorated_usage_screen_keeps_the_emoji_headings() {
+        let screen = usage_instructions(Style::Decorated, None);
+        assert!(screen.contains("📋 CLI NOT FOUND:"));
+        assert!(screen.contains("🌍 OPTION 1"));
+        assert_ne!(screen, usage_instructions(Style::Plain, None));
+    }
+
+    #[test]
+    fn a_registry_mirror_reaches_the_suggested_install_command() {
+        let screen = usage_instructions(Style::Plain, Some("https://npm.corp.example"));
+        assert!(screen
+            .contains("npm install @0xshariq/package-installer --registry https://npm.corp.example"));
+        assert!(!usage_instructions(Style::Plain, None).contains("--registry"));
+    }
+}
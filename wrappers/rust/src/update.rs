@@ -0,0 +1,355 @@
+//! `pi wrapper update`: downloads the standalone CLI bundle from GitHub
+//! releases, so the wrapper can be kept working without npm at all.
+//!
+//! The latest (or `--version <tag>` pinned) release is queried through
+//! the GitHub API, the asset whose name matches this platform's OS and
+//! architecture is downloaded to a temporary file, and the result is
+//! atomically renamed into the per-user bundle directory
+//! (`~/.local/share/package-installer/bundle-standalone/`), which the
+//! resolver probes as part of its bundled step. The release API base is
+//! overridable via `PI_WRAPPER_RELEASE_BASE` for testing against a mock
+//! server.
+
+use std::env;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+/// GitHub API base for the CLI's releases.
+const DEFAULT_RELEASE_BASE: &str = "https://api.github.com/repos/0xshariq/package_installer_cli";
+
+/// One release, as returned by the GitHub releases API.
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    size: u64,
+}
+
+/// Endpoint for the latest release or a pinned tag.
+fn release_endpoint(base: &str, tag: Option<&str>) -> String {
+    match tag {
+        Some(tag) => format!("{}/releases/tags/{}", base, tag),
+        None => format!("{}/releases/latest", base),
+    }
+}
+
+/// Name fragments that identify this OS in a release asset name.
+fn os_tokens(os: &str) -> &'static [&'static str] {
+    match os {
+        "linux" => &["linux"],
+        "macos" => &["darwin", "macos"],
+        "windows" => &["windows", "win64"],
+        _ => &[],
+    }
+}
+
+/// Name fragments that identify this architecture in an asset name.
+fn arch_tokens(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "x86_64" => &["x86_64", "amd64", "x64"],
+        "aarch64" => &["aarch64", "arm64"],
+        _ => &[],
+    }
+}
+
+/// Picks the asset whose name matches both the OS and the architecture.
+fn select_asset<'a>(assets: &'a [ReleaseAsset], os: &str, arch: &str) -> Option<&'a ReleaseAsset> {
+    assets.iter().find(|asset| {
+        let name = asset.name.to_ascii_lowercase();
+        os_tokens(os).iter().any(|token| name.contains(token))
+            && arch_tokens(arch).iter().any(|token| name.contains(token))
+    })
+}
+
+/// The checksum manifest asset, when the release publishes one.
+fn select_manifest(assets: &[ReleaseAsset]) -> Option<&ReleaseAsset> {
+    assets
+        .iter()
+        .find(|asset| asset.name.eq_ignore_ascii_case("manifest.json"))
+}
+
+/// Per-user bundle directory the resolver probes and updates install
+/// into, honoring `XDG_DATA_HOME`.
+pub fn user_bundle_dir() -> Option<PathBuf> {
+    let data_home = env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| env::home_dir().map(|home| home.join(".local").join("share")))?;
+    Some(data_home.join("package-installer").join("bundle-standalone"))
+}
+
+/// Extracts `--version <tag>` from the subcommand arguments.
+fn parse_pinned_tag(args: &[String]) -> Result<Option<String>, String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--version" {
+            return match iter.next() {
+                Some(tag) => Ok(Some(tag.clone())),
+                None => Err("--version requires a release tag argument".to_string()),
+            };
+        }
+    }
+    Ok(None)
+}
+
+/// Queries the release metadata, with errors that say what to do next.
+fn fetch_release(base: &str, tag: Option<&str>) -> Result<Release, String> {
+    let url = release_endpoint(base, tag);
+    let response = crate::http::agent_for(&url)
+        .get(&url)
+        .set("User-Agent", "package-installer-cli-wrapper")
+        .call()
+        .map_err(|e| match e {
+            ureq::Error::Status(404, _) => match tag {
+                Some(tag) => format!(
+                    "Release {} was not found — check the tag name against the project's releases page",
+                    tag
+                ),
+                None => "No releases found for the CLI repository".to_string(),
+            },
+            other => format!(
+                "Cannot reach the GitHub releases API at {}: {} (check your network connection or proxy settings)",
+                url, other
+            ),
+        })?;
+    let body = response
+        .into_string()
+        .map_err(|e| format!("Cannot read the releases API response: {}", e))?;
+    serde_json::from_str(&body)
+        .map_err(|e| format!("Unexpected response from the releases API: {}", e))
+}
+
+/// Streams `asset` into `dir`, writing to a temp file first and
+/// renaming into place only on success, with coarse progress on stderr.
+fn download_asset(asset: &ReleaseAsset, dir: &PathBuf) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+
+    let response = crate::http::agent_for(&asset.browser_download_url)
+        .get(&asset.browser_download_url)
+        .set("User-Agent", "package-installer-cli-wrapper")
+        .call()
+        .map_err(|e| {
+            format!(
+                "Download of {} failed: {} (check your network connection and retry)",
+                asset.browser_download_url, e
+            )
+        })?;
+
+    let temp_path = dir.join(format!(".pi.download-{}", std::process::id()));
+    let mut temp = std::fs::File::create(&temp_path)
+        .map_err(|e| format!("Cannot write to {}: {}", temp_path.display(), e))?;
+
+    let mut reader = response.into_reader();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut downloaded: u64 = 0;
+    let mut last_reported_percent = 0;
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("Download interrupted: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        temp.write_all(&buffer[..read])
+            .map_err(|e| format!("Cannot write to {}: {}", temp_path.display(), e))?;
+        downloaded += read as u64;
+        if let Some(percent) = (downloaded * 100).checked_div(asset.size) {
+            let percent = percent as u32;
+            if percent >= last_reported_percent + 10 {
+                last_reported_percent = percent - percent % 10;
+                eprintln!("  {}% ({} / {} bytes)", last_reported_percent, downloaded, asset.size);
+            }
+        }
+    }
+    drop(temp);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Cannot mark {} executable: {}", temp_path.display(), e))?;
+    }
+
+    let final_path = dir.join(if cfg!(windows) { "pi.exe" } else { "pi" });
+    std::fs::rename(&temp_path, &final_path)
+        .map_err(|e| format!("Cannot install to {}: {}", final_path.display(), e))?;
+    Ok(final_path)
+}
+
+/// Fetches the checksum manifest next to the installed binary, via a
+/// temp file and rename like the binary itself.
+fn download_manifest(asset: &ReleaseAsset, dir: &std::path::Path) -> Result<(), String> {
+    let response = crate::http::agent_for(&asset.browser_download_url)
+        .get(&asset.browser_download_url)
+        .set("User-Agent", "package-installer-cli-wrapper")
+        .call()
+        .map_err(|e| {
+            format!(
+                "Download of {} failed: {} (check your network connection and retry)",
+                asset.browser_download_url, e
+            )
+        })?;
+    let body = response
+        .into_string()
+        .map_err(|e| format!("Cannot read the manifest download: {}", e))?;
+    let temp = dir.join(format!(".pi.manifest-{}", std::process::id()));
+    std::fs::write(&temp, &body).map_err(|e| format!("Cannot write to {}: {}", temp.display(), e))?;
+    let target = dir.join("manifest.json");
+    std::fs::rename(&temp, &target)
+        .map_err(|e| format!("Cannot install to {}: {}", target.display(), e))
+}
+
+fn update(args: &[String]) -> Result<(), String> {
+    let tag = parse_pinned_tag(args)?;
+    install_from_release(tag.as_deref()).map(|_| ())
+}
+
+/// Fetches the latest (or pinned) release and installs its platform
+/// asset into the user bundle directory, returning the installed path.
+/// Shared between `pi wrapper update` and the automatic download on a
+/// failed resolution.
+pub fn install_from_release(tag: Option<&str>) -> Result<PathBuf, String> {
+    let base = env::var("PI_WRAPPER_RELEASE_BASE").unwrap_or_else(|_| DEFAULT_RELEASE_BASE.to_string());
+
+    eprintln!("Checking for releases...");
+    let release = fetch_release(&base, tag)?;
+    let asset = select_asset(&release.assets, env::consts::OS, env::consts::ARCH).ok_or_else(|| {
+        format!(
+            "Release {} has no asset for {} {} (assets: {})",
+            release.tag_name,
+            env::consts::OS,
+            env::consts::ARCH,
+            release
+                .assets
+                .iter()
+                .map(|asset| asset.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })?;
+
+    let dir = user_bundle_dir().ok_or("Cannot determine the user data directory")?;
+    // One updater at a time; parallel CI shards otherwise tear the
+    // bundle mid-extraction
+    let _lock = crate::lock::for_write(&dir)
+        .ok_or("Another wrapper process is updating the bundle; try again shortly")?;
+    eprintln!("Downloading {} ({})...", asset.name, release.tag_name);
+    let installed = download_asset(asset, &dir)?;
+    // A published manifest.json lands next to the binary and is checked
+    // right away: a bundle that fails its own manifest is removed
+    // rather than left for the resolver to find and run
+    if let Some(manifest) = select_manifest(&release.assets) {
+        download_manifest(manifest, &dir)?;
+        if let Err(reason) = crate::verify::verify_bundle(&installed) {
+            let _ = std::fs::remove_file(&installed);
+            let _ = std::fs::remove_file(dir.join("manifest.json"));
+            return Err(format!("Downloaded bundle failed verification: {}", reason));
+        }
+        eprintln!("Verified {} against the release manifest", installed.display());
+    }
+    eprintln!("Installed {} to {}", release.tag_name, installed.display());
+
+    // Register the download under $PI_HOME/versions/<semver>/ too, so
+    // `pi wrapper use` and `.pi-version` pins can select it later. Tags
+    // that are not plain semver only get the bundle-standalone install.
+    if let Ok(version) = crate::versions::normalize_version(&release.tag_name) {
+        if let Some(target) = crate::versions::install_target(&version) {
+            register_version(&installed, &target)?;
+            eprintln!("Registered as version {} (select it with `pi wrapper use {}`)", version, version);
+        }
+    }
+    Ok(installed)
+}
+
+/// Copies the installed bundle to a per-version path, via a temp file
+/// and rename so an interrupted copy never looks like a complete
+/// installation.
+fn register_version(installed: &PathBuf, target: &PathBuf) -> Result<(), String> {
+    let dir = target.parent().ok_or("Version path has no parent directory")?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    let temp = dir.join(format!(".pi.download-{}", std::process::id()));
+    std::fs::copy(installed, &temp)
+        .map_err(|e| format!("Cannot copy to {}: {}", temp.display(), e))?;
+    std::fs::rename(&temp, target)
+        .map_err(|e| format!("Cannot install to {}: {}", target.display(), e))
+}
+
+/// Implements `pi wrapper update`; returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    match update(args) {
+        Ok(()) => 0,
+        Err(message) => {
+            eprintln!("{}", crate::ui::Style::for_stderr().error(&message));
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.invalid/{}", name),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn endpoints_cover_latest_and_pinned_tags() {
+        assert_eq!(
+            release_endpoint("https://api.example/repo", None),
+            "https://api.example/repo/releases/latest"
+        );
+        assert_eq!(
+            release_endpoint("https://api.example/repo", Some("v2.5.0")),
+            "https://api.example/repo/releases/tags/v2.5.0"
+        );
+    }
+
+    #[test]
+    fn asset_selection_matches_os_and_arch_tokens() {
+        let assets = vec![
+            asset("pi-windows-x64.exe"),
+            asset("pi-darwin-arm64"),
+            asset("pi-linux-amd64"),
+            asset("pi-linux-aarch64"),
+        ];
+        assert_eq!(
+            select_asset(&assets, "linux", "x86_64").map(|a| a.name.as_str()),
+            Some("pi-linux-amd64")
+        );
+        assert_eq!(
+            select_asset(&assets, "macos", "aarch64").map(|a| a.name.as_str()),
+            Some("pi-darwin-arm64")
+        );
+        assert_eq!(
+            select_asset(&assets, "windows", "x86_64").map(|a| a.name.as_str()),
+            Some("pi-windows-x64.exe")
+        );
+        assert!(select_asset(&assets, "linux", "riscv64").is_none());
+    }
+
+    #[test]
+    fn pinned_tag_parsing_requires_an_argument() {
+        let none = parse_pinned_tag(&[]).unwrap();
+        assert_eq!(none, None);
+        let pinned =
+            parse_pinned_tag(&["--version".to_string(), "v2.4.0".to_string()]).unwrap();
+        assert_eq!(pinned, Some("v2.4.0".to_string()));
+        assert!(parse_pinned_tag(&["--version".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Integrity verification for the standalone bundle.
+//!
+//! A bundle may ship a `manifest.json` next to the `pi` executable,
+//! mapping file names to their expected SHA-256 hex digests:
+//!
+//! ```json
+//! { "files": { "pi": "ba7816bf..." } }
+//! ```
+//!
+//! Before a bundled executable is run, every file listed in the
+//! manifest is hashed (streaming, so large bundles are never loaded
+//! into memory) and compared. A bundle without a manifest keeps working
+//! unverified, and `PI_WRAPPER_NO_VERIFY=1` skips the check entirely
+//! for development. Bundles fetched by `pi wrapper update` go through
+//! the same check, since they are executed via the same resolution
+//! path; `pi verify` re-checks cached bundles on demand, where a
+//! missing manifest counts as a failure.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::debug::debug_log;
+
+/// `manifest.json` layout: file name to expected SHA-256 hex digest.
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    files: BTreeMap<String, String>,
+}
+
+/// Streaming SHA-256 of a file, as a lowercase hex string.
+pub fn sha256_hex(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Verifies every file listed in the manifest next to `executable`.
+///
+/// A missing manifest is not an error — unsigned bundles keep working —
+/// but an unreadable manifest, a listed file that is missing, or a
+/// digest mismatch all are.
+pub fn verify_bundle(executable: &Path) -> Result<(), String> {
+    if std::env::var("PI_WRAPPER_NO_VERIFY").map(|v| v == "1").unwrap_or(false) {
+        debug_log!("bundle verification skipped (PI_WRAPPER_NO_VERIFY=1)");
+        return Ok(());
+    }
+
+    let Some(dir) = executable.parent() else {
+        return Ok(());
+    };
+    let manifest_path = dir.join("manifest.json");
+    if !manifest_path.exists() {
+        debug_log!("no manifest.json next to {}, running unverified", executable.display());
+        return Ok(());
+    }
+
+    verify_listed_files(dir, &manifest_path)
+}
+
+/// Explicit re-verification for `pi verify`: unlike the pre-execution
+/// check, a missing manifest is an error and `PI_WRAPPER_NO_VERIFY`
+/// does not apply — the user asked for the answer.
+pub fn recheck(executable: &Path) -> Result<(), String> {
+    let dir = executable
+        .parent()
+        .ok_or_else(|| format!("{} has no parent directory", executable.display()))?;
+    let manifest_path = dir.join("manifest.json");
+    if !manifest_path.exists() {
+        return Err(format!(
+            "No manifest.json next to {} — nothing to verify against",
+            executable.display()
+        ));
+    }
+    verify_listed_files(dir, &manifest_path)
+}
+
+/// Hashes and compares every file listed in `manifest_path`.
+fn verify_listed_files(dir: &Path, manifest_path: &Path) -> Result<(), String> {
+    let contents = std::fs::read_to_string(manifest_path)
+        .map_err(|e| format!("Cannot read {}: {}", manifest_path.display(), e))?;
+    let manifest: Manifest = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid manifest {}: {}", manifest_path.display(), e))?;
+
+    for (name, expected) in &manifest.files {
+        let file = dir.join(name);
+        let actual = sha256_hex(&file).map_err(|e| {
+            format!(
+                "Cannot verify {} listed in {}: {}",
+                file.display(),
+                manifest_path.display(),
+                e
+            )
+        })?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!(
+                "checksum mismatch for {}: manifest expects {} but the file hashes to {}",
+                file.display(),
+                expected,
+                actual
+            ));
+        }
+        debug_log!("verified {} ({})", file.display(), actual);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn bundle_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pi-wrapper-verify-test-{}-{}",
+            tag,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn sha256_matches_the_known_test_vector() {
+        let dir = bundle_dir("vector");
+        let file = dir.join("abc.txt");
+        std::fs::write(&file, "abc").unwrap();
+        assert_eq!(
+            sha256_hex(&file).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn matching_manifest_passes() {
+        let dir = bundle_dir("match");
+        let pi = dir.join("pi");
+        std::fs::write(&pi, "#!/bin/sh\n").unwrap();
+        let digest = sha256_hex(&pi).unwrap();
+        std::fs::write(
+            dir.join("manifest.json"),
+            format!(r#"{{"files": {{"pi": "{}"}}}}"#, digest),
+        )
+        .unwrap();
+
+        assert_eq!(verify_bundle(&pi), Ok(()));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn mismatching_manifest_names_file_and_both_hashes() {
+        let dir = bundle_dir("mismatch");
+        let pi = dir.join("pi");
+        std::fs::write(&pi, "#!/bin/sh\n").unwrap();
+        let actual = sha256_hex(&pi).unwrap();
+        let wrong = "0".repeat(64);
+        std::fs::write(
+            dir.join("manifest.json"),
+            format!(r#"{{"files": {{"pi": "{}"}}}}"#, wrong),
+        )
+        .unwrap();
+
+        let error = verify_bundle(&pi).unwrap_err();
+        assert!(error.contains("checksum mismatch"));
+        assert!(error.contains("pi"));
+        assert!(error.contains(&wrong));
+        assert!(error.contains(&actual));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn missing_manifest_runs_unverified() {
+        let dir = bundle_dir("nomanifest");
+        let pi = dir.join("pi");
+        std::fs::write(&pi, "#!/bin/sh\n").unwrap();
+        assert_eq!(verify_bundle(&pi), Ok(()));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn recheck_treats_a_missing_manifest_as_an_error() {
+        let dir = bundle_dir("recheck");
+        let pi = dir.join("pi");
+        std::fs::write(&pi, "#!/bin/sh\n").unwrap();
+        let error = recheck(&pi).unwrap_err();
+        assert!(error.contains("No manifest.json"));
+
+        let digest = sha256_hex(&pi).unwrap();
+        std::fs::write(
+            dir.join("manifest.json"),
+            format!(r#"{{"files": {{"pi": "{}"}}}}"#, digest),
+        )
+        .unwrap();
+        assert_eq!(recheck(&pi), Ok(()));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn listed_but_missing_sidecar_fails_verification() {
+        let dir = bundle_dir("sidecar");
+        let pi = dir.join("pi");
+        std::fs::write(&pi, "#!/bin/sh\n").unwrap();
+        let digest = sha256_hex(&pi).unwrap();
+        std::fs::write(
+            dir.join("manifest.json"),
+            format!(r#"{{"files": {{"pi": "{0}", "pi.data": "{0}"}}}}"#, digest),
+        )
+        .unwrap();
+
+        let error = verify_bundle(&pi).unwrap_err();
+        assert!(error.contains("pi.data"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,343 @@
+//! Multiple side-by-side CLI versions under `$PI_HOME/versions/`.
+//!
+//! `pi wrapper use <version>` records a global selection in a small
+//! state file, a `.pi-version` file in a project root pins a version
+//! for that tree (found by walking parent directories, like the local
+//! `node_modules` probe), and `pi wrapper list` shows what is installed
+//! and which version is active. `PI_HOME` defaults to the same per-user
+//! data directory the bundle updater uses
+//! (`~/.local/share/package-installer`, honoring `XDG_DATA_HOME`).
+
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::ui;
+
+/// Root directory for wrapper-managed state, overridable via `PI_HOME`.
+pub fn pi_home() -> Option<PathBuf> {
+    if let Ok(home) = env::var("PI_HOME") {
+        if !home.is_empty() {
+            return Some(PathBuf::from(home));
+        }
+    }
+    let data_home = env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| env::home_dir().map(|home| home.join(".local").join("share")))?;
+    Some(data_home.join("package-installer"))
+}
+
+fn versions_dir(home: &Path) -> PathBuf {
+    home.join("versions")
+}
+
+fn state_file(home: &Path) -> PathBuf {
+    home.join("active-version")
+}
+
+/// The executable a complete installation of `version` would contain.
+fn executable_in(home: &Path, version: &str) -> PathBuf {
+    versions_dir(home)
+        .join(version)
+        .join(if cfg!(windows) { "pi.exe" } else { "pi" })
+}
+
+/// Validates and normalizes a version string: an optional leading `v`
+/// is stripped, and what remains must be `major.minor.patch` with an
+/// optional `-prerelease` suffix.
+pub fn normalize_version(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    let version = trimmed.strip_prefix('v').unwrap_or(trimmed);
+    let core = version.split('-').next().unwrap_or("");
+    let parts: Vec<&str> = core.split('.').collect();
+    let valid = parts.len() == 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if !valid {
+        return Err(format!(
+            "'{}' is not a valid version — expected something like 2.5.0",
+            trimmed
+        ));
+    }
+    Ok(version.to_string())
+}
+
+/// Where a version selection came from, for status and debug output.
+pub enum SelectionSource {
+    /// A `.pi-version` file found in this directory.
+    Project(PathBuf),
+    /// The global state written by `pi wrapper use`.
+    Global,
+}
+
+impl fmt::Display for SelectionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectionSource::Project(dir) => {
+                write!(f, ".pi-version in {}", dir.display())
+            }
+            SelectionSource::Global => write!(f, "pi wrapper use"),
+        }
+    }
+}
+
+/// Finds a `.pi-version` pin by walking from `start` up through 5
+/// parent directories, matching the local `node_modules` probe depth.
+fn project_pin_in(start: &Path) -> Option<(String, PathBuf)> {
+    let mut check_dir = Some(start);
+    for _ in 0..=5 {
+        let dir = check_dir?;
+        let pin = dir.join(".pi-version");
+        if let Ok(contents) = std::fs::read_to_string(&pin) {
+            let version = contents.trim();
+            if !version.is_empty() {
+                return Some((version.to_string(), dir.to_path_buf()));
+            }
+        }
+        check_dir = dir.parent();
+    }
+    None
+}
+
+/// Reads the global selection recorded by `pi wrapper use`.
+fn global_selection(home: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(state_file(home)).ok()?;
+    let version = contents.trim();
+    if version.is_empty() {
+        None
+    } else {
+        Some(version.to_string())
+    }
+}
+
+/// The version the wrapper should run, if any is pinned: a project
+/// `.pi-version` beats the global selection. Invalid version strings
+/// are reported so a typo in a pin file is not silently ignored.
+pub fn selection() -> Option<(String, SelectionSource)> {
+    if let Ok(cwd) = env::current_dir() {
+        if let Some((raw, dir)) = project_pin_in(&cwd) {
+            return match normalize_version(&raw) {
+                Ok(version) => Some((version, SelectionSource::Project(dir))),
+                Err(reason) => {
+                    eprintln!(
+                        "{}",
+                        ui::Style::for_stderr().warn(&format!(
+                            "Ignoring .pi-version in {}: {}",
+                            dir.display(),
+                            reason
+                        ))
+                    );
+                    None
+                }
+            };
+        }
+    }
+    let home = pi_home()?;
+    let raw = global_selection(&home)?;
+    match normalize_version(&raw) {
+        Ok(version) => Some((version, SelectionSource::Global)),
+        Err(reason) => {
+            eprintln!(
+                "{}",
+                ui::Style::for_stderr().warn(&format!(
+                    "Ignoring the selection in {}: {}",
+                    state_file(&home).display(),
+                    reason
+                ))
+            );
+            None
+        }
+    }
+}
+
+/// The executable for an installed version, or `None` when the version
+/// is missing or its directory is incomplete (e.g. an interrupted
+/// download left the directory without the executable).
+pub fn installed_executable(version: &str) -> Option<PathBuf> {
+    let path = executable_in(&pi_home()?, version);
+    if path.exists() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Directory a release download should install `version` into. Created
+/// on demand.
+pub fn install_target(version: &str) -> Option<PathBuf> {
+    Some(executable_in(&pi_home()?, version))
+}
+
+/// Every version directory under `$PI_HOME/versions`, with whether the
+/// installation is complete, sorted by name.
+fn installed_versions(home: &Path) -> Vec<(String, bool)> {
+    let Ok(entries) = std::fs::read_dir(versions_dir(home)) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<(String, bool)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let complete = executable_in(home, &name).exists();
+            (name, complete)
+        })
+        .collect();
+    versions.sort();
+    versions
+}
+
+/// Atomically records `version` as the global selection: the state is
+/// written to a temp file and renamed into place, so concurrent `use`
+/// calls end with one whole selection rather than interleaved bytes.
+fn write_selection(home: &Path, version: &str) -> Result<(), String> {
+    std::fs::create_dir_all(home).map_err(|e| format!("Cannot create {}: {}", home.display(), e))?;
+    let state = state_file(home);
+    let temp = home.join(format!(".active-version-{}", std::process::id()));
+    std::fs::write(&temp, format!("{}\n", version))
+        .map_err(|e| format!("Cannot write to {}: {}", temp.display(), e))?;
+    std::fs::rename(&temp, &state)
+        .map_err(|e| format!("Cannot update {}: {}", state.display(), e))
+}
+
+fn use_version(args: &[String]) -> Result<String, String> {
+    let raw = args
+        .first()
+        .ok_or("wrapper use requires a version argument (e.g. pi wrapper use 2.5.0)")?;
+    let version = normalize_version(raw)?;
+    let home = pi_home().ok_or("Cannot determine the user data directory")?;
+    if !executable_in(&home, &version).exists() {
+        return Err(format!(
+            "Version {} is not installed — run `pi wrapper update --version v{}` to fetch it",
+            version, version
+        ));
+    }
+    let _lock = crate::lock::for_write(&state_file(&home))
+        .ok_or("Another wrapper process is changing the selected version; try again shortly")?;
+    write_selection(&home, &version)?;
+    Ok(version)
+}
+
+/// Implements `pi wrapper use <version>`; returns the process exit code.
+pub fn run_use(args: &[String]) -> i32 {
+    let style = ui::Style::for_stderr();
+    match use_version(args) {
+        Ok(version) => {
+            eprintln!("{}", style.ok(&format!("Now using CLI version {}", version)));
+            0
+        }
+        Err(message) => {
+            eprintln!("{}", style.error(&message));
+            1
+        }
+    }
+}
+
+/// Implements `pi wrapper list`; returns the process exit code.
+pub fn run_list() -> i32 {
+    let Some(home) = pi_home() else {
+        eprintln!(
+            "{}",
+            ui::Style::for_stderr().error("Cannot determine the user data directory")
+        );
+        return 1;
+    };
+    let active = selection().map(|(version, _)| version);
+    let versions = installed_versions(&home);
+    if versions.is_empty() {
+        println!(
+            "No versions installed under {} (run `pi wrapper update` to fetch one)",
+            versions_dir(&home).display()
+        );
+        return 0;
+    }
+    for (version, complete) in versions {
+        let marker = if Some(&version) == active.as_ref() { "*" } else { " " };
+        let note = if complete { "" } else { "  (incomplete download)" };
+        println!("{} {}{}", marker, version, note);
+    }
+    if let Some((version, source)) = selection() {
+        println!("\nActive: {} (from {})", version, source);
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn home_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pi-wrapper-versions-test-{}-{}",
+            tag,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn versions_normalize_with_and_without_the_v_prefix() {
+        assert_eq!(normalize_version("2.5.0").unwrap(), "2.5.0");
+        assert_eq!(normalize_version("v2.5.0").unwrap(), "2.5.0");
+        assert_eq!(normalize_version(" v2.5.0-beta.1 ").unwrap(), "2.5.0-beta.1");
+    }
+
+    #[test]
+    fn invalid_versions_are_rejected() {
+        for raw in ["", "latest", "2.5", "2..0", "2.5.x"] {
+            assert!(normalize_version(raw).is_err(), "{raw:?} should be invalid");
+        }
+    }
+
+    #[test]
+    fn project_pin_is_found_in_a_parent_directory() {
+        let root = home_dir("pin");
+        std::fs::write(root.join(".pi-version"), "2.4.0\n").unwrap();
+        let nested = root.join("packages").join("app");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let (version, dir) = project_pin_in(&nested).unwrap();
+        assert_eq!(version, "2.4.0");
+        assert_eq!(dir, root);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn selection_writes_are_atomic_and_readable() {
+        let home = home_dir("select");
+        write_selection(&home, "2.5.0").unwrap();
+        assert_eq!(global_selection(&home), Some("2.5.0".to_string()));
+        // No temp file is left behind
+        let leftovers: Vec<_> = std::fs::read_dir(&home)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(".active-version-")
+            })
+            .collect();
+        assert!(leftovers.is_empty());
+        std::fs::remove_dir_all(&home).ok();
+    }
+
+    #[test]
+    fn incomplete_version_dirs_are_flagged_not_hidden() {
+        let home = home_dir("list");
+        let complete = versions_dir(&home).join("2.5.0");
+        std::fs::create_dir_all(&complete).unwrap();
+        std::fs::write(complete.join(if cfg!(windows) { "pi.exe" } else { "pi" }), "#!/bin/sh\n")
+            .unwrap();
+        std::fs::create_dir_all(versions_dir(&home).join("2.4.0")).unwrap();
+
+        assert_eq!(
+            installed_versions(&home),
+            vec![("2.4.0".to_string(), false), ("2.5.0".to_string(), true)]
+        );
+        std::fs::remove_dir_all(&home).ok();
+    }
+}
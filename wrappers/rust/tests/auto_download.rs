@@ -0,0 +1,110 @@
+//! Integration tests: when resolution finds no CLI anywhere, the
+//! wrapper fetches the standalone bundle from the (mock) releases API,
+//! installs it into the per-user bundle directory, and runs it —
+//! unless `--no-download` opts out.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+
+use harness::{test_root, wrapper};
+
+const BUNDLE_BODY: &str = "#!/bin/sh\necho AUTO_DOWNLOADED_CLI\n";
+
+/// Serves the releases API and the asset download from one listener.
+fn start_mock_release_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = [0u8; 4096];
+            let Ok(read) = stream.read(&mut request) else { continue };
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+
+            let body = if request.starts_with("GET /releases/latest") {
+                format!(
+                    r#"{{"tag_name": "v9.9.9", "assets": [
+                        {{"name": "pi-{os}-{arch}", "browser_download_url": "http://127.0.0.1:{port}/asset", "size": {size}}}
+                    ]}}"#,
+                    port = port,
+                    os = std::env::consts::OS,
+                    arch = std::env::consts::ARCH,
+                    size = BUNDLE_BODY.len()
+                )
+            } else {
+                BUNDLE_BODY.to_string()
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    format!("http://127.0.0.1:{}", port)
+}
+
+/// A wrapper invocation with nothing resolvable and the offline
+/// switches cleared, so the download path is actually reachable.
+fn download_wrapper(root: &Path, base: &str) -> Command {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    let mut command = wrapper(root, &project);
+    command
+        .env_remove("PI_NO_UPDATE_CHECK")
+        .env_remove("CI")
+        .env("PI_WRAPPER_RELEASE_BASE", base);
+    command
+}
+
+#[test]
+fn a_failed_resolution_downloads_the_bundle_and_runs_it() {
+    let base = start_mock_release_server();
+    let root = test_root("autodownload-fetch");
+
+    let output = download_wrapper(&root, &base)
+        .arg("analyze")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "AUTO_DOWNLOADED_CLI\n");
+
+    // The download landed where the resolver probes on the next run
+    let installed = root
+        .join("data")
+        .join("package-installer")
+        .join("bundle-standalone")
+        .join("pi");
+    assert_eq!(std::fs::read_to_string(&installed).unwrap(), BUNDLE_BODY);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn no_download_keeps_the_failure_and_touches_nothing() {
+    let base = start_mock_release_server();
+    let root = test_root("autodownload-optout");
+
+    let output = download_wrapper(&root, &base)
+        .args(["--no-download", "analyze"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(!root
+        .join("data")
+        .join("package-installer")
+        .join("bundle-standalone")
+        .exists());
+
+    std::fs::remove_dir_all(&root).ok();
+}
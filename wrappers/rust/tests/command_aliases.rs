@@ -0,0 +1,117 @@
+//! Integration tests: user-defined aliases from `aliases.toml` expand
+//! the first forwarded argument, extra arguments are appended, and the
+//! wrapper's own subcommands can never be shadowed.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::{fake_node_script, recorded_args, test_root, wrapper};
+
+/// Writes `contents` as the test root's alias file.
+fn alias_file(root: &Path, contents: &str) {
+    let dir = root.join("config").join("package-installer");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("aliases.toml"), contents).unwrap();
+}
+
+/// A project with a local CLI install whose argv lands in the returned
+/// marker file.
+fn project_with_local_cli(root: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let marker = root.join("args.txt");
+    fake_node_script(
+        &project
+            .join("node_modules")
+            .join("@0xshariq")
+            .join("package-installer")
+            .join("dist")
+            .join("index.js"),
+        &marker,
+        0,
+    );
+    (project, marker)
+}
+
+#[test]
+fn an_alias_expands_and_extra_arguments_are_appended() {
+    let root = test_root("aliases-expand");
+    let (project, marker) = project_with_local_cli(&root);
+    alias_file(&root, "ad = [\"analyze\", \"--detailed\"]\n");
+
+    let status = wrapper(&root, &project)
+        .args(["ad", "src/", "--json"])
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert_eq!(
+        recorded_args(&marker),
+        ["analyze", "--detailed", "src/", "--json"]
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn unaliased_commands_pass_through_untouched() {
+    let root = test_root("aliases-passthrough");
+    let (project, marker) = project_with_local_cli(&root);
+    alias_file(&root, "c = [\"create\"]\n");
+
+    let status = wrapper(&root, &project)
+        .args(["analyze", "c"])
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    // `c` past position zero is an ordinary argument, not an alias
+    assert_eq!(recorded_args(&marker), ["analyze", "c"]);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn aliases_cannot_shadow_wrapper_subcommands() {
+    let root = test_root("aliases-shadow");
+    let (project, _marker) = project_with_local_cli(&root);
+    alias_file(&root, "wrapper = [\"create\", \"evil\"]\n");
+
+    let output = wrapper(&root, &project)
+        .args(["wrapper", "list"])
+        .output()
+        .unwrap();
+
+    // The wrapper's own `list` must still answer, not the alias
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("No versions installed"),
+        "`pi wrapper list` must keep working, got: {stdout}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_malformed_alias_file_warns_but_does_not_break_commands() {
+    let root = test_root("aliases-malformed");
+    let (project, marker) = project_with_local_cli(&root);
+    alias_file(&root, "this is not toml [[[");
+
+    let output = wrapper(&root, &project).arg("analyze").output().unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(recorded_args(&marker), ["analyze"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Ignoring malformed alias file"),
+        "got: {stderr}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,164 @@
+//! Integration tests: each forwarded command lands in
+//! `history.jsonl` with its resolution source — plus exit code and
+//! duration on the spawn-and-wait path, while the default `exec()`
+//! path records neither — secrets are redacted before they touch
+//! disk, and `pi wrapper history` slices the log with `--last`,
+//! `--failed` and `--json`.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+
+use harness::{fake_node_script, test_root, wrapper};
+
+fn project_with_local_cli(root: &Path, exit_code: i32) -> PathBuf {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    fake_node_script(
+        &project
+            .join("node_modules")
+            .join("@0xshariq")
+            .join("package-installer")
+            .join("dist")
+            .join("index.js"),
+        &root.join("args.txt"),
+        exit_code,
+    );
+    project
+}
+
+fn history_file(root: &Path) -> PathBuf {
+    root.join("data").join("package-installer").join("history.jsonl")
+}
+
+#[test]
+fn runs_are_recorded_with_exit_code_source_and_duration() {
+    let root = test_root("history-record");
+    let project = project_with_local_cli(&root, 3);
+
+    // Spawn-and-wait so the wrapper survives to observe the exit code
+    let status = wrapper(&root, &project)
+        .env("PI_WRAPPER_NO_EXEC", "1")
+        .args(["analyze", "--detailed"])
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+
+    let contents = std::fs::read_to_string(history_file(&root)).expect("history written");
+    let entry: serde_json::Value = serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["exit_code"], 3);
+    assert_eq!(entry["source"], "local-npm");
+    assert_eq!(entry["args"][0], "analyze");
+    assert_eq!(entry["args"][1], "--detailed");
+    assert!(entry["timestamp"].as_u64().unwrap() > 0);
+    assert!(entry["duration_ms"].is_u64());
+    assert_eq!(entry["cwd"], project.display().to_string());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn execed_runs_are_recorded_without_exit_code_or_duration() {
+    let root = test_root("history-exec");
+    let project = project_with_local_cli(&root, 0);
+
+    let status = wrapper(&root, &project).arg("analyze").status().unwrap();
+    assert!(status.success());
+
+    // The entry is written just before exec() replaces the wrapper, so
+    // the fields only a surviving parent could fill stay null
+    let contents = std::fs::read_to_string(history_file(&root)).expect("history written");
+    let entry: serde_json::Value = serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["exit_code"], serde_json::Value::Null);
+    assert_eq!(entry["duration_ms"], serde_json::Value::Null);
+    assert_eq!(entry["source"], "local-npm");
+    assert_eq!(entry["args"][0], "analyze");
+    assert!(entry["timestamp"].as_u64().unwrap() > 0);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn secrets_never_reach_the_history_file() {
+    let root = test_root("history-redact");
+    let project = project_with_local_cli(&root, 0);
+
+    let status = wrapper(&root, &project)
+        .args(["deploy", "--token", "secret123", "--password=hunter2"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let contents = std::fs::read_to_string(history_file(&root)).unwrap();
+    assert!(!contents.contains("secret123"), "got: {contents}");
+    assert!(!contents.contains("hunter2"), "got: {contents}");
+    assert!(contents.contains("<redacted>"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_kill_switch_disables_recording() {
+    let root = test_root("history-off");
+    let project = project_with_local_cli(&root, 0);
+
+    let status = wrapper(&root, &project)
+        .env("PI_WRAPPER_NO_HISTORY", "1")
+        .arg("analyze")
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(!history_file(&root).exists());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_viewer_slices_by_last_and_failed_and_emits_json() {
+    let root = test_root("history-view");
+    let project = project_with_local_cli(&root, 0);
+    let file = history_file(&root);
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    let seeded: String = [
+        r#"{"timestamp":100,"cwd":"/a","args":["create","one"],"source":"local-npm","exit_code":0,"duration_ms":10}"#,
+        r#"{"timestamp":200,"cwd":"/a","args":["analyze"],"source":"local-npm","exit_code":2,"duration_ms":20}"#,
+        r#"{"timestamp":300,"cwd":"/a","args":["create","two"],"source":"global-npm","exit_code":0,"duration_ms":30}"#,
+        r#"{"timestamp":400,"cwd":"/a","args":["deploy"],"source":"bundled","exit_code":1,"duration_ms":40}"#,
+    ]
+    .map(|line| format!("{line}\n"))
+    .concat();
+    std::fs::write(&file, seeded).unwrap();
+
+    let view = |extra: &[&str]| {
+        let output = wrapper(&root, &project)
+            .args(["wrapper", "history"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(0), "args: {extra:?}");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let all = view(&[]);
+    assert_eq!(all.lines().count(), 4);
+    assert!(all.contains("pi create one"));
+
+    let last = view(&["--last", "2"]);
+    assert_eq!(last.lines().count(), 2);
+    assert!(!last.contains("create one"), "got: {last}");
+    assert!(last.contains("pi deploy"));
+
+    let failed = view(&["--failed"]);
+    assert_eq!(failed.lines().count(), 2);
+    assert!(failed.contains("exit 2") && failed.contains("exit 1"), "got: {failed}");
+
+    let json = view(&["--json", "--failed", "--last", "1"]);
+    let entry: serde_json::Value = serde_json::from_str(json.trim()).unwrap();
+    assert_eq!(entry["args"][0], "deploy");
+    assert_eq!(entry["exit_code"], 1);
+
+    std::fs::remove_dir_all(&root).ok();
+}
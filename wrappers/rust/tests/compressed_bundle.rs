@@ -0,0 +1,134 @@
+//! Integration tests: a `bundle-standalone.tar.gz` next to the wrapper
+//! binary is extracted into the per-user bundle cache on first use,
+//! reused while its hash is unchanged, and re-extracted into a fresh
+//! hash directory when the archive is replaced.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use harness::{test_root, wrapper_at};
+
+/// Builds `bundle-standalone.tar.gz` in `dir`, containing a `pi` stub
+/// that records its argv into `marker` and exits with `exit_code`.
+fn bundle_archive(dir: &Path, marker: &Path, exit_code: i32) {
+    use std::os::unix::fs::PermissionsExt;
+    let stage = dir.join(format!(".stage-{exit_code}"));
+    std::fs::create_dir_all(&stage).unwrap();
+    let pi = stage.join("pi");
+    std::fs::write(
+        &pi,
+        format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$@\" > {}\nexit {}\n",
+            marker.display(),
+            exit_code
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&pi, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(dir.join("bundle-standalone.tar.gz"))
+        .arg("-C")
+        .arg(&stage)
+        .arg("pi")
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn extracted_roots(root: &Path) -> Vec<PathBuf> {
+    let bundles = root.join("cache").join("package-installer").join("bundles");
+    let Ok(entries) = std::fs::read_dir(bundles) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+#[test]
+fn the_archive_is_extracted_once_and_reused_until_replaced() {
+    let root = test_root("compressed-bundle");
+    let bin_dir = root.join("release");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    let installed = bin_dir.join("pi");
+    std::fs::copy(env!("CARGO_BIN_EXE_package-installer-cli"), &installed).unwrap();
+    let marker = root.join("invoked.txt");
+    bundle_archive(&bin_dir, &marker, 7);
+    // Run from an empty project so only the bundled step can win
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let home = root.join("home");
+    std::fs::create_dir_all(&home).unwrap();
+
+    let run = || {
+        wrapper_at(&installed, &root, &project)
+            .arg("analyze")
+            .env("HOME", &home)
+            .env("PATH", "/usr/bin:/bin")
+            .output()
+            .unwrap()
+    };
+
+    // First run extracts and executes the stub from the cache
+    let output = run();
+    assert_eq!(
+        output.status.code(),
+        Some(7),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(extracted_roots(&root).len(), 1);
+
+    // Second run reuses the same extraction
+    std::fs::remove_file(&marker).unwrap();
+    assert_eq!(run().status.code(), Some(7));
+    assert_eq!(extracted_roots(&root).len(), 1);
+
+    // A replaced archive is re-extracted into a fresh hash directory
+    std::fs::remove_file(bin_dir.join("bundle-standalone.tar.gz")).unwrap();
+    bundle_archive(&bin_dir, &marker, 9);
+    assert_eq!(run().status.code(), Some(9));
+    assert_eq!(extracted_roots(&root).len(), 2);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_loose_bundle_directory_beats_the_archive() {
+    let root = test_root("compressed-vs-loose");
+    let bin_dir = root.join("release");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    let installed = bin_dir.join("pi");
+    std::fs::copy(env!("CARGO_BIN_EXE_package-installer-cli"), &installed).unwrap();
+    let marker = root.join("invoked.txt");
+    bundle_archive(&bin_dir, &marker, 9);
+    harness::fake_executable(&bin_dir.join("bundle-standalone").join("pi"), &marker, 0);
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let home = root.join("home");
+    std::fs::create_dir_all(&home).unwrap();
+
+    let output = wrapper_at(&installed, &root, &project)
+        .arg("analyze")
+        .env("HOME", &home)
+        .env("PATH", "/usr/bin:/bin")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0), "the loose bundle must win");
+    assert!(
+        extracted_roots(&root).is_empty(),
+        "the archive must not even be extracted when a loose bundle exists"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
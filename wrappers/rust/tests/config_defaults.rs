@@ -0,0 +1,188 @@
+//! Integration tests: the per-user config file can live at
+//! `~/.config/pi/config.toml`, and its `default_flags` apply to every
+//! invocation while explicit flags keep winning.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+
+use harness::{test_root, wrapper};
+
+fn local_project(root: &Path) -> PathBuf {
+    let project = root.join("project");
+    let dist = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    std::fs::create_dir_all(&dist).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    std::fs::write(dist.join("index.js"), "console.log('MUST_NOT_RUN');\n").unwrap();
+    project
+}
+
+fn write_pi_config(root: &Path, contents: &str) {
+    let dir = root.join("config").join("pi");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("config.toml"), contents).unwrap();
+}
+
+#[test]
+fn default_flags_from_the_pi_config_file_apply_to_every_run() {
+    let root = test_root("configdefaults-defaults");
+    let project = local_project(&root);
+    write_pi_config(&root, "default_flags = [\"--wrapper-dry-run\"]\n");
+
+    let output = wrapper(&root, &project)
+        .args(["analyze"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("MUST_NOT_RUN"));
+    assert!(stdout.contains("source: local node_modules"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_canonical_wrapper_toml_wins_over_the_pi_spelling() {
+    let root = test_root("configdefaults-precedence");
+    let project = local_project(&root);
+    write_pi_config(&root, "default_flags = [\"--no-such-wrapper-flag\"]\n");
+    let canonical = root.join("config").join("package-installer");
+    std::fs::create_dir_all(&canonical).unwrap();
+    std::fs::write(
+        canonical.join("wrapper.toml"),
+        "default_flags = [\"--wrapper-dry-run\"]\n",
+    )
+    .unwrap();
+
+    let output = wrapper(&root, &project)
+        .args(["analyze"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("source: local node_modules"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn js_runtime_from_config_picks_bun_without_probing() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = test_root("configdefaults-jsruntime");
+    let project = local_project(&root);
+    write_pi_config(&root, "js_runtime = \"bun\"\n");
+
+    // A fake bun on PATH records its arguments instead of running JS
+    let bin = root.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let marker = root.join("bun-args.txt");
+    let script = format!("#!/bin/sh\nprintf '%s\\n' \"$@\" > {}\n", marker.display());
+    std::fs::write(bin.join("bun"), script).unwrap();
+    std::fs::set_permissions(bin.join("bun"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let output = wrapper(&root, &project)
+        .env("PATH", path)
+        .args(["analyze"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let args = std::fs::read_to_string(&marker).unwrap();
+    let args: Vec<&str> = args.lines().collect();
+    assert_eq!(args.first(), Some(&"run"));
+    assert!(args.iter().any(|arg| arg.ends_with("index.js")));
+    assert!(args.contains(&"analyze"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_runtime_flag_wins_over_the_config_key() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = test_root("configdefaults-runtime-flag");
+    let project = local_project(&root);
+    write_pi_config(&root, "js_runtime = \"node\"\n");
+
+    let bin = root.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let marker = root.join("bun-args.txt");
+    let script = format!("#!/bin/sh\nprintf '%s\\n' \"$@\" > {}\n", marker.display());
+    std::fs::write(bin.join("bun"), script).unwrap();
+    std::fs::set_permissions(bin.join("bun"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let output = wrapper(&root, &project)
+        .env("PATH", path)
+        .args(["--runtime", "bun", "analyze"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let args = std::fs::read_to_string(&marker).unwrap();
+    assert!(args.lines().any(|arg| arg == "analyze"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn an_unknown_js_runtime_in_config_is_an_error() {
+    let root = test_root("configdefaults-jsruntime-bad");
+    let project = local_project(&root);
+    write_pi_config(&root, "js_runtime = \"quickjs\"\n");
+
+    let output = wrapper(&root, &project)
+        .args(["analyze"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("js_runtime") && stderr.contains("quickjs"),
+        "stderr: {stderr}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn resolution_settings_load_from_the_pi_config_file() {
+    let root = test_root("configdefaults-resolution");
+    let project = local_project(&root);
+    // Bundled-first order skips the local install entirely
+    write_pi_config(&root, "resolution_order = [\"bundled\"]\n");
+
+    let output = wrapper(&root, &project)
+        .args(["--wrapper-dry-run", "analyze"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        !stdout.contains("local node_modules"),
+        "bundled-first order must not pick the local install: {stdout}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,86 @@
+//! Integration tests: `-C <dir>` / `--cwd <dir>` runs the CLI in a
+//! different directory — resolution and the child process both follow.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn test_root(tag: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pi-wrapper-cwd-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+/// A project with a local CLI install whose entry prints `process.cwd()`.
+fn fake_project(root: &Path) -> PathBuf {
+    let project = root.join("project");
+    let dist = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    std::fs::create_dir_all(&dist).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    std::fs::write(
+        dist.join("index.js"),
+        "console.log('LOCAL_CLI ' + process.cwd());\n",
+    )
+    .unwrap();
+    project
+}
+
+fn wrapper_command(root: &Path, from: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"));
+    command
+        .current_dir(from)
+        .env_remove("PI_CLI_PATH")
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_DATA_HOME", root.join("data"));
+    command
+}
+
+#[test]
+fn cwd_flag_resolves_and_runs_in_the_target_directory() {
+    let root = test_root("switch");
+    let project = fake_project(&root);
+    let elsewhere = root.join("elsewhere");
+    std::fs::create_dir_all(&elsewhere).unwrap();
+
+    let output = wrapper_command(&root, &elsewhere)
+        .args(["-C", &project.display().to_string(), "analyze"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Resolution found the project-local install, and the child's cwd
+    // is the target directory, not where the wrapper was started
+    let canonical_project = std::fs::canonicalize(&project).unwrap();
+    assert_eq!(
+        stdout.trim(),
+        format!("LOCAL_CLI {}", canonical_project.display())
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_nonexistent_cwd_is_a_clean_error_naming_the_path() {
+    let root = test_root("missing");
+    let output = wrapper_command(&root, &root)
+        .args(["--cwd", "/no/such/directory", "analyze"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("/no/such/directory"),
+        "expected the path in the error, got: {stderr}"
+    );
+    assert!(output.stdout.is_empty());
+
+    std::fs::remove_dir_all(&root).ok();
+}
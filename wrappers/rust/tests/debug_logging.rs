@@ -0,0 +1,64 @@
+//! Integration test: `PI_WRAPPER_DEBUG=1` logs every probed candidate
+//! in order, with the `[pi-wrapper]` prefix, and names the winner.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn debug_output_mentions_every_probed_location_in_order() {
+    // Project root holds the install; the wrapper runs from a nested
+    // subdirectory, so the first probes miss and a parent probe wins.
+    let root = std::env::temp_dir().join(format!("pi-wrapper-debug-test-{}", std::process::id()));
+    let dist = root
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    fs::create_dir_all(&dist).unwrap();
+    fs::write(dist.join("index.js"), "console.log('CHILD_OUT');\n").unwrap();
+    let subdir = root.join("packages").join("app");
+    fs::create_dir_all(&subdir).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .arg("analyze")
+        .current_dir(&subdir)
+        .env_remove("PI_CLI_PATH")
+        .env("PI_WRAPPER_DEBUG", "1")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let position = |path: &PathBuf| {
+        stderr
+            .find(&path.display().to_string())
+            .unwrap_or_else(|| panic!("debug output must mention {}", path.display()))
+    };
+
+    // Every probed location on the walk from subdir to the winning root
+    // appears, in walk order
+    let scoped = "node_modules/@0xshariq/package-installer/dist/index.js";
+    let unscoped = "node_modules/package-installer-cli/dist/index.js";
+    let probes = [
+        subdir.join(scoped),
+        subdir.join(unscoped),
+        root.join("packages").join(scoped),
+        root.join("packages").join(unscoped),
+        root.join(scoped),
+    ];
+    let positions: Vec<_> = probes.iter().map(position).collect();
+    let mut sorted = positions.clone();
+    sorted.sort_unstable();
+    assert_eq!(positions, sorted, "probes must be logged in walk order");
+
+    // Every debug line carries the prefix, misses and the winner are
+    // called out, and stdout stays pure child output
+    assert!(stderr.lines().all(|line| line.starts_with("[pi-wrapper]")));
+    assert!(stderr.contains("missing"));
+    assert!(stderr.contains(&format!("winner: {} (local)", root.join(scoped).display())));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "CHILD_OUT\n");
+
+    fs::remove_dir_all(&root).ok();
+}
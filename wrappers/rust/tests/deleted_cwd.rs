@@ -0,0 +1,116 @@
+//! Integration tests: the wrapper must survive its working directory
+//! being deleted from under it (a stale shell after `rm -rf`). The cwd
+//! is removed between `cd` and the wrapper invocation by a small shell
+//! trampoline, since `Command::current_dir` cannot point at a
+//! directory that is already gone.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+use std::process::Command;
+
+use harness::{fake_executable, recorded_args, test_root};
+
+/// Runs `binary` with `args` from a directory that is deleted first,
+/// with the harness's usual env hygiene applied.
+fn run_from_deleted_dir(binary: &Path, root: &Path, args: &[&str]) -> Command {
+    let doomed = root.join("doomed");
+    std::fs::create_dir_all(&doomed).unwrap();
+    let home = root.join("home");
+    std::fs::create_dir_all(&home).unwrap();
+    // Absolute so the trampoline itself survives a stripped PATH
+    let mut command = Command::new("/bin/sh");
+    command
+        .arg("-c")
+        .arg(r#"cd "$1" && /bin/rm -rf "$1" && bin="$2" && shift 2 && exec "$bin" "$@""#)
+        .arg("sh")
+        .arg(&doomed)
+        .arg(binary)
+        .args(args)
+        .env_remove("PI_CLI_PATH")
+        .env_remove("PI_WRAPPER_NO_LOCAL")
+        .env("HOME", &home)
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_CONFIG_HOME", root.join("config"))
+        .env("XDG_DATA_HOME", root.join("data"));
+    command
+}
+
+#[test]
+fn env_override_still_runs_when_the_cwd_is_gone() {
+    let root = test_root("deleted-cwd-override");
+    let marker = root.join("invoked.txt");
+    let stub = root.join("stub").join("pi");
+    fake_executable(&stub, &marker, 5);
+
+    let output = run_from_deleted_dir(
+        Path::new(env!("CARGO_BIN_EXE_package-installer-cli")),
+        &root,
+        &["analyze"],
+    )
+    .env("PI_CLI_PATH", &stub)
+    .output()
+    .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(5),
+        "override must run despite the deleted cwd; stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(recorded_args(&marker), ["analyze"]);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn bundled_standalone_still_resolves_when_the_cwd_is_gone() {
+    let root = test_root("deleted-cwd-bundled");
+    // Install the wrapper into a fake release layout with a bundle
+    let bin_dir = root.join("release");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    let installed = bin_dir.join("pi");
+    std::fs::copy(env!("CARGO_BIN_EXE_package-installer-cli"), &installed).unwrap();
+    let marker = root.join("invoked.txt");
+    fake_executable(&bin_dir.join("bundle-standalone").join("pi"), &marker, 0);
+
+    let output = run_from_deleted_dir(&installed, &root, &["create", "my-app"])
+        .env("PATH", "/nonexistent")
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "bundled resolution needs no cwd; stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(recorded_args(&marker), ["create", "my-app"]);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn failure_diagnostics_name_the_skipped_cwd_relative_sources() {
+    let root = test_root("deleted-cwd-diagnostics");
+
+    let output = run_from_deleted_dir(
+        Path::new(env!("CARGO_BIN_EXE_package-installer-cli")),
+        &root,
+        &["analyze"],
+    )
+    .env("PATH", "/nonexistent")
+    .output()
+    .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("skipped (working directory unavailable)"),
+        "expected the cwd-skip reason in the attempt list, got: {stderr}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,68 @@
+//! Integration tests for `--wrapper-docker`. They need a running
+//! Docker daemon and pull `node:lts-slim`, so they only run with
+//! `cargo test --features docker-tests`.
+
+#![cfg(all(unix, feature = "docker-tests"))]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::{test_root, wrapper};
+
+/// A project with a local CLI install whose entrypoint records its argv
+/// (container-side) into `/work/args.txt` and exits with `exit_code`.
+fn project_with_local_cli(root: &Path, exit_code: i32) -> std::path::PathBuf {
+    let project = root.join("project");
+    let entry = project.join("node_modules/@0xshariq/package-installer/dist/index.js");
+    std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+    std::fs::write(
+        &entry,
+        format!(
+            "require('fs').writeFileSync('/work/args.txt', process.argv.slice(2).join('\\n'));\n\
+             console.log('CONTAINER_OUT');\n\
+             process.exit({exit_code});\n"
+        ),
+    )
+    .unwrap();
+    project
+}
+
+#[test]
+fn a_mounted_local_install_runs_inside_the_container() {
+    let root = test_root("docker-local");
+    let project = project_with_local_cli(&root, 0);
+
+    let output = wrapper(&root, &project)
+        .args(["--wrapper-docker", "analyze", "--verbose"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(0), "stderr: {stderr}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("CONTAINER_OUT"));
+    // The mount works both ways: the entrypoint wrote into /work
+    let recorded = std::fs::read_to_string(project.join("args.txt")).unwrap();
+    assert_eq!(recorded.lines().collect::<Vec<_>>(), ["analyze", "--verbose"]);
+    // --user mapped the file back to us, not root
+    {
+        use std::os::unix::fs::MetadataExt;
+        let meta = std::fs::metadata(project.join("args.txt")).unwrap();
+        assert_eq!(meta.uid(), unsafe { libc::getuid() });
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_container_exit_code_is_propagated() {
+    let root = test_root("docker-exit");
+    let project = project_with_local_cli(&root, 7);
+
+    let output = wrapper(&root, &project)
+        .args(["--wrapper-docker", "analyze"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(7));
+
+    std::fs::remove_dir_all(&root).ok();
+}
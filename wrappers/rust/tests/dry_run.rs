@@ -0,0 +1,120 @@
+//! Integration tests: `--wrapper-dry-run` prints the invocation that
+//! would run — `source:` / `program:` / `arg:` lines — without
+//! executing anything.
+
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn test_root(tag: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pi-wrapper-dryrun-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn wrapper_command(root: &Path, from: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"));
+    command
+        .current_dir(from)
+        .env_remove("PI_CLI_PATH")
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_DATA_HOME", root.join("data"));
+    command
+}
+
+#[test]
+fn dry_run_prints_the_local_node_invocation_without_running_it() {
+    let root = test_root("local");
+    let project = root.join("project");
+    let dist = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    std::fs::create_dir_all(&dist).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    std::fs::write(dist.join("index.js"), "console.log('MUST_NOT_RUN');\n").unwrap();
+
+    let output = wrapper_command(&root, &project)
+        .args(["--wrapper-dry-run", "analyze", "--verbose"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("MUST_NOT_RUN"));
+    assert_eq!(
+        stdout,
+        format!(
+            "source: local node_modules\nprogram: node\narg: {}\narg: analyze\narg: --verbose\n",
+            dist.join("index.js").display()
+        )
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn dry_run_composes_with_cwd_override_and_no_local() {
+    let root = test_root("compose");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    let elsewhere = root.join("elsewhere");
+    std::fs::create_dir_all(&elsewhere).unwrap();
+    let override_cli = root.join("custom-pi");
+    std::fs::write(&override_cli, "#!/bin/sh\necho MUST_NOT_RUN\n").unwrap();
+    std::fs::set_permissions(&override_cli, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = wrapper_command(&root, &elsewhere)
+        .args([
+            "-C",
+            &project.display().to_string(),
+            "--wrapper-dry-run",
+            "--wrapper-no-local",
+            "analyze",
+        ])
+        .env("PI_CLI_PATH", &override_cli)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        format!(
+            "source: PI_CLI_PATH override\nprogram: {}\narg: analyze\n",
+            override_cli.display()
+        )
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn dry_run_still_reports_resolution_failures_and_exits_nonzero() {
+    let root = test_root("failure");
+    let empty = root.join("empty");
+    std::fs::create_dir_all(&empty).unwrap();
+    // A boundary so the walk cannot find anything above the fixture
+    std::fs::write(empty.join("package.json"), "{}").unwrap();
+
+    let output = wrapper_command(&root, &empty)
+        .args(["--wrapper-dry-run", "analyze"])
+        .env("HOME", root.join("home").display().to_string())
+        .env("PATH", "/nonexistent")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("attempts:"),
+        "expected the aggregated failure list, got: {stderr}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,74 @@
+//! Integration tests: `PI_NODE_PATH` pins the Node.js interpreter the
+//! wrapper uses — use it or fail, never silently fall back.
+
+#![cfg(unix)]
+
+mod harness;
+
+use harness::{fake_executable, fake_node_script, recorded_args, test_root, wrapper};
+
+fn local_project(root: &std::path::Path) -> std::path::PathBuf {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let entry = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist")
+        .join("index.js");
+    fake_node_script(&entry, &root.join("unused.txt"), 0);
+    project
+}
+
+#[test]
+fn pi_node_path_selects_the_interpreter() {
+    let root = test_root("env-node-path");
+    let project = local_project(&root);
+    let marker = root.join("invoked.txt");
+    let pinned_node = root.join("bin").join("ci-node");
+    fake_executable(&pinned_node, &marker, 0);
+
+    let output = wrapper(&root, &project)
+        .env("PI_NODE_PATH", &pinned_node)
+        .env("PI_JS_RUNTIME", "node")
+        .env("PI_WRAPPER_SKIP_NODE_CHECK", "1")
+        .args(["analyze"])
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let args = recorded_args(&marker);
+    assert!(args.iter().any(|arg| arg.ends_with("index.js")));
+    assert!(args.iter().any(|arg| arg == "analyze"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_missing_pi_node_path_fails_instead_of_falling_back() {
+    let root = test_root("env-node-path-missing");
+    let project = local_project(&root);
+
+    let output = wrapper(&root, &project)
+        .env("PI_NODE_PATH", root.join("no-such-node"))
+        .env("PI_JS_RUNTIME", "node")
+        .env("PI_WRAPPER_SKIP_NODE_CHECK", "1")
+        .args(["analyze"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("PI_NODE_PATH points to") && stderr.contains("does not exist"),
+        "stderr: {stderr}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,65 @@
+//! Integration test: on Unix the wrapper execs into the resolved CLI
+//! by default — history gets its entry before the replacement — and
+//! `PI_WRAPPER_NO_EXEC=1` (or a mode that must outlive the child, like
+//! `--wrapper-timeout`) spawns and waits instead.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::process::{Command, Stdio};
+
+fn pid_printing_script(tag: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "pi-wrapper-exec-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("print-pid.sh");
+    fs::write(&script, "#!/bin/sh\necho $$\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+/// Runs the wrapper against a script that prints its own PID and returns
+/// (wrapper PID, PID the script reported).
+fn wrapper_and_script_pids(script: &std::path::Path, extra_env: &[(&str, &str)]) -> (u32, u32) {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"));
+    command
+        .arg("run")
+        .env("PI_CLI_PATH", script)
+        .env("PI_NO_UPDATE_CHECK", "1")
+        .stdout(Stdio::piped());
+    for (key, value) in extra_env {
+        command.env(key, value);
+    }
+    let mut child = command.spawn().unwrap();
+    let wrapper_pid = child.id();
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)
+        .unwrap();
+    child.wait().unwrap();
+    (wrapper_pid, stdout.trim().parse().unwrap())
+}
+
+#[test]
+fn exec_replaces_the_wrapper_process_by_default() {
+    let script = pid_printing_script("exec");
+    // History recording is on by default and must not hold exec back
+    let (wrapper_pid, script_pid) = wrapper_and_script_pids(&script, &[]);
+    // With exec the script *is* the wrapper process
+    assert_eq!(script_pid, wrapper_pid);
+}
+
+#[test]
+fn no_exec_escape_hatch_spawns_a_separate_child() {
+    let script = pid_printing_script("noexec");
+    let (wrapper_pid, script_pid) = wrapper_and_script_pids(&script, &[("PI_WRAPPER_NO_EXEC", "1")]);
+    assert_ne!(script_pid, wrapper_pid);
+}
@@ -0,0 +1,49 @@
+//! Integration test: a bundled `pi` that lost its execute bit (the
+//! usual result of unzipping a release on Linux) is fixed up in place
+//! and runs, instead of failing with a bare "Permission denied".
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::os::unix::fs::PermissionsExt;
+
+use harness::{recorded_args, test_root, wrapper_at};
+
+#[test]
+fn a_bundled_executable_without_the_execute_bit_is_fixed_and_run() {
+    let root = test_root("execbit");
+    let bin_dir = root.join("release");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    let installed = bin_dir.join("pi");
+    std::fs::copy(env!("CARGO_BIN_EXE_package-installer-cli"), &installed).unwrap();
+    let marker = root.join("invoked.txt");
+    let bundled = bin_dir.join("bundle-standalone").join("pi");
+    harness::fake_executable(&bundled, &marker, 0);
+    // Simulate the unzip: the executable bit is gone
+    std::fs::set_permissions(&bundled, std::fs::Permissions::from_mode(0o644)).unwrap();
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let home = root.join("home");
+    std::fs::create_dir_all(&home).unwrap();
+
+    let output = wrapper_at(&installed, &root, &project)
+        .args(["create", "my-app"])
+        .env("HOME", &home)
+        .env("PATH", "/nonexistent")
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(recorded_args(&marker), ["create", "my-app"]);
+    let mode = std::fs::metadata(&bundled).unwrap().permissions().mode();
+    assert_ne!(mode & 0o111, 0, "the execute bit must be restored on disk");
+
+    std::fs::remove_dir_all(&root).ok();
+}
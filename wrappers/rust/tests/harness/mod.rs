@@ -0,0 +1,94 @@
+//! Shared harness for integration tests that simulate real
+//! installation layouts in temp directories: fake node entrypoints and
+//! fake standalone executables that record how they were invoked, plus
+//! a pre-hygienized wrapper `Command`. Nothing here needs npm or a
+//! real CLI install.
+
+// Each test binary compiles its own copy of this module and rarely
+// uses every helper.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A unique temp root for one test, named so stray failures are easy
+/// to attribute and clean up.
+pub fn test_root(tag: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pi-wrapper-layout-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+/// Writes a fake JS entrypoint at `entry` that records its argv (one
+/// argument per line) into `marker`, prints `OUT` to stdout and `ERR`
+/// to stderr, and exits with `exit_code`. Parent directories are
+/// created as needed.
+pub fn fake_node_script(entry: &Path, marker: &Path, exit_code: i32) {
+    std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+    std::fs::write(
+        entry,
+        format!(
+            "require('fs').writeFileSync({marker:?}, process.argv.slice(2).join('\\n'));\n\
+             console.log('OUT');\n\
+             console.error('ERR');\n\
+             process.exit({exit_code});\n",
+            marker = marker.display().to_string(),
+        ),
+    )
+    .unwrap();
+}
+
+/// Writes a fake standalone executable at `path` with the same
+/// contract as [`fake_node_script`]: argv into `marker`, `OUT` on
+/// stdout, `ERR` on stderr, exiting with `exit_code`.
+pub fn fake_executable(path: &Path, marker: &Path, exit_code: i32) {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        path,
+        format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$@\" > {}\necho OUT\necho ERR >&2\nexit {}\n",
+            marker.display(),
+            exit_code
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// The recorded argv from a marker file, one argument per entry.
+pub fn recorded_args(marker: &Path) -> Vec<String> {
+    std::fs::read_to_string(marker)
+        .unwrap_or_else(|_| panic!("stub never ran: no marker at {}", marker.display()))
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// A wrapper invocation running `binary` from `cwd`, hygienized so the
+/// host machine's CLI installs, cache and config cannot leak in.
+pub fn wrapper_at(binary: &Path, root: &Path, cwd: &Path) -> Command {
+    let mut command = Command::new(binary);
+    command
+        .current_dir(cwd)
+        .env_remove("PI_CLI_PATH")
+        .env_remove("PI_WRAPPER_NO_LOCAL")
+        .env("PI_NO_UPDATE_CHECK", "1")
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_CONFIG_HOME", root.join("config"))
+        .env("XDG_DATA_HOME", root.join("data"));
+    command
+}
+
+/// [`wrapper_at`] with the crate's own test binary.
+pub fn wrapper(root: &Path, cwd: &Path) -> Command {
+    wrapper_at(
+        Path::new(env!("CARGO_BIN_EXE_package-installer-cli")),
+        root,
+        cwd,
+    )
+}
@@ -0,0 +1,189 @@
+//! Integration tests: `pi wrapper install-node` downloads the portable
+//! Node.js runtime from a (mock) dist server, verifies it against
+//! SHASUMS256.txt, and installs it under `$PI_HOME/node/<version>/`;
+//! with `PI_WRAPPER_AUTO_NODE=1` the runtime steps in when the system
+//! has no node at all.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use harness::{test_root, wrapper};
+use sha2::{Digest, Sha256};
+
+/// The platform suffix nodejs.org would use for this host.
+fn platform() -> String {
+    let os = match std::env::consts::OS {
+        "linux" => "linux",
+        "macos" => "darwin",
+        other => other,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    format!("{}-{}", os, arch)
+}
+
+/// Builds an official-style archive whose `bin/node` is a stub that
+/// prints `PRIVATE_NODE`, returning its bytes and sha256.
+fn fixture_archive(root: &Path) -> (Vec<u8>, String) {
+    let top = format!("node-v1.2.3-{}", platform());
+    let bin = root.join("stage").join(&top).join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let node = bin.join("node");
+    std::fs::write(&node, "#!/bin/sh\necho PRIVATE_NODE\n").unwrap();
+    std::fs::set_permissions(&node, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let archive = root.join("fixture.tar.gz");
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(root.join("stage"))
+        .arg(&top)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let bytes = std::fs::read(&archive).unwrap();
+    let checksum = format!("{:x}", Sha256::digest(&bytes));
+    (bytes, checksum)
+}
+
+/// Serves the checksum file and the archive from one listener.
+fn start_mock_dist_server(archive: Vec<u8>, advertised_checksum: String) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let file = format!("node-v1.2.3-{}.tar.gz", platform());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = [0u8; 4096];
+            let Ok(read) = stream.read(&mut request) else { continue };
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+
+            let body: Vec<u8> = if request.starts_with("GET /v1.2.3/SHASUMS256.txt") {
+                format!("{}  {}\n", advertised_checksum, file).into_bytes()
+            } else {
+                archive.clone()
+            };
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(header.as_bytes());
+            let _ = stream.write_all(&body);
+        }
+    });
+
+    format!("http://127.0.0.1:{}", port)
+}
+
+/// A project whose config pins node_version 1.2.3, matching the fixture.
+fn pinned_project(root: &Path) -> PathBuf {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join(".pi-wrapper.toml"), "node_version = \"1.2.3\"\n").unwrap();
+    project
+}
+
+#[test]
+fn install_node_verifies_and_installs_under_pi_home() {
+    let root = test_root("nodeinstall-ok");
+    let (archive, checksum) = fixture_archive(&root);
+    let base = start_mock_dist_server(archive, checksum);
+    let project = pinned_project(&root);
+
+    let output = wrapper(&root, &project)
+        .args(["wrapper", "install-node"])
+        .env("PI_WRAPPER_NODE_BASE", &base)
+        .env("PI_HOME", root.join("pi-home"))
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "install failed: {stderr}");
+    assert!(stderr.contains("Installed Node.js v1.2.3"), "got: {stderr}");
+
+    let node = root.join("pi-home").join("node").join("v1.2.3").join("bin").join("node");
+    assert!(node.exists());
+    // Scratch and download files are cleaned up
+    let leftovers: Vec<_> = std::fs::read_dir(root.join("pi-home").join("node"))
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(".pi."))
+        .collect();
+    assert!(leftovers.is_empty(), "leftovers: {leftovers:?}");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_tampered_download_is_rejected_and_nothing_is_installed() {
+    let root = test_root("nodeinstall-tamper");
+    let (archive, _) = fixture_archive(&root);
+    let base = start_mock_dist_server(archive, "0".repeat(64));
+    let project = pinned_project(&root);
+
+    let output = wrapper(&root, &project)
+        .args(["wrapper", "install-node"])
+        .env("PI_WRAPPER_NODE_BASE", &base)
+        .env("PI_HOME", root.join("pi-home"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Checksum mismatch"), "got: {stderr}");
+    assert!(!root.join("pi-home").join("node").join("v1.2.3").exists());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn auto_node_rescues_a_system_without_any_runtime() {
+    let root = test_root("nodeinstall-auto");
+    let (archive, checksum) = fixture_archive(&root);
+    let base = start_mock_dist_server(archive, checksum);
+    let project = pinned_project(&root);
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    // The local CLI entrypoint only needs to exist; the stub "node"
+    // from the fixture prints PRIVATE_NODE instead of running it
+    let dist = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    std::fs::create_dir_all(&dist).unwrap();
+    std::fs::write(dist.join("index.js"), "// never parsed\n").unwrap();
+    // A PATH with tar but no node/bun/deno
+    let bindir = root.join("bin");
+    std::fs::create_dir_all(&bindir).unwrap();
+    for tool in ["tar", "gzip"] {
+        for candidate in [format!("/usr/bin/{tool}"), format!("/bin/{tool}")] {
+            if Path::new(&candidate).exists() {
+                std::os::unix::fs::symlink(&candidate, bindir.join(tool)).ok();
+                break;
+            }
+        }
+    }
+
+    let output = wrapper(&root, &project)
+        .arg("analyze")
+        .env("PATH", &bindir)
+        .env("PI_WRAPPER_NODE_BASE", &base)
+        .env("PI_HOME", root.join("pi-home"))
+        .env("PI_WRAPPER_AUTO_NODE", "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(0), "stderr: {stderr}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("PRIVATE_NODE"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
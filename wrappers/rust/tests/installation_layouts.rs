@@ -0,0 +1,120 @@
+//! Integration tests: the three real installation layouts (local
+//! project, nested monorepo package, bundled standalone next to the
+//! binary) each execute the right target with the right argv, exit
+//! code and stream separation — and an empty directory fails with the
+//! aggregated error. Built on the shared harness in `tests/harness/`.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+
+use harness::{fake_executable, fake_node_script, recorded_args, test_root, wrapper, wrapper_at};
+
+/// The local npm entrypoint location under a project directory.
+fn local_entry(project: &Path) -> PathBuf {
+    project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist")
+        .join("index.js")
+}
+
+#[test]
+fn local_project_runs_the_node_entry_with_argv_and_exit_code() {
+    let root = test_root("local");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let marker = root.join("invoked.txt");
+    fake_node_script(&local_entry(&project), &marker, 7);
+
+    let output = wrapper(&root, &project)
+        .args(["analyze", "--verbose"])
+        .output()
+        .unwrap();
+
+    assert_eq!(recorded_args(&marker), ["analyze", "--verbose"]);
+    assert_eq!(output.status.code(), Some(7), "stub exit code must propagate");
+    // The stub's streams arrive unmixed
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "OUT\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("ERR"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn nested_monorepo_package_resolves_the_repo_root_install() {
+    let root = test_root("monorepo");
+    let repo = root.join("repo");
+    std::fs::create_dir_all(repo.join(".git")).unwrap();
+    let marker = root.join("invoked.txt");
+    fake_node_script(&local_entry(&repo), &marker, 0);
+    // Several levels below the repo root, with no install of its own
+    let package = repo.join("packages").join("tools").join("cli").join("src");
+    std::fs::create_dir_all(&package).unwrap();
+
+    let output = wrapper(&root, &package).arg("check").output().unwrap();
+
+    assert_eq!(recorded_args(&marker), ["check"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn bundled_standalone_next_to_a_copied_wrapper_binary_is_executed() {
+    let root = test_root("bundled");
+    // Install the wrapper binary itself into a fake release layout
+    let bin_dir = root.join("release");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    let installed = bin_dir.join("pi");
+    std::fs::copy(env!("CARGO_BIN_EXE_package-installer-cli"), &installed).unwrap();
+    let marker = root.join("invoked.txt");
+    fake_executable(&bin_dir.join("bundle-standalone").join("pi"), &marker, 0);
+    // Run from an empty project so only the bundle can win
+    let project = root.join("empty-project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+
+    let output = wrapper_at(&installed, &root, &project)
+        .args(["create", "my-app"])
+        .env("HOME", root.join("home").display().to_string())
+        .output()
+        .unwrap();
+
+    assert_eq!(recorded_args(&marker), ["create", "my-app"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "OUT\n");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn empty_directory_fails_with_the_aggregated_attempt_list() {
+    let root = test_root("empty");
+    let empty = root.join("nothing-here");
+    std::fs::create_dir_all(&empty).unwrap();
+    // A boundary so the walk cannot escape the fixture
+    std::fs::write(empty.join("package.json"), "{}").unwrap();
+
+    let output = wrapper(&root, &empty)
+        .arg("analyze")
+        .env("HOME", root.join("home").display().to_string())
+        .env("PATH", "/nonexistent")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("attempts:"),
+        "expected the aggregated failure list, got: {stderr}"
+    );
+    assert!(stderr.contains("local node_modules installation"));
+    assert!(stderr.contains("bundled standalone executable"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
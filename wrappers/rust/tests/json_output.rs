@@ -0,0 +1,131 @@
+//! Integration tests: `--wrapper-json` (and `PI_WRAPPER_OUTPUT=json`)
+//! turn wrapper-originated output into single-line JSON on stderr,
+//! while the child CLI's own streams stay untouched.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::{fake_node_script, test_root, wrapper};
+
+/// The single JSON object a command printed on stderr.
+fn stderr_json(output: &std::process::Output) -> serde_json::Value {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr.trim();
+    assert!(
+        !line.contains('\n'),
+        "expected exactly one JSON line, got: {stderr}"
+    );
+    serde_json::from_str(line).unwrap_or_else(|e| panic!("not JSON ({e}): {stderr}"))
+}
+
+fn project_with_local_cli(root: &Path) -> std::path::PathBuf {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    fake_node_script(
+        &project
+            .join("node_modules")
+            .join("@0xshariq")
+            .join("package-installer")
+            .join("dist")
+            .join("index.js"),
+        &root.join("args.txt"),
+        0,
+    );
+    project
+}
+
+#[test]
+fn resolution_failures_become_a_tagged_json_object() {
+    let root = test_root("json-resolution");
+    let empty = root.join("empty");
+    std::fs::create_dir_all(&empty).unwrap();
+
+    let output = wrapper(&root, &empty)
+        .env("PATH", "/nonexistent") // no global npm, no node
+        .args(["--wrapper-json", "--wrapper-non-interactive", "create", "app"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let message = stderr_json(&output);
+    assert_eq!(message["type"], "resolution_error");
+    assert!(message["message"].as_str().unwrap().contains("no CLI installation"));
+    assert!(
+        !message["attempts"].as_array().unwrap().is_empty(),
+        "got: {message}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn dry_run_reports_are_json_and_the_child_never_runs() {
+    let root = test_root("json-dry-run");
+    let project = project_with_local_cli(&root);
+
+    let output = wrapper(&root, &project)
+        .args(["--wrapper-json", "--wrapper-dry-run", "create", "app"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty(), "JSON goes to stderr only");
+    let message = stderr_json(&output);
+    assert_eq!(message["type"], "dry_run");
+    assert!(message["source"].as_str().unwrap().contains("local"));
+    let args: Vec<&str> = message["args"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|arg| arg.as_str().unwrap())
+        .collect();
+    assert!(args.contains(&"create") && args.contains(&"app"), "got: {args:?}");
+    assert!(!root.join("args.txt").exists(), "dry run must not execute");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_env_switch_covers_version_info() {
+    let root = test_root("json-version");
+    let project = project_with_local_cli(&root);
+
+    let output = wrapper(&root, &project)
+        .env("PI_WRAPPER_OUTPUT", "json")
+        .arg("--wrapper-version")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    let message = stderr_json(&output);
+    assert_eq!(message["type"], "version");
+    assert_eq!(message["wrapper_version"], env!("CARGO_PKG_VERSION"));
+    assert!(
+        message["cli_path"].as_str().unwrap().ends_with("index.js"),
+        "got: {message}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn child_cli_streams_are_untouched() {
+    let root = test_root("json-child");
+    let project = project_with_local_cli(&root);
+
+    let output = wrapper(&root, &project)
+        .args(["--wrapper-json", "analyze"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    // The fake CLI prints OUT/ERR; the wrapper adds nothing
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "OUT");
+    assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "ERR");
+
+    std::fs::remove_dir_all(&root).ok();
+}
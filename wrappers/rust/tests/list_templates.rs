@@ -0,0 +1,96 @@
+//! Integration tests: `pi list-templates` is served from the embedded
+//! catalog when no CLI resolves, and `--native` forces the embedded
+//! listing even when one does.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::{fake_node_script, recorded_args, test_root, wrapper};
+
+fn empty_dir(root: &Path) -> std::path::PathBuf {
+    let dir = root.join("empty");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn with_no_cli_the_embedded_catalog_answers() {
+    let root = test_root("templates-offline");
+    let cwd = empty_dir(&root);
+
+    let output = wrapper(&root, &cwd)
+        .env("PATH", "/nonexistent")
+        .args(["--wrapper-non-interactive", "list-templates", "--language", "rust"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("rust:"), "got: {stdout}");
+    assert!(stdout.contains("axum") && stdout.contains("rocket"), "got: {stdout}");
+    assert!(!stdout.contains("python:"), "filter must narrow the groups");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn json_output_is_an_array_of_catalog_entries() {
+    let root = test_root("templates-json");
+    let cwd = empty_dir(&root);
+
+    let output = wrapper(&root, &cwd)
+        .env("PATH", "/nonexistent")
+        .args(["--wrapper-non-interactive", "templates", "--json", "--language", "rust"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    let entries: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout must be JSON");
+    let entries = entries.as_array().unwrap();
+    assert!(!entries.is_empty());
+    for entry in entries {
+        assert_eq!(entry["language"], "rust");
+        assert!(entry["path"].as_str().unwrap().starts_with("templates/rust/"));
+        assert!(!entry["description"].as_str().unwrap().is_empty());
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn native_forces_the_embedded_listing_past_an_installed_cli() {
+    let root = test_root("templates-native");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let marker = root.join("args.txt");
+    fake_node_script(
+        &project
+            .join("node_modules")
+            .join("@0xshariq")
+            .join("package-installer")
+            .join("dist")
+            .join("index.js"),
+        &marker,
+        0,
+    );
+
+    let output = wrapper(&root, &project)
+        .args(["list-templates", "--native"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("rust:"));
+    assert!(!marker.exists(), "--native must not launch the CLI");
+
+    // Without --native the installed CLI handles it
+    let status = wrapper(&root, &project).arg("list-templates").status().unwrap();
+    assert!(status.success());
+    assert_eq!(recorded_args(&marker), ["list-templates"]);
+
+    std::fs::remove_dir_all(&root).ok();
+}
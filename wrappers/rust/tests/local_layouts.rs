@@ -0,0 +1,145 @@
+//! Integration tests: local installations that do not expose
+//! `node_modules/@0xshariq/...` directly — a bare `node_modules/.bin`
+//! shim, and a Yarn Plug'n'Play project with no node_modules at all.
+
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn test_root(tag: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pi-wrapper-layout-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn write_executable(path: &Path, contents: &str) {
+    std::fs::write(path, contents).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn wrapper_command(root: &Path, project: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"));
+    command
+        .current_dir(project)
+        .env_remove("PI_CLI_PATH")
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_DATA_HOME", root.join("data"));
+    command
+}
+
+#[test]
+fn a_bare_bin_shim_is_found_and_executed() {
+    let root = test_root("shim");
+    let project = root.join("project");
+    let bin = project.join("node_modules").join(".bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    // Project boundary, so the walk never leaves the fixture
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    write_executable(&bin.join("pi"), "#!/bin/sh\necho BIN_SHIM\n");
+
+    let output = wrapper_command(&root, &project)
+        .arg("analyze")
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "BIN_SHIM\n");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn wrapper_no_local_bypasses_a_present_local_install() {
+    let root = test_root("nolocal");
+    let project = root.join("project");
+    let package = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    std::fs::create_dir_all(&package).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    std::fs::write(package.join("index.js"), "console.log('LOCAL_CLI');\n").unwrap();
+    // Bundled CLI in the per-user data directory
+    let bundle = root
+        .join("data")
+        .join("package-installer")
+        .join("bundle-standalone");
+    std::fs::create_dir_all(&bundle).unwrap();
+    write_executable(&bundle.join("pi"), "#!/bin/sh\necho BUNDLED_CLI\n");
+
+    let with_local = wrapper_command(&root, &project)
+        .arg("analyze")
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&with_local.stdout), "LOCAL_CLI\n");
+
+    let without_local = wrapper_command(&root, &project)
+        .args(["--wrapper-no-local", "analyze"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&without_local.stdout),
+        "BUNDLED_CLI\n"
+    );
+
+    // The env var form works the same way
+    let via_env = wrapper_command(&root, &project)
+        .arg("analyze")
+        .env("PI_WRAPPER_NO_LOCAL", "1")
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&via_env.stdout), "BUNDLED_CLI\n");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_yarn_pnp_project_runs_under_node_with_the_manifest_required() {
+    let root = test_root("pnp");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    // The manifest sets a marker the entry checks, proving --require ran
+    std::fs::write(
+        project.join(".pnp.cjs"),
+        "globalThis.__pnp_loaded = true;\n",
+    )
+    .unwrap();
+    let entry = project.join("entry.js");
+    std::fs::write(
+        &entry,
+        "console.log(globalThis.__pnp_loaded ? 'PNP_CLI' : 'PNP_NOT_LOADED');\n",
+    )
+    .unwrap();
+    // Fake `yarn bin pi` answering with the entry path
+    let fake_bin = root.join("fake-bin");
+    std::fs::create_dir_all(&fake_bin).unwrap();
+    write_executable(
+        &fake_bin.join("yarn"),
+        &format!("#!/bin/sh\necho {}\n", entry.display()),
+    );
+    let path = format!(
+        "{}:{}",
+        fake_bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let output = wrapper_command(&root, &project)
+        .arg("analyze")
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "PNP_CLI\n",
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,63 @@
+//! Stress test: many wrapper processes hammering the same cache and
+//! `PI_HOME` concurrently must never corrupt the shared state. Each
+//! shard resolves a local install (writing its cache entry) from its
+//! own working directory; afterwards the cache file has to be valid
+//! JSON containing every shard's entry.
+
+#![cfg(unix)]
+
+mod harness;
+
+use harness::{fake_executable, test_root, wrapper};
+
+const SHARDS: usize = 8;
+
+#[test]
+fn parallel_shards_do_not_tear_the_shared_cache() {
+    let root = test_root("lock-stress");
+
+    let mut children = Vec::new();
+    for shard in 0..SHARDS {
+        let project = root.join(format!("shard-{shard}"));
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("package.json"), "{}").unwrap();
+        let marker = root.join(format!("invoked-{shard}.txt"));
+        fake_executable(
+            &project.join("node_modules").join(".bin").join("pi"),
+            &marker,
+            0,
+        );
+        let child = wrapper(&root, &project)
+            .env("PI_HOME", root.join("pi-home"))
+            .arg("analyze")
+            .spawn()
+            .unwrap();
+        children.push(child);
+    }
+
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let cache_file = root
+        .join("cache")
+        .join("package-installer")
+        .join("resolved.json");
+    let contents = std::fs::read_to_string(&cache_file)
+        .unwrap_or_else(|_| panic!("no cache written at {}", cache_file.display()));
+    let parsed: serde_json::Value =
+        serde_json::from_str(&contents).expect("cache must stay valid JSON under contention");
+    let entries = parsed
+        .get("entries")
+        .and_then(|entries| entries.as_object())
+        .expect("cache keeps its entries map");
+    for shard in 0..SHARDS {
+        let dir = root.join(format!("shard-{shard}")).display().to_string();
+        assert!(
+            entries.contains_key(&dir),
+            "shard {shard} entry missing from {contents}"
+        );
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
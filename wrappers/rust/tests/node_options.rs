@@ -0,0 +1,61 @@
+//! Integration test: `PI_NODE_OPTIONS` flags land between the node
+//! binary and the script path, in order, with quoted values intact.
+//! The node binary is replaced by a shell stub (via the `NODE`
+//! override) so the full command line is observable.
+
+#![cfg(unix)]
+
+mod harness;
+
+use harness::{fake_executable, fake_node_script, recorded_args, test_root, wrapper};
+
+#[test]
+fn pi_node_options_are_inserted_before_the_script_in_order() {
+    let root = test_root("node-options");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let entry = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist")
+        .join("index.js");
+    fake_node_script(&entry, &root.join("unused.txt"), 0);
+    // The "node" the wrapper runs is a stub recording its own argv
+    let marker = root.join("invoked.txt");
+    let fake_node = root.join("bin").join("node");
+    fake_executable(&fake_node, &marker, 0);
+
+    let output = wrapper(&root, &project)
+        .env("NODE", &fake_node)
+        .env("PI_JS_RUNTIME", "node")
+        .env("PI_WRAPPER_SKIP_NODE_CHECK", "1")
+        .env(
+            "PI_NODE_OPTIONS",
+            "--inspect-brk --title='my pi' --max-old-space-size=512",
+        )
+        .args(["analyze", "--verbose"])
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        recorded_args(&marker),
+        [
+            "--inspect-brk",
+            "--title=my pi",
+            "--max-old-space-size=512",
+            &entry.display().to_string(),
+            "analyze",
+            "--verbose",
+        ]
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,63 @@
+//! Integration tests: arguments that are not valid UTF-8 must reach
+//! the resolved CLI byte-for-byte instead of panicking in the wrapper.
+//! Uses shell stubs (via the shared harness) because `printf '%s' "$@"`
+//! preserves raw bytes exactly.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStringExt;
+
+use harness::{fake_executable, test_root, wrapper};
+
+/// `./donn<E9>es` — Latin-1 "données", invalid as UTF-8.
+fn latin1_arg() -> OsString {
+    OsString::from_vec(b"./donn\xE9es".to_vec())
+}
+
+#[test]
+fn non_utf8_argument_reaches_an_overridden_cli_byte_for_byte() {
+    let root = test_root("non-utf8-override");
+    let marker = root.join("invoked.txt");
+    let stub = root.join("stub").join("pi");
+    fake_executable(&stub, &marker, 0);
+
+    let output = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .args(["analyze", "--path"])
+        .arg(latin1_arg())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        std::fs::read(&marker).unwrap(),
+        b"analyze\n--path\n./donn\xE9es\n",
+        "argument bytes must survive the wrapper unchanged"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn non_utf8_argument_survives_normal_resolution_through_a_bin_shim() {
+    let root = test_root("non-utf8-shim");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let marker = root.join("invoked.txt");
+    // No dist/index.js, so resolution falls through to the .bin shim
+    fake_executable(&project.join("node_modules").join(".bin").join("pi"), &marker, 3);
+
+    let output = wrapper(&root, &project)
+        .arg(latin1_arg())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(std::fs::read(&marker).unwrap(), b"./donn\xE9es\n");
+
+    std::fs::remove_dir_all(&root).ok();
+}
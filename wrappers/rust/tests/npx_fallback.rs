@@ -0,0 +1,33 @@
+//! Integration test for the opt-in npx fallback. Downloads the real
+//! package from the npm registry, so it only runs with
+//! `cargo test --features network-tests`.
+
+#![cfg(all(unix, feature = "network-tests"))]
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn npx_fallback_runs_the_published_cli_when_nothing_is_installed() {
+    // An empty directory guarantees no local installation is found
+    let dir = std::env::temp_dir().join(format!("pi-wrapper-npx-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .args(["--wrapper-allow-npx", "--version"])
+        .current_dir(&dir)
+        .env_remove("PI_CLI_PATH")
+        .env("PI_WRAPPER_QUIET", "0")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("falling back to npx"),
+        "expected the npx warning, got: {stderr}"
+    );
+    assert!(output.status.success(), "npx fallback failed: {stderr}");
+    assert!(!output.stdout.is_empty());
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,124 @@
+//! Integration tests: with no CLI resolvable, `pi create` scaffolds
+//! the embedded Rust templates natively. Every template must come out
+//! as a manifest `cargo metadata` can parse.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use harness::{test_root, wrapper};
+
+/// Frameworks the offline fallback promises.
+const FRAMEWORKS: &[&str] = &[
+    "actix-web", "axum", "bevy", "gotham", "poem", "rocket", "rouille", "salvo", "thruster",
+    "tide", "warp",
+];
+
+/// A wrapper command guaranteed to fail resolution (no node, no npm,
+/// no bundle on an empty PATH in an empty directory).
+fn offline_wrapper(root: &Path, cwd: &Path) -> Command {
+    let mut command = wrapper(root, cwd);
+    command
+        .env("PATH", "/nonexistent")
+        .arg("--wrapper-non-interactive");
+    command
+}
+
+fn scaffold(root: &Path, cwd: &Path, name: &str, framework: &str) -> std::process::Output {
+    offline_wrapper(root, cwd)
+        .args(["create", name, "--template", &format!("rust/{framework}")])
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn every_embedded_template_scaffolds_into_a_parsable_crate() {
+    let root = test_root("offline-create");
+    let workspace = root.join("projects");
+    std::fs::create_dir_all(&workspace).unwrap();
+
+    for framework in FRAMEWORKS {
+        let name = format!("app-{}", framework);
+        let output = scaffold(&root, &workspace, &name, framework);
+        assert_eq!(
+            output.status.code(),
+            Some(0),
+            "{framework}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("Offline fallback"),
+            "{framework} must announce the fallback, got: {stderr}"
+        );
+
+        let project = workspace.join(&name);
+        let manifest = project.join("Cargo.toml");
+        let contents = std::fs::read_to_string(&manifest).unwrap();
+        assert!(
+            contents.contains(&format!("name = \"{}\"", name)),
+            "{framework}: package name not rewritten: {contents}"
+        );
+        assert!(project.join("src").join("main.rs").exists());
+
+        let metadata = Command::new("cargo")
+            .args(["metadata", "--no-deps", "--format-version", "1"])
+            .arg("--manifest-path")
+            .arg(&manifest)
+            .output()
+            .unwrap();
+        assert!(
+            metadata.status.success(),
+            "{framework}: cargo metadata failed: {}",
+            String::from_utf8_lossy(&metadata.stderr)
+        );
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn non_empty_targets_are_refused_without_force() {
+    let root = test_root("offline-create-force");
+    let workspace = root.join("projects");
+    let occupied: PathBuf = workspace.join("taken");
+    std::fs::create_dir_all(&occupied).unwrap();
+    std::fs::write(occupied.join("precious.txt"), "keep me").unwrap();
+
+    let output = scaffold(&root, &workspace, "taken", "axum");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--force"), "got: {stderr}");
+    assert!(!occupied.join("Cargo.toml").exists());
+
+    let output = offline_wrapper(&root, &workspace)
+        .args(["create", "taken", "--template", "rust/axum", "--force"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(occupied.join("Cargo.toml").exists());
+    assert!(occupied.join("precious.txt").exists(), "--force scaffolds into, not over");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn an_unusable_template_still_surfaces_the_resolution_error_with_a_hint() {
+    let root = test_root("offline-create-hint");
+    let workspace = root.join("projects");
+    std::fs::create_dir_all(&workspace).unwrap();
+
+    let output = offline_wrapper(&root, &workspace)
+        .args(["create", "my-app"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("rust/<framework>"), "got: {stderr}");
+    assert!(stderr.contains("Failed to execute the CLI"), "got: {stderr}");
+
+    std::fs::remove_dir_all(&root).ok();
+}
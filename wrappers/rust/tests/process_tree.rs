@@ -0,0 +1,109 @@
+//! Integration tests: on the spawn-and-wait paths (an exec'd run has
+//! no wrapper left to manage anything) the child runs in its own
+//! process group, so killing the wrapper takes the whole tree —
+//! including grandchildren the CLI spawned — down with it.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use harness::{test_root, wrapper};
+
+/// A stub CLI that spawns a long-lived grandchild, records both PIDs,
+/// and then blocks.
+fn nested_stub(path: &Path, pid_dir: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        path,
+        format!(
+            "#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then echo 3.0.0; exit 0; fi\nsleep 300 &\necho $! > {dir}/grandchild.pid\necho $$ > {dir}/child.pid\nexec sleep 300\n",
+            dir = pid_dir.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// Waits for a PID file to appear and returns its contents.
+fn wait_for_pid(file: &Path) -> i32 {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if let Ok(contents) = std::fs::read_to_string(file) {
+            if let Ok(pid) = contents.trim().parse() {
+                return pid;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(25));
+    }
+    panic!("stub never wrote {}", file.display());
+}
+
+fn alive(pid: i32) -> bool {
+    unsafe { libc::kill(pid, 0) == 0 }
+}
+
+/// Polls until `pid` is gone, failing after a grace window.
+fn assert_dies(pid: i32, what: &str) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if !alive(pid) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    unsafe { libc::kill(pid, libc::SIGKILL) }; // don't leak it past the test
+    panic!("{what} (pid {pid}) survived the wrapper's death");
+}
+
+#[test]
+fn terminating_the_wrapper_kills_the_grandchild_too() {
+    let root = test_root("tree-term");
+    let stub = root.join("cli.sh");
+    nested_stub(&stub, &root);
+
+    let mut child = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .env("PI_WRAPPER_NO_EXEC", "1")
+        .arg("create")
+        .spawn()
+        .unwrap();
+    let grandchild = wait_for_pid(&root.join("grandchild.pid"));
+    assert!(alive(grandchild));
+
+    unsafe { libc::kill(child.id() as i32, libc::SIGTERM) };
+    child.wait().unwrap();
+    assert_dies(grandchild, "grandchild");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn sigkilling_the_wrapper_still_takes_the_direct_child_down() {
+    let root = test_root("tree-kill");
+    let stub = root.join("cli.sh");
+    nested_stub(&stub, &root);
+
+    let mut child = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .env("PI_WRAPPER_NO_EXEC", "1")
+        .arg("create")
+        .spawn()
+        .unwrap();
+    let direct = wait_for_pid(&root.join("child.pid"));
+    let grandchild = wait_for_pid(&root.join("grandchild.pid"));
+    assert!(alive(direct));
+
+    unsafe { libc::kill(child.id() as i32, libc::SIGKILL) };
+    child.wait().unwrap();
+    // PDEATHSIG covers the process the wrapper spawned directly
+    assert_dies(direct, "direct child");
+    // The orphaned grandchild is out of reach after SIGKILL; reap it
+    unsafe { libc::kill(grandchild, libc::SIGKILL) };
+
+    std::fs::remove_dir_all(&root).ok();
+}
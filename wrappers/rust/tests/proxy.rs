@@ -0,0 +1,136 @@
+//! Integration tests: wrapper HTTP goes through the proxy named by
+//! the standard environment — CONNECT for https targets, absolute-form
+//! requests for http ones — and `NO_PROXY` bypasses it. Exercised via
+//! the update-check refresh, the lightest networked code path.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::mpsc;
+
+use harness::{test_root, wrapper};
+
+/// A stub proxy that records the first request line it receives and
+/// then drops the connection.
+fn stub_proxy() -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut buffer = [0u8; 2048];
+            let read = stream.read(&mut buffer).unwrap_or(0);
+            let request = String::from_utf8_lossy(&buffer[..read]);
+            let line = request.lines().next().unwrap_or("").to_string();
+            let _ = sender.send(line);
+        }
+    });
+    (address, receiver)
+}
+
+fn refresh(root: &Path) -> std::process::Command {
+    let mut command = wrapper(root, root);
+    command.env("PI_WRAPPER_INTERNAL_UPDATE_CHECK", "1");
+    command
+}
+
+#[test]
+fn https_requests_connect_through_the_proxy() {
+    let root = test_root("proxy-connect");
+    let (proxy, request_line) = stub_proxy();
+
+    let status = refresh(&root)
+        .env("PI_WRAPPER_REGISTRY_BASE", "https://registry.invalid")
+        .env("HTTPS_PROXY", format!("http://user:pass@{proxy}"))
+        .status()
+        .unwrap();
+
+    // The refresh swallows the failed fetch, but the CONNECT must have
+    // reached the proxy
+    assert!(status.success());
+    let line = request_line
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .expect("the proxy never saw a request");
+    assert!(
+        line.starts_with("CONNECT registry.invalid:443"),
+        "got request line: {line}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_config_proxy_key_applies_when_the_environment_sets_none() {
+    let root = test_root("proxy-config");
+    let (proxy, request_line) = stub_proxy();
+    let config_dir = root.join("config").join("package-installer");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("wrapper.toml"),
+        format!("proxy = \"http://user:pass@{proxy}\"\n"),
+    )
+    .unwrap();
+
+    let status = refresh(&root)
+        .env("PI_WRAPPER_REGISTRY_BASE", "https://registry.invalid")
+        .env_remove("HTTPS_PROXY")
+        .env_remove("https_proxy")
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    let line = request_line
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .expect("the proxy never saw a request");
+    assert!(
+        line.starts_with("CONNECT registry.invalid:443"),
+        "got request line: {line}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn no_proxy_bypasses_the_proxy_for_matching_hosts() {
+    let root = test_root("proxy-bypass");
+    // The registry itself answers directly; the proxy would be a dead end
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut buffer = [0u8; 2048];
+            let _ = stream.read(&mut buffer);
+            let body = r#"{"version":"99.9.9"}"#;
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+
+    let status = refresh(&root)
+        .env("PI_WRAPPER_REGISTRY_BASE", &base)
+        .env("HTTP_PROXY", "http://127.0.0.1:1") // nothing listens here
+        .env("NO_PROXY", "example.com,127.0.0.1")
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    let state = root
+        .join("cache")
+        .join("package-installer")
+        .join("update-check.json");
+    let contents = std::fs::read_to_string(&state).expect("state file written");
+    assert!(
+        contents.contains("99.9.9"),
+        "the bypassed request must reach the registry directly, got: {contents}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,57 @@
+//! Integration test: the second invocation from the same directory hits
+//! the resolution cache, and `pi wrapper clear-cache` empties it.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run(project: &Path, cache_home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .args(args)
+        .current_dir(project)
+        .env_remove("PI_CLI_PATH")
+        .env("XDG_CACHE_HOME", cache_home)
+        .env("PI_WRAPPER_DEBUG", "1")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn second_invocation_hits_the_cache_until_it_is_cleared() {
+    let root = std::env::temp_dir().join(format!("pi-wrapper-cache-int-{}", std::process::id()));
+    let dist = root
+        .join("project")
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    fs::create_dir_all(&dist).unwrap();
+    fs::write(dist.join("index.js"), "console.log('CHILD_OUT');\n").unwrap();
+    let project = root.join("project");
+    let cache_home = root.join("cache");
+
+    let first = run(&project, &cache_home, &["analyze"]);
+    assert!(String::from_utf8_lossy(&first.stderr).contains("cache miss"));
+    assert_eq!(String::from_utf8_lossy(&first.stdout), "CHILD_OUT\n");
+
+    let second = run(&project, &cache_home, &["analyze"]);
+    let stderr = String::from_utf8_lossy(&second.stderr);
+    assert!(stderr.contains("cache hit"), "expected a cache hit, got: {stderr}");
+    assert_eq!(String::from_utf8_lossy(&second.stdout), "CHILD_OUT\n");
+
+    // --wrapper-no-cache skips the cache even though an entry exists
+    let uncached = run(&project, &cache_home, &["--wrapper-no-cache", "analyze"]);
+    let stderr = String::from_utf8_lossy(&uncached.stderr);
+    assert!(!stderr.contains("cache hit"));
+    assert!(stderr.contains("winner:"));
+
+    let cleared = run(&project, &cache_home, &["wrapper", "clear-cache"]);
+    assert!(cleared.status.success());
+
+    let after_clear = run(&project, &cache_home, &["analyze"]);
+    assert!(String::from_utf8_lossy(&after_clear.stderr).contains("cache miss"));
+
+    fs::remove_dir_all(&root).ok();
+}
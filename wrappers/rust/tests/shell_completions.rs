@@ -0,0 +1,39 @@
+//! Integration tests: `pi completions <shell>` emits a completion
+//! script natively, without resolving or booting the Node CLI.
+
+#![cfg(unix)]
+
+mod harness;
+
+use harness::{test_root, wrapper};
+
+#[test]
+fn the_top_level_spelling_emits_a_script_without_any_cli_installed() {
+    let root = test_root("completions-toplevel");
+    let output = wrapper(&root, &root)
+        .args(["completions", "bash"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let script = String::from_utf8_lossy(&output.stdout);
+    for command in ["create", "analyze", "completions"] {
+        assert!(script.contains(command), "bash script misses {command}");
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn an_unknown_shell_fails_with_a_helpful_message() {
+    let root = test_root("completions-unknown");
+    let output = wrapper(&root, &root)
+        .args(["completions", "tcsh"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("tcsh"));
+    assert!(stderr.contains("bash"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,70 @@
+//! Integration test: terminal signals reach the child CLI and the
+//! wrapper reports `128 + signal`.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+#[test]
+fn sigint_terminates_wrapper_and_child_with_exit_code_130() {
+    let dir = std::env::temp_dir().join(format!("pi-wrapper-signal-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    // The script execs into sleep, so the PID it records is the PID of
+    // the long-running child the wrapper must forward the signal to.
+    let pid_file = dir.join("child.pid");
+    let script = dir.join("sleepy.sh");
+    fs::write(
+        &script,
+        format!("#!/bin/sh\necho $$ > {}\nexec sleep 30\n", pid_file.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut wrapper = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .arg("run")
+        .env("PI_CLI_PATH", &script)
+        // Exercise the spawn-and-wait path; the exec path has no second
+        // process to forward anything to
+        .env("PI_WRAPPER_NO_EXEC", "1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Wait until the child script has started and reported its PID
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let child_pid = loop {
+        if let Ok(contents) = fs::read_to_string(&pid_file) {
+            if let Ok(pid) = contents.trim().parse::<i32>() {
+                break pid;
+            }
+        }
+        assert!(Instant::now() < deadline, "child script never started");
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    unsafe {
+        libc::kill(wrapper.id() as i32, libc::SIGINT);
+    }
+
+    // The wrapper must exit promptly with 128 + SIGINT
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let status = loop {
+        if let Some(status) = wrapper.try_wait().unwrap() {
+            break status;
+        }
+        assert!(Instant::now() < deadline, "wrapper did not exit after SIGINT");
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    assert_eq!(status.code(), Some(130));
+
+    // ... and the child must be gone as well
+    let child_gone = unsafe { libc::kill(child_pid, 0) } == -1;
+    assert!(child_gone, "child process survived the forwarded SIGINT");
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,106 @@
+//! Integration tests: wrapper banners go to stderr (or nowhere when
+//! quiet), and stdout is a pure passthrough of the child CLI's output.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Creates a directory that looks like a project with a local npm
+/// install of the CLI; the fake `dist/index.js` echoes a marker plus the
+/// arguments it received.
+fn fake_local_install(tag: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "pi-wrapper-stream-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    let dist = dir
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist");
+    fs::create_dir_all(&dist).unwrap();
+    fs::write(
+        dist.join("index.js"),
+        "console.log(['CHILD_OUT'].concat(process.argv.slice(2)).join(' '));\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn run_wrapper(project_dir: &PathBuf, args: &[&str], env: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"));
+    command
+        .args(args)
+        .current_dir(project_dir)
+        .env_remove("PI_CLI_PATH")
+        .env_remove("PI_WRAPPER_QUIET");
+    for (key, value) in env {
+        command.env(key, value);
+    }
+    command.output().unwrap()
+}
+
+#[test]
+fn banner_goes_to_stderr_and_stdout_carries_only_child_output() {
+    let dir = fake_local_install("banner");
+    // PI_WRAPPER_QUIET=0 forces the banner on even though stderr is a pipe
+    let output = run_wrapper(&dir, &["analyze"], &[("PI_WRAPPER_QUIET", "0")]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stdout, "CHILD_OUT analyze\n");
+    assert!(
+        stderr.contains("locally installed CLI"),
+        "expected banner on stderr, got: {stderr}"
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn banners_are_auto_suppressed_when_stderr_is_not_a_tty() {
+    let dir = fake_local_install("notty");
+    let output = run_wrapper(&dir, &["analyze"], &[]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "CHILD_OUT analyze\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn wrapper_quiet_flag_suppresses_banners_and_is_not_forwarded() {
+    let dir = fake_local_install("flag");
+    // The flag must win even when the env var would force banners on
+    let output = run_wrapper(
+        &dir,
+        &["--wrapper-quiet", "analyze"],
+        &[("PI_WRAPPER_QUIET", "0")],
+    );
+
+    // The child must not see --wrapper-quiet
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "CHILD_OUT analyze\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn failure_and_usage_instructions_go_to_stderr() {
+    let dir = fake_local_install("failure");
+    let output = run_wrapper(
+        &dir,
+        &["analyze"],
+        &[("PI_CLI_PATH", "/does/not/exist/index.js")],
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    assert!(stderr.contains("Failed to execute the CLI"));
+    assert!(stderr.contains("CLI NOT FOUND"));
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,122 @@
+//! Integration tests: the update notifier against a mock registry —
+//! the background refresh records the latest version, a later run
+//! prints the single-line notice, and the kill switches silence it.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+
+use harness::{test_root, wrapper};
+
+/// Serves one HTTP response with the given JSON body and returns the
+/// base URL.
+fn mock_registry(body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+    base
+}
+
+/// A stub CLI that reports `version` to `--version` probes.
+fn versioned_stub(path: &Path, version: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        path,
+        format!(
+            "#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then echo {}; fi\nexit 0\n",
+            version
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn state_file(root: &Path) -> std::path::PathBuf {
+    root.join("cache")
+        .join("package-installer")
+        .join("update-check.json")
+}
+
+#[test]
+fn the_refresh_records_the_latest_version_and_a_later_run_announces_it() {
+    let root = test_root("update-notice");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    versioned_stub(&project.join("node_modules").join(".bin").join("pi"), "3.1.2");
+
+    // Phase 1: the background refresh against the mock registry
+    let base = mock_registry(r#"{"version":"99.4.0"}"#);
+    let status = wrapper(&root, &project)
+        .env("PI_WRAPPER_INTERNAL_UPDATE_CHECK", "1")
+        .env("PI_WRAPPER_REGISTRY_BASE", &base)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let state = std::fs::read_to_string(state_file(&root)).expect("state file written");
+    assert!(state.contains("99.4.0"), "got: {state}");
+
+    // Phase 2: a normal run sees the state and prints the notice
+    let output = wrapper(&root, &project)
+        .env_remove("PI_NO_UPDATE_CHECK")
+        .env_remove("CI")
+        .arg("analyze")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("A new version (99.4.0) of package-installer is available (you have 3.1.2)"),
+        "got: {stderr}"
+    );
+    assert!(stderr.contains("pi wrapper update"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn ci_and_the_kill_switch_silence_the_notice() {
+    let root = test_root("update-notice-off");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    versioned_stub(&project.join("node_modules").join(".bin").join("pi"), "3.1.2");
+    // Pre-seed a state that would normally trigger the notice
+    let state = state_file(&root);
+    std::fs::create_dir_all(state.parent().unwrap()).unwrap();
+    std::fs::write(&state, r#"{"checked_at":4102444800,"latest":"99.4.0"}"#).unwrap();
+
+    for (key, value) in [("CI", "1"), ("PI_NO_UPDATE_CHECK", "1")] {
+        let output = wrapper(&root, &project)
+            .env_remove("PI_NO_UPDATE_CHECK")
+            .env_remove("CI")
+            .env(key, value)
+            .arg("analyze")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(0));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("A new version"),
+            "{key}={value} must silence the notice, got: {stderr}"
+        );
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,85 @@
+//! Integration tests: `pi verify` re-checks cached bundles against
+//! their `manifest.json` without running anything.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use harness::{test_root, wrapper};
+use sha2::{Digest, Sha256};
+
+const BUNDLE_BODY: &str = "#!/bin/sh\necho CACHED_BUNDLE\n";
+
+/// A wrapper invocation from an empty project directory, so nothing
+/// but the seeded user bundle is resolvable.
+fn verify_wrapper(root: &Path) -> Command {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    wrapper(root, &project)
+}
+
+/// Installs a user bundle with a manifest matching `BUNDLE_BODY`.
+fn install_user_bundle(root: &Path) -> PathBuf {
+    let dir = root
+        .join("data")
+        .join("package-installer")
+        .join("bundle-standalone");
+    std::fs::create_dir_all(&dir).unwrap();
+    let pi = dir.join("pi");
+    std::fs::write(&pi, BUNDLE_BODY).unwrap();
+    let digest = format!("{:x}", Sha256::digest(BUNDLE_BODY.as_bytes()));
+    std::fs::write(
+        dir.join("manifest.json"),
+        format!(r#"{{"files": {{"pi": "{}"}}}}"#, digest),
+    )
+    .unwrap();
+    pi
+}
+
+#[test]
+fn verify_reports_an_intact_bundle_at_both_spellings() {
+    let root = test_root("verify-cmd-intact");
+    let pi = install_user_bundle(&root);
+
+    for spelling in [&["verify"][..], &["wrapper", "verify"][..]] {
+        let output = verify_wrapper(&root).args(spelling).output().unwrap();
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(&pi.display().to_string()), "stdout: {stdout}");
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn verify_fails_on_a_tampered_bundle() {
+    let root = test_root("verify-cmd-tampered");
+    let pi = install_user_bundle(&root);
+    std::fs::write(&pi, "#!/bin/sh\necho TAMPERED\n").unwrap();
+
+    let output = verify_wrapper(&root).arg("verify").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("checksum mismatch"), "stderr: {stderr}");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn verify_with_no_cached_bundle_points_at_wrapper_update() {
+    let root = test_root("verify-cmd-empty");
+
+    let output = verify_wrapper(&root).arg("verify").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pi wrapper update"), "stderr: {stderr}");
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,111 @@
+//! Integration tests: version pinning via `.pi-version` and
+//! `pi wrapper use` against versions installed under `$PI_HOME`.
+
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn test_root(tag: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pi-wrapper-pin-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+/// Installs a fake CLI as `version` under `home`, printing `marker`.
+fn install_version(home: &Path, version: &str, marker: &str) {
+    let dir = home.join("versions").join(version);
+    std::fs::create_dir_all(&dir).unwrap();
+    let pi = dir.join("pi");
+    std::fs::write(&pi, format!("#!/bin/sh\necho {}\n", marker)).unwrap();
+    std::fs::set_permissions(&pi, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn wrapper_command(root: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"));
+    command
+        .env_remove("PI_CLI_PATH")
+        .env("PI_HOME", root.join("pi-home"))
+        .env("XDG_CACHE_HOME", root.join("cache"));
+    command
+}
+
+#[test]
+fn a_project_pi_version_file_selects_the_pinned_version() {
+    let root = test_root("project");
+    let home = root.join("pi-home");
+    install_version(&home, "2.4.0", "VERSION_2_4_0");
+    install_version(&home, "2.5.0", "VERSION_2_5_0");
+
+    let project = root.join("repo").join("packages").join("app");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(root.join("repo").join(".pi-version"), "v2.4.0\n").unwrap();
+
+    let output = wrapper_command(&root)
+        .arg("analyze")
+        .current_dir(&project)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "VERSION_2_4_0\n");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn wrapper_use_selects_globally_and_list_marks_it_active() {
+    let root = test_root("use");
+    let home = root.join("pi-home");
+    install_version(&home, "2.5.0", "VERSION_2_5_0");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+
+    let selected = wrapper_command(&root)
+        .args(["wrapper", "use", "2.5.0"])
+        .current_dir(&project)
+        .output()
+        .unwrap();
+    assert!(
+        selected.status.success(),
+        "use failed: {}",
+        String::from_utf8_lossy(&selected.stderr)
+    );
+
+    let run = wrapper_command(&root)
+        .arg("analyze")
+        .current_dir(&project)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "VERSION_2_5_0\n");
+
+    let list = wrapper_command(&root)
+        .args(["wrapper", "list"])
+        .current_dir(&project)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&list.stdout);
+    assert!(stdout.contains("* 2.5.0"), "expected active marker: {stdout}");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn using_an_uninstalled_version_names_the_install_command() {
+    let root = test_root("missing");
+    let output = wrapper_command(&root)
+        .args(["wrapper", "use", "9.9.9"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("pi wrapper update --version v9.9.9"),
+        "expected install hint, got: {stderr}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,94 @@
+//! Integration tests: `--wrapper-capture` streams the child's output
+//! live while teeing labeled, timestamped copies of both streams into
+//! a log file, and prints the log path at exit.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::{test_root, wrapper};
+
+/// A project whose local CLI writes `ONE` and `TWO` to stdout, `THREE`
+/// to stderr, and exits with `exit_code`.
+fn project_with_noisy_cli(root: &Path, exit_code: i32) -> std::path::PathBuf {
+    let project = root.join("project");
+    let entry = project.join("node_modules/@0xshariq/package-installer/dist/index.js");
+    std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+    std::fs::write(
+        &entry,
+        format!(
+            "console.log('ONE');\n\
+             console.log('TWO');\n\
+             console.error('THREE');\n\
+             process.exit({exit_code});\n"
+        ),
+    )
+    .unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    project
+}
+
+#[test]
+fn both_streams_land_in_the_log_in_order_and_still_stream_live() {
+    let root = test_root("capture-log");
+    let project = project_with_noisy_cli(&root, 0);
+    let log = root.join("run.log");
+
+    let output = wrapper(&root, &project)
+        .arg(format!("--wrapper-capture={}", log.display()))
+        .arg("analyze")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(0), "stderr: {stderr}");
+
+    // Live output is untouched: stdout lines on stdout, stderr on stderr
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("ONE\n") && stdout.contains("TWO\n"), "got: {stdout}");
+    assert!(stderr.contains("THREE"), "got: {stderr}");
+    // ... and the exit banner names the log
+    assert!(stderr.contains(&log.display().to_string()), "got: {stderr}");
+
+    let logged = std::fs::read_to_string(&log).unwrap();
+    let one = logged.find("stdout] ONE").expect("ONE missing from log");
+    let two = logged.find("stdout] TWO").expect("TWO missing from log");
+    assert!(one < two, "stdout lines out of order:\n{logged}");
+    assert!(logged.contains("stderr] THREE"), "got:\n{logged}");
+    // Every log line is timestamped
+    for line in logged.lines() {
+        assert!(line.starts_with('['), "unlabeled line: {line:?}");
+        assert!(line.contains("s std"), "no timestamp in: {line:?}");
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_bare_capture_flag_defaults_to_a_pi_run_log_and_keeps_the_exit_code() {
+    let root = test_root("capture-default");
+    let project = project_with_noisy_cli(&root, 5);
+
+    let output = wrapper(&root, &project)
+        .args(["--wrapper-capture", "analyze"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(5));
+
+    let log = std::fs::read_dir(&project)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("pi-run-") && name.ends_with(".log"))
+        })
+        .expect("no default pi-run-*.log was written");
+    let logged = std::fs::read_to_string(&log).unwrap();
+    assert!(logged.contains("stdout] ONE"));
+    assert!(logged.contains("stderr] THREE"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
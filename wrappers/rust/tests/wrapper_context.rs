@@ -0,0 +1,116 @@
+//! Integration tests: the wrapper advertises itself to the child CLI
+//! through `PI_WRAPPER`, `PI_WRAPPER_VERSION` and
+//! `PI_WRAPPER_RESOLUTION`, and never clobbers a value the user
+//! already exported.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::{test_root, wrapper};
+
+/// A stub CLI that dumps the three context variables into `marker`.
+fn env_dump_stub(path: &Path, marker: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        path,
+        format!(
+            "#!/bin/sh\n{{\n\
+             echo \"PI_WRAPPER=$PI_WRAPPER\"\n\
+             echo \"PI_WRAPPER_VERSION=$PI_WRAPPER_VERSION\"\n\
+             echo \"PI_WRAPPER_RESOLUTION=$PI_WRAPPER_RESOLUTION\"\n\
+             }} > {}\nexit 0\n",
+            marker.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn dumped_env(marker: &Path) -> String {
+    std::fs::read_to_string(marker)
+        .unwrap_or_else(|_| panic!("stub never ran: no marker at {}", marker.display()))
+}
+
+#[test]
+fn local_resolution_exports_the_wrapper_context() {
+    let root = test_root("context-local");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    let marker = root.join("env.txt");
+    env_dump_stub(
+        &project.join("node_modules").join(".bin").join("pi"),
+        &marker,
+    );
+
+    let output = wrapper(&root, &project)
+        .env_remove("PI_WRAPPER")
+        .env_remove("PI_WRAPPER_VERSION")
+        .env_remove("PI_WRAPPER_RESOLUTION")
+        .arg("analyze")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    let env = dumped_env(&marker);
+    assert!(env.contains("PI_WRAPPER=1\n"), "got: {env}");
+    assert!(
+        env.contains(&format!("PI_WRAPPER_VERSION={}\n", env!("CARGO_PKG_VERSION"))),
+        "got: {env}"
+    );
+    assert!(env.contains("PI_WRAPPER_RESOLUTION=local-npm\n"), "got: {env}");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn an_env_override_reports_the_env_channel() {
+    let root = test_root("context-override");
+    let marker = root.join("env.txt");
+    let stub = root.join("stub").join("pi");
+    env_dump_stub(&stub, &marker);
+
+    let output = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .env_remove("PI_WRAPPER_RESOLUTION")
+        .arg("analyze")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(
+        dumped_env(&marker).contains("PI_WRAPPER_RESOLUTION=env\n"),
+        "got: {}",
+        dumped_env(&marker)
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn user_exported_values_are_not_clobbered() {
+    let root = test_root("context-user-wins");
+    let marker = root.join("env.txt");
+    let stub = root.join("stub").join("pi");
+    env_dump_stub(&stub, &marker);
+
+    let output = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .env("PI_WRAPPER_RESOLUTION", "user-set")
+        .arg("analyze")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(
+        dumped_env(&marker).contains("PI_WRAPPER_RESOLUTION=user-set\n"),
+        "got: {}",
+        dumped_env(&marker)
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,86 @@
+//! Integration tests: every run leaves a trace in the persistent
+//! `wrapper.log`, and `PI_WRAPPER_NO_LOG=1` opts out.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+
+use harness::{fake_node_script, test_root, wrapper};
+
+fn project_with_local_cli(root: &Path) -> PathBuf {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    fake_node_script(
+        &project
+            .join("node_modules")
+            .join("@0xshariq")
+            .join("package-installer")
+            .join("dist")
+            .join("index.js"),
+        &root.join("args.txt"),
+        0,
+    );
+    project
+}
+
+fn log_file(root: &Path) -> PathBuf {
+    root.join("cache").join("package-installer").join("wrapper.log")
+}
+
+#[test]
+fn runs_leave_timestamped_resolution_traces() {
+    let root = test_root("log-trace");
+    let project = project_with_local_cli(&root);
+
+    let status = wrapper(&root, &project).arg("analyze").status().unwrap();
+    assert!(status.success());
+
+    let contents = std::fs::read_to_string(log_file(&root)).expect("log written");
+    let first = contents.lines().next().unwrap();
+    // `<timestamp> [<pid>] <message>`
+    assert!(first.contains("T") && first.contains("Z ["), "got: {first}");
+    assert!(
+        contents.contains("node_modules"),
+        "resolution decisions must be traceable, got: {contents}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_opt_out_leaves_no_file_behind() {
+    let root = test_root("log-optout");
+    let project = project_with_local_cli(&root);
+
+    let status = wrapper(&root, &project)
+        .env("PI_WRAPPER_NO_LOG", "1")
+        .arg("analyze")
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(!log_file(&root).exists());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn doctor_names_the_log_file() {
+    let root = test_root("log-doctor");
+    let project = project_with_local_cli(&root);
+
+    let output = wrapper(&root, &project)
+        .args(["wrapper", "doctor"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("log file: {}", log_file(&root).display())),
+        "got: {stdout}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
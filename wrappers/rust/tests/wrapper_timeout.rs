@@ -0,0 +1,85 @@
+//! Integration tests: `--wrapper-timeout` (and `PI_WRAPPER_TIMEOUT`)
+//! kill a wedged CLI invocation with exit code 124 and a message, while
+//! well-behaved invocations are untouched.
+
+#![cfg(unix)]
+
+mod harness;
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use harness::{fake_executable, test_root, wrapper};
+
+/// A stub CLI that hangs until killed.
+fn hanging_stub(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    // `exec` so the TERM/KILL hits the sleep itself and no orphan
+    // keeps the captured output pipes open
+    std::fs::write(path, "#!/bin/sh\nexec sleep 600\n").unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn the_timeout_flag_kills_a_hung_invocation_with_exit_124() {
+    let root = test_root("timeout-flag");
+    let stub = root.join("stub").join("pi");
+    hanging_stub(&stub);
+
+    let start = Instant::now();
+    let output = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .args(["--wrapper-timeout", "1s", "analyze"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(124));
+    assert!(
+        start.elapsed() < Duration::from_secs(30),
+        "the wrapper must not wait out the full sleep"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("wrapper timeout"),
+        "expected a kill message, got: {stderr}"
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn the_timeout_env_variable_works_too() {
+    let root = test_root("timeout-env");
+    let stub = root.join("stub").join("pi");
+    hanging_stub(&stub);
+
+    let output = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .env("PI_WRAPPER_TIMEOUT", "1s")
+        .arg("analyze")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(124));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn a_fast_invocation_is_unaffected_by_the_timeout() {
+    let root = test_root("timeout-fast");
+    let marker = root.join("invoked.txt");
+    let stub = root.join("stub").join("pi");
+    fake_executable(&stub, &marker, 4);
+
+    let output = wrapper(&root, &root)
+        .env("PI_CLI_PATH", &stub)
+        .args(["--wrapper-timeout", "10m", "analyze"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(4));
+
+    std::fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,59 @@
+//! Integration tests: `--wrapper-timing` prints a per-stage breakdown
+//! on stderr after the child exits, and stays silent otherwise.
+
+#![cfg(unix)]
+
+mod harness;
+
+use harness::{fake_node_script, test_root, wrapper};
+use std::path::Path;
+
+fn project_with_cli(root: &Path) -> std::path::PathBuf {
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    fake_node_script(
+        &project
+            .join("node_modules")
+            .join("@0xshariq")
+            .join("package-installer")
+            .join("dist")
+            .join("index.js"),
+        &root.join("args.txt"),
+        0,
+    );
+    project
+}
+
+#[test]
+fn the_timing_flag_prints_every_stage_to_stderr() {
+    let root = test_root("timing-on");
+    let project = project_with_cli(&root);
+
+    let output = wrapper(&root, &project)
+        .args(["--wrapper-timing", "analyze"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("wrapper timing:"), "got: {stderr}");
+    for stage in ["resolution", "spawn", "child", "total"] {
+        assert!(stderr.contains(stage), "missing {stage} in: {stderr}");
+    }
+    // The child's own output stays on stdout, untouched
+    assert!(String::from_utf8_lossy(&output.stdout).contains("OUT"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn without_the_flag_no_breakdown_is_printed() {
+    let root = test_root("timing-off");
+    let project = project_with_cli(&root);
+
+    let output = wrapper(&root, &project).arg("analyze").output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("wrapper timing:"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
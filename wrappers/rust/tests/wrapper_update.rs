@@ -0,0 +1,202 @@
+//! Integration test: `pi wrapper update` downloads the platform asset
+//! from a (mock) releases API and installs it atomically into the
+//! per-user bundle directory, where the resolver then finds it.
+
+#![cfg(unix)]
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+
+const BUNDLE_BODY: &str = "#!/bin/sh\necho BUNDLED_CLI\n";
+
+/// Serves the releases API and the asset download from one listener.
+fn start_mock_release_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = [0u8; 4096];
+            let Ok(read) = stream.read(&mut request) else { continue };
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+
+            let body = if request.starts_with("GET /releases/latest") {
+                format!(
+                    r#"{{"tag_name": "v9.9.9", "assets": [
+                        {{"name": "pi-windows-x64.exe", "browser_download_url": "http://127.0.0.1:{port}/asset-win", "size": 1}},
+                        {{"name": "pi-{os}-{arch}", "browser_download_url": "http://127.0.0.1:{port}/asset", "size": {size}}}
+                    ]}}"#,
+                    port = port,
+                    os = std::env::consts::OS,
+                    arch = std::env::consts::ARCH,
+                    size = BUNDLE_BODY.len()
+                )
+            } else {
+                BUNDLE_BODY.to_string()
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    format!("http://127.0.0.1:{}", port)
+}
+
+#[test]
+fn update_installs_the_platform_asset_into_the_user_bundle_dir() {
+    let base = start_mock_release_server();
+    let root = std::env::temp_dir().join(format!("pi-wrapper-update-test-{}", std::process::id()));
+    let data_home = root.join("data");
+    let project = root.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+
+    let update = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .args(["wrapper", "update"])
+        .current_dir(&project)
+        .env("PI_WRAPPER_RELEASE_BASE", &base)
+        .env("XDG_DATA_HOME", &data_home)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&update.stderr);
+    assert!(update.status.success(), "update failed: {stderr}");
+    assert!(stderr.contains("v9.9.9"));
+
+    let installed = data_home
+        .join("package-installer")
+        .join("bundle-standalone")
+        .join("pi");
+    assert_eq!(std::fs::read_to_string(&installed).unwrap(), BUNDLE_BODY);
+    // No temp download file is left behind
+    let leftovers: Vec<_> = std::fs::read_dir(installed.parent().unwrap())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(".pi.download"))
+        .collect();
+    assert!(leftovers.is_empty());
+
+    // The resolver now finds and runs the downloaded bundle
+    let run = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .arg("analyze")
+        .current_dir(&project)
+        .env_remove("PI_CLI_PATH")
+        .env("XDG_DATA_HOME", &data_home)
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "BUNDLED_CLI\n");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+/// Like the plain mock, but the release also publishes a
+/// `manifest.json` asset carrying `digest` for the `pi` binary.
+fn start_mock_server_with_manifest(digest: String) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = [0u8; 4096];
+            let Ok(read) = stream.read(&mut request) else { continue };
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+
+            let body = if request.starts_with("GET /releases/latest") {
+                format!(
+                    r#"{{"tag_name": "v9.9.9", "assets": [
+                        {{"name": "pi-{os}-{arch}", "browser_download_url": "http://127.0.0.1:{port}/asset", "size": {size}}},
+                        {{"name": "manifest.json", "browser_download_url": "http://127.0.0.1:{port}/manifest", "size": 0}}
+                    ]}}"#,
+                    port = port,
+                    os = std::env::consts::OS,
+                    arch = std::env::consts::ARCH,
+                    size = BUNDLE_BODY.len()
+                )
+            } else if request.starts_with("GET /manifest") {
+                format!(r#"{{"files": {{"pi": "{}"}}}}"#, digest)
+            } else {
+                BUNDLE_BODY.to_string()
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    format!("http://127.0.0.1:{}", port)
+}
+
+#[test]
+fn update_enforces_a_published_checksum_manifest() {
+    use sha2::{Digest, Sha256};
+
+    let root = std::env::temp_dir().join(format!(
+        "pi-wrapper-update-manifest-test-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    let data_home = root.join("data");
+    let bundle_dir = data_home.join("package-installer").join("bundle-standalone");
+
+    // A wrong digest refuses the download and leaves nothing behind
+    let bad = start_mock_server_with_manifest("0".repeat(64));
+    let output = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .args(["wrapper", "update"])
+        .current_dir(&root)
+        .env("PI_WRAPPER_RELEASE_BASE", &bad)
+        .env("XDG_DATA_HOME", &data_home)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("failed verification"), "stderr: {stderr}");
+    assert!(!bundle_dir.join("pi").exists());
+
+    // The matching digest installs the binary and its manifest
+    let digest = format!("{:x}", Sha256::digest(BUNDLE_BODY.as_bytes()));
+    let good = start_mock_server_with_manifest(digest);
+    let output = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .args(["wrapper", "update"])
+        .current_dir(&root)
+        .env("PI_WRAPPER_RELEASE_BASE", &good)
+        .env("XDG_DATA_HOME", &data_home)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "update failed: {stderr}");
+    assert!(stderr.contains("Verified"), "stderr: {stderr}");
+    assert!(bundle_dir.join("manifest.json").exists());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn unknown_pinned_tag_produces_an_actionable_error() {
+    let base = start_mock_release_server();
+    // The mock answers every non-latest path with the asset body, which
+    // is not JSON — so point at a closed port instead for a clean
+    // network failure
+    drop(base);
+    let unreachable = "http://127.0.0.1:1";
+
+    let output = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"))
+        .args(["wrapper", "update", "--version", "v0.0.1"])
+        .env("PI_WRAPPER_RELEASE_BASE", unreachable)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Cannot reach the GitHub releases API"),
+        "expected actionable network error, got: {stderr}"
+    );
+}
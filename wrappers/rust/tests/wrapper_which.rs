@@ -0,0 +1,115 @@
+//! Integration tests: `pi wrapper which` prints the path the resolver
+//! would execute, suitable for command substitution in shell scripts.
+
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn test_root(tag: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pi-wrapper-which-test-{}-{}",
+        tag,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn wrapper_command(root: &Path, project: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_package-installer-cli"));
+    command
+        .current_dir(project)
+        .env_remove("PI_CLI_PATH")
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_DATA_HOME", root.join("data"));
+    command
+}
+
+/// Local install + a development bundle in the same project.
+fn layered_project(root: &Path) -> (PathBuf, PathBuf, PathBuf) {
+    let project = root.join("project");
+    let entry = project
+        .join("node_modules")
+        .join("@0xshariq")
+        .join("package-installer")
+        .join("dist")
+        .join("index.js");
+    std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    std::fs::write(&entry, "console.log('LOCAL');\n").unwrap();
+
+    let dev_bundle = project.join("bundle-standalone").join("pi");
+    std::fs::create_dir_all(dev_bundle.parent().unwrap()).unwrap();
+    std::fs::write(&dev_bundle, "#!/bin/sh\necho DEV\n").unwrap();
+    std::fs::set_permissions(&dev_bundle, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    (project, entry, dev_bundle)
+}
+
+#[test]
+fn which_prints_the_winning_path_and_kind() {
+    let root = test_root("winning");
+    let (project, entry, dev_bundle) = layered_project(&root);
+
+    let plain = wrapper_command(&root, &project)
+        .args(["wrapper", "which"])
+        .output()
+        .unwrap();
+    assert!(plain.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&plain.stdout).trim(),
+        entry.display().to_string()
+    );
+
+    let with_kind = wrapper_command(&root, &project)
+        .args(["wrapper", "which", "--kind"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&with_kind.stdout).trim(),
+        format!("{} local-npm", entry.display())
+    );
+
+    let all = wrapper_command(&root, &project)
+        .args(["wrapper", "which", "--all", "--kind"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&all.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], format!("{} local-npm", entry.display()));
+    assert!(lines.contains(&format!("{} dev-bundle", dev_bundle.display()).as_str()));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn which_honors_the_env_override_and_fails_cleanly_when_unresolved() {
+    let root = test_root("override");
+    let project = root.join("empty");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+
+    let unresolved = wrapper_command(&root, &project)
+        .args(["wrapper", "which"])
+        .env("HOME", root.join("home").display().to_string())
+        .output()
+        .unwrap();
+    assert!(!unresolved.status.success());
+    assert!(unresolved.stdout.is_empty());
+
+    let custom = root.join("custom-pi");
+    std::fs::write(&custom, "#!/bin/sh\n").unwrap();
+    let overridden = wrapper_command(&root, &project)
+        .args(["wrapper", "which", "--kind"])
+        .env("PI_CLI_PATH", &custom)
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&overridden.stdout).trim(),
+        format!("{} env-override", custom.display())
+    );
+
+    std::fs::remove_dir_all(&root).ok();
+}